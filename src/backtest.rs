@@ -0,0 +1,364 @@
+//! Backtesting of option overlay strategies.
+//!
+//! An overlay holds one unit of the underlying and repeatedly sells a call
+//! against it (a covered call). Options are priced with Black-Scholes off a
+//! historical series of asset prices and volatilities, so strategies can be
+//! evaluated without historical option quotes.
+
+use crate::option::BSOption;
+use crate::utils::normal_inverse_cdf;
+use wasm_bindgen::prelude::*;
+
+const TIMESTAMP_ONE_DAY: u32 = 86_400;
+/// Used to annualize the volatility of daily returns.
+const TRADING_DAYS_PER_YEAR: f64 = 252.0;
+
+/// Specifies which calls an overlay sells and when it rolls them.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct OverlayRule {
+    target_delta: f64,
+    days_to_expiry: u32,
+    roll_days_to_expiry: u32,
+    interest: f64,
+    payout_rate: f64,
+}
+
+#[wasm_bindgen]
+impl OverlayRule {
+    /// Creates an `OverlayRule`.
+    ///
+    /// # Arguments
+    ///
+    /// - `target_delta`: the delta of the calls to sell, in decimal form
+    ///      (e.g. `0.3` for a 30-delta call).
+    ///
+    /// - `days_to_expiry`: the number of days to expiry of newly sold calls.
+    ///      Must be positive.
+    ///
+    /// - `roll_days_to_expiry`: calls are bought back and replaced once
+    ///      they have this many days to expiry left. Use `0` to hold calls
+    ///      until expiry.
+    ///
+    /// - `interest`: the interest rate used for pricing, in decimal form.
+    ///
+    /// - `payout_rate`: the payout rate used for pricing, in decimal form.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use opcalc::backtest::OverlayRule;
+    ///
+    /// // sell 30-delta monthly calls, and roll them at 21 days to expiry
+    /// let rule = OverlayRule::new(0.3, 30, 21, 0.005, 0.0);
+    /// ```
+    pub fn new(
+        target_delta: f64,
+        days_to_expiry: u32,
+        roll_days_to_expiry: u32,
+        interest: f64,
+        payout_rate: f64,
+    ) -> OverlayRule {
+        OverlayRule {
+            target_delta,
+            days_to_expiry,
+            roll_days_to_expiry,
+            interest,
+            payout_rate,
+        }
+    }
+}
+
+impl OverlayRule {
+    /// Whether a call with the rule's days to expiry can have the target
+    /// delta, i.e. `T > 0` and `0 < delta < e^(-qT)`. Calls expiring
+    /// immediately only have deltas of `0` or `1`.
+    fn has_valid_delta(&self) -> bool {
+        let t = f64::from(self.days_to_expiry) / 365.0;
+        let max_delta = (-self.payout_rate.ln_1p() * t).exp();
+
+        t > 0.0 && self.target_delta > 0.0 && self.target_delta < max_delta
+    }
+}
+
+/// The result of an overlay backtest.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct BacktestReport {
+    values: Vec<f64>,
+    premium_collected: f64,
+    rolls: u32,
+}
+
+#[wasm_bindgen]
+impl BacktestReport {
+    /// Get the value of the strategy (underlying plus cash, minus the short
+    /// call's mark) at each timestamp of the input series.
+    pub fn values(&self) -> Vec<f64> {
+        self.values.clone()
+    }
+
+    /// Get the total premium received from selling calls.
+    pub fn premium_collected(&self) -> f64 {
+        self.premium_collected
+    }
+
+    /// Get the number of calls that were bought back before expiry and
+    /// replaced.
+    pub fn rolls(&self) -> u32 {
+        self.rolls
+    }
+
+    /// Get the strategy's total return over the backtest, in decimal form.
+    pub fn total_return(&self) -> f64 {
+        self.values[self.values.len() - 1] / self.values[0] - 1.0
+    }
+
+    /// Get the annualized volatility of the strategy's daily returns,
+    /// assuming 252 trading days per year.
+    pub fn annualized_volatility(&self) -> f64 {
+        let returns: Vec<f64> = self
+            .values
+            .windows(2)
+            .map(|pair| pair[1] / pair[0] - 1.0)
+            .collect();
+        if returns.len() < 2 {
+            return 0.0;
+        }
+
+        let mean = returns.iter().sum::<f64>() / returns.len() as f64;
+        let variance =
+            returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (returns.len() - 1) as f64;
+
+        (variance * TRADING_DAYS_PER_YEAR).sqrt()
+    }
+
+    /// Get the largest peak-to-trough decline of the strategy's value, as a
+    /// fraction of the peak.
+    pub fn max_drawdown(&self) -> f64 {
+        let mut peak = f64::MIN;
+
+        self.values.iter().fold(0.0, |max_drawdown: f64, &value| {
+            peak = peak.max(value);
+            max_drawdown.max((peak - value) / peak)
+        })
+    }
+}
+
+/// Backtest a covered call overlay over a historical price series.
+///
+/// Returns `None` if the series have different lengths, contain fewer than
+/// two entries, or if the timestamps are not in ascending order. Also
+/// returns `None` if no call has the rule's target delta, i.e. unless the
+/// days to expiry are positive and it is between `0` and `e^(-qT)`, or if
+/// the expiries of the calls do not fit in a `u32` timestamp.
+///
+/// # Arguments
+///
+/// - `times`: timestamps, in seconds, of the observations (typically daily
+///      closes).
+///
+/// - `asset_prices`: the underlying's price at each timestamp.
+///
+/// - `volatilities`: the implied volatility used to price the calls at each
+///      timestamp.
+///
+/// - `rule`: the overlay's rule for selling and rolling calls.
+#[wasm_bindgen]
+pub fn backtest_covered_call(
+    times: &[u32],
+    asset_prices: &[f64],
+    volatilities: &[f64],
+    rule: &OverlayRule,
+) -> Option<BacktestReport> {
+    crate::utils::set_panic_hook();
+    trace_scope!("backtest::backtest_covered_call");
+
+    let n = times.len();
+    if n < 2 || asset_prices.len() != n || volatilities.len() != n {
+        return None;
+    }
+    if times.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return None;
+    }
+    if !rule.has_valid_delta() {
+        return None;
+    }
+    let roll_window = rule.roll_days_to_expiry.checked_mul(TIMESTAMP_ONE_DAY)?;
+    // the expiries of all calls sold must fit in a timestamp
+    let _latest_expiry = rule
+        .days_to_expiry
+        .checked_mul(TIMESTAMP_ONE_DAY)
+        .and_then(|expiry| times[n - 1].checked_add(expiry))?;
+
+    let mut cash = 0.0;
+    let mut premium_collected = 0.0;
+    let mut rolls = 0;
+    let mut short_call: Option<BSOption> = None;
+    let mut values = Vec::with_capacity(n);
+
+    for i in 0..n {
+        let (time, asset_price, volatility) = (times[i], asset_prices[i], volatilities[i]);
+
+        // settle or roll the call sold earlier
+        if let Some(call) = short_call {
+            if time >= call.time_maturity() {
+                cash -= (asset_price - call.strike()).max(0.0);
+                short_call = None;
+            } else if call.time_maturity() - time <= roll_window {
+                cash -= call
+                    .value_at(time, Some(asset_price), Some(volatility))
+                    .call_value();
+                rolls += 1;
+                short_call = None;
+            }
+        }
+
+        // sell a new call, unless this is the last observation
+        if short_call.is_none() && i < n - 1 {
+            let call = sell_call(time, asset_price, volatility, rule);
+            premium_collected += call.call_value();
+            cash += call.call_value();
+            short_call = Some(call);
+        }
+
+        let short_call_value = short_call.map_or(0.0, |call| {
+            call.value_at(time, Some(asset_price), Some(volatility))
+                .call_value()
+        });
+        values.push(asset_price + cash - short_call_value);
+    }
+
+    Some(BacktestReport {
+        values,
+        premium_collected,
+        rolls,
+    })
+}
+
+/// Creates a call with the rule's days to expiry, struck such that its delta
+/// matches the rule's target delta.
+fn sell_call(time: u32, asset_price: f64, volatility: f64, rule: &OverlayRule) -> BSOption {
+    let time_maturity = time + rule.days_to_expiry * TIMESTAMP_ONE_DAY;
+    let mut call = BSOption::new(
+        time,
+        time_maturity,
+        asset_price,
+        asset_price,
+        rule.interest,
+        volatility,
+        rule.payout_rate,
+    );
+
+    // invert delta = e^(-qT) * N(d1) for the strike
+    let t = call.time_to_maturity();
+    let (r, q) = (rule.interest.ln_1p(), rule.payout_rate.ln_1p());
+    let d1 = normal_inverse_cdf(rule.target_delta * (q * t).exp());
+    let strike =
+        asset_price * (-d1 * volatility * t.sqrt() + (r - q + volatility.powi(2) / 2.0) * t).exp();
+
+    call.set_strike(strike);
+    call
+}
+
+#[cfg(test)]
+mod backtest_tests {
+    use crate::backtest::{backtest_covered_call, sell_call, OverlayRule};
+
+    const TIME_START: u32 = 1606780800; // 2020/12/01 00:00:00
+
+    #[test]
+    fn sells_calls_at_the_target_delta() {
+        let rule = OverlayRule::new(0.3, 30, 21, 0.005, 0.01);
+        let call = sell_call(TIME_START, 100.0, 0.25, &rule);
+
+        approx::assert_abs_diff_eq!(call.call_delta(), 0.3, epsilon = 1e-10);
+        assert!(call.strike() > 100.0);
+    }
+
+    #[test]
+    fn collects_premium_in_flat_markets() {
+        let times: Vec<u32> = (0..90).map(|day| TIME_START + day * 86_400).collect();
+        let prices = vec![100.0; 90];
+        let volatilities = vec![0.2; 90];
+        let rule = OverlayRule::new(0.3, 30, 21, 0.005, 0.0);
+
+        let report = backtest_covered_call(&times, &prices, &volatilities, &rule).unwrap();
+
+        // calls are sold on day 0 and rolled every 9 days
+        assert_eq!(report.rolls(), 9);
+        assert_eq!(report.values().len(), 90);
+        approx::assert_abs_diff_eq!(report.values()[0], 100.0, epsilon = 1e-12);
+        assert!(report.premium_collected() > 0.0);
+        assert!(report.total_return() > 0.0);
+        approx::assert_abs_diff_eq!(report.max_drawdown(), 0.0);
+    }
+
+    #[test]
+    fn caps_gains_in_rallies() {
+        let times: Vec<u32> = (0..31).map(|day| TIME_START + day * 86_400).collect();
+        let prices: Vec<f64> = (0..31).map(|day| 100.0 + day as f64).collect();
+        let volatilities = vec![0.2; 31];
+        let rule = OverlayRule::new(0.3, 30, 0, 0.005, 0.0);
+
+        let report = backtest_covered_call(&times, &prices, &volatilities, &rule).unwrap();
+
+        assert_eq!(report.rolls(), 0);
+        assert!(report.total_return() < 0.3);
+    }
+
+    #[test]
+    fn rejects_malformed_series() {
+        let rule = OverlayRule::new(0.3, 30, 21, 0.005, 0.0);
+
+        assert!(backtest_covered_call(&[TIME_START], &[100.0], &[0.2], &rule).is_none());
+        assert!(backtest_covered_call(
+            &[TIME_START, TIME_START],
+            &[100.0, 100.0],
+            &[0.2, 0.2],
+            &rule
+        )
+        .is_none());
+    }
+
+    #[test]
+    fn rejects_unattainable_target_deltas() {
+        let times: Vec<u32> = (0..10).map(|day| TIME_START + day * 86_400).collect();
+        let prices = vec![100.0; 10];
+        let volatilities = vec![0.2; 10];
+
+        for &target_delta in [0.0, -0.3, 1.0, 0.999].iter() {
+            let rule = OverlayRule::new(target_delta, 365, 0, 0.005, 0.05);
+            assert!(backtest_covered_call(&times, &prices, &volatilities, &rule).is_none());
+        }
+
+        // calls expiring immediately have no delta between 0 and 1
+        let rule = OverlayRule::new(0.3, 0, 0, 0.005, 0.0);
+        assert!(backtest_covered_call(&times, &prices, &volatilities, &rule).is_none());
+    }
+
+    #[test]
+    fn rejects_expiries_past_the_largest_timestamp() {
+        let times = [u32::MAX - 86_400, u32::MAX - 10];
+        let rule = OverlayRule::new(0.3, 30, 0, 0.005, 0.0);
+        assert!(backtest_covered_call(&times, &[100.0, 100.0], &[0.2, 0.2], &rule).is_none());
+
+        let rule = OverlayRule::new(0.3, 100_000, 0, 0.005, 0.0);
+        assert!(backtest_covered_call(
+            &[TIME_START, TIME_START + 86_400],
+            &[100.0, 100.0],
+            &[0.2, 0.2],
+            &rule
+        )
+        .is_none());
+
+        let rule = OverlayRule::new(0.3, 30, 100_000, 0.005, 0.0);
+        assert!(backtest_covered_call(
+            &[TIME_START, TIME_START + 86_400],
+            &[100.0, 100.0],
+            &[0.2, 0.2],
+            &rule
+        )
+        .is_none());
+    }
+}
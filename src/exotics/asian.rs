@@ -0,0 +1,291 @@
+//! Average-price (Asian) options.
+//!
+//! An Asian option pays off on the average of the asset price over a
+//! schedule of fixing times, rather than on the price at maturity. Averaging
+//! dampens the volatility of the underlying, so Asian options are cheaper
+//! than their vanilla counterparts.
+//!
+//! The log of a geometric average of lognormal prices is normally
+//! distributed, so geometric-average options have an exact closed form.
+//! Arithmetic-average options do not, and are priced by Monte Carlo
+//! simulation, with the geometric-average option as a control variate: the
+//! two averages are almost perfectly correlated, which removes nearly all of
+//! the simulation noise.
+
+use crate::montecarlo::{
+    control_coefficient, draw_normals, unshifted, MonteCarloEstimate, PathModel,
+};
+use crate::option::{BSOption, OptionType};
+use crate::utils::normal_cdf;
+use wasm_bindgen::prelude::*;
+
+const SECONDS_PER_YEAR: f64 = 31_536_000.0;
+
+/// An Asian option on the asset, strike and maturity of a `BSOption`.
+///
+/// Like their geometric closed form, Asian options ignore the option's shift.
+///
+/// ```rust
+/// use opcalc::exotics::asian::AsianOption;
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+///
+/// // daily fixings
+/// let asian = AsianOption::evenly_spaced(&option, 45);
+/// let call = asian.arithmetic_call_value();
+/// assert!(call.value() < option.call_value());
+/// ```
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct AsianOption {
+    option: BSOption,
+    fixing_times: Vec<u32>,
+    paths: usize,
+    seed: u64,
+}
+
+#[wasm_bindgen]
+impl AsianOption {
+    /// Create an `AsianOption` averaging the asset price at `fixing_times`,
+    /// as Unix timestamps in seconds. The fixing times must lie after the
+    /// option's current time, and no later than its maturity.
+    #[wasm_bindgen(constructor)]
+    pub fn new(option: &BSOption, fixing_times: Vec<u32>) -> AsianOption {
+        AsianOption {
+            option: *option,
+            fixing_times,
+            paths: 10_000,
+            seed: 1,
+        }
+    }
+
+    /// Create an `AsianOption` averaging the asset price at `fixings` evenly
+    /// spaced times, the last of which is the option's maturity.
+    pub fn evenly_spaced(option: &BSOption, fixings: usize) -> AsianOption {
+        let fixings = fixings.max(1);
+        let (start, end) = (option.time_curr() as u64, option.time_maturity() as u64);
+        let fixing_times = (1..=fixings as u64)
+            .map(|i| (start + (end - start) * i / fixings as u64) as u32)
+            .collect();
+
+        AsianOption::new(option, fixing_times)
+    }
+
+    /// Set the number of paths simulated for arithmetic-average options.
+    /// Must be at least two.
+    pub fn with_paths(self, paths: usize) -> AsianOption {
+        AsianOption {
+            paths: paths.max(2),
+            ..self
+        }
+    }
+
+    /// Set the seed of the random number generator.
+    pub fn with_seed(self, seed: u64) -> AsianOption {
+        AsianOption { seed, ..self }
+    }
+
+    /// Get the fixing times, as Unix timestamps in seconds.
+    pub fn fixing_times(&self) -> Vec<u32> {
+        self.fixing_times.clone()
+    }
+
+    /// Get the value of the call on the geometric average.
+    pub fn geometric_call_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("AsianOption::geometric_call_value");
+
+        self.geometric_value(OptionType::Call)
+    }
+
+    /// Get the value of the put on the geometric average.
+    pub fn geometric_put_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("AsianOption::geometric_put_value");
+
+        self.geometric_value(OptionType::Put)
+    }
+
+    /// Get the value of the call on the arithmetic average.
+    pub fn arithmetic_call_value(&self) -> MonteCarloEstimate {
+        crate::utils::set_panic_hook();
+        trace_scope!("AsianOption::arithmetic_call_value");
+
+        self.arithmetic_value(OptionType::Call)
+    }
+
+    /// Get the value of the put on the arithmetic average.
+    pub fn arithmetic_put_value(&self) -> MonteCarloEstimate {
+        crate::utils::set_panic_hook();
+        trace_scope!("AsianOption::arithmetic_put_value");
+
+        self.arithmetic_value(OptionType::Put)
+    }
+}
+
+impl AsianOption {
+    /// Get the fixing times in years from now.
+    fn fixing_years(&self) -> Vec<f64> {
+        let time_curr = self.option.time_curr() as f64;
+
+        self.fixing_times
+            .iter()
+            .map(|&time| (time as f64 - time_curr) / SECONDS_PER_YEAR)
+            .collect()
+    }
+
+    fn payoff(&self, average: f64, option_type: OptionType) -> f64 {
+        match option_type {
+            OptionType::Call => (average - self.option.strike()).max(0.0),
+            OptionType::Put => (self.option.strike() - average).max(0.0),
+        }
+    }
+
+    fn geometric_value(&self, option_type: OptionType) -> f64 {
+        let times = self.fixing_years();
+        let fixings = times.len() as f64;
+        let rate = self.option.interest().ln_1p();
+        let carry = rate - self.option.payout_rate().ln_1p();
+        let variance_rate = self.option.volatility().powi(2);
+
+        // ln(G) is normal with this mean and variance
+        let mean = self.option.asset_price().ln()
+            + (carry - variance_rate / 2.0) * times.iter().sum::<f64>() / fixings;
+        let covariances: f64 = times
+            .iter()
+            .map(|s| times.iter().map(|t| s.min(*t)).sum::<f64>())
+            .sum();
+        let variance = variance_rate * covariances / fixings.powi(2);
+
+        let strike = self.option.strike();
+        let d2 = (mean - strike.ln()) / variance.sqrt();
+        let d1 = d2 + variance.sqrt();
+        let forward = (mean + variance / 2.0).exp();
+        let discount_factor = (-rate * self.option.time_to_maturity()).exp();
+
+        discount_factor
+            * match option_type {
+                OptionType::Call => forward * normal_cdf(d1) - strike * normal_cdf(d2),
+                OptionType::Put => strike * normal_cdf(-d2) - forward * normal_cdf(-d1),
+            }
+    }
+
+    fn arithmetic_value(&self, option_type: OptionType) -> MonteCarloEstimate {
+        let times = self.fixing_years();
+        let fixings = times.len();
+        let discount_factor = (-self.option.discount_rate() * self.option.horizon()).exp();
+
+        let model = unshifted(&self.option);
+        let mut payoffs = Vec::with_capacity(self.paths);
+        let mut controls = Vec::with_capacity(self.paths);
+        for normals in draw_normals(self.paths, fixings, 1, self.seed, false) {
+            let (mut price, mut time) = (model.initial_state(), 0.0);
+            let (mut sum, mut log_sum) = (0.0, 0.0);
+            for (&fixing, step) in times.iter().zip(&normals) {
+                price = model.step(&price, fixing - time, step);
+                time = fixing;
+                sum += price;
+                log_sum += price.ln();
+            }
+
+            let arithmetic = sum / fixings as f64;
+            let geometric = (log_sum / fixings as f64).exp();
+            payoffs.push(discount_factor * self.payoff(arithmetic, option_type));
+            controls.push(discount_factor * self.payoff(geometric, option_type));
+        }
+
+        let coefficient = control_coefficient(&payoffs, &controls);
+        let geometric_value = self.geometric_value(option_type);
+        let adjusted: Vec<f64> = payoffs
+            .iter()
+            .zip(&controls)
+            .map(|(payoff, control)| payoff - coefficient * (control - geometric_value))
+            .collect();
+
+        MonteCarloEstimate::from_samples(&adjusted)
+    }
+}
+
+#[cfg(test)]
+mod asian_tests {
+    use crate::exotics::asian::AsianOption;
+    use crate::option::BSOption;
+    use crate::utils::normal_cdf;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.01)
+    }
+
+    #[test]
+    fn values_a_single_fixing_like_a_european_option() {
+        let option = create_test_option();
+        let asian = AsianOption::new(&option, vec![1610668800]);
+
+        approx::assert_abs_diff_eq!(
+            asian.geometric_call_value(),
+            option.call_value(),
+            epsilon = 1e-10
+        );
+        approx::assert_abs_diff_eq!(
+            asian.geometric_put_value(),
+            option.put_value(),
+            epsilon = 1e-10
+        );
+
+        // the control variate is the payoff itself
+        let arithmetic = asian.arithmetic_put_value();
+        approx::assert_abs_diff_eq!(arithmetic.value(), option.put_value(), epsilon = 1e-10);
+    }
+
+    #[test]
+    fn values_arithmetic_averages_above_geometric_ones() {
+        let option = create_test_option();
+        let asian = AsianOption::evenly_spaced(&option, 45);
+        assert_eq!(asian.fixing_times().len(), 45);
+        assert_eq!(asian.fixing_times()[44], 1610668800);
+
+        let geometric = asian.geometric_call_value();
+        let arithmetic = asian.arithmetic_call_value();
+        assert!(geometric < option.call_value());
+        assert!(arithmetic.value() > geometric);
+        assert!(arithmetic.standard_error() < 1e-3);
+
+        // the put is cheaper on the higher arithmetic average
+        assert!(asian.arithmetic_put_value().value() < asian.geometric_put_value());
+    }
+
+    #[test]
+    fn converges_to_continuous_averaging() {
+        // Kemna and Vorst (1990): a continuously averaged geometric option is
+        // a European option with a third of the variance and adjusted carry
+        let option = create_test_option();
+        let asian = AsianOption::evenly_spaced(&option, 5_000);
+
+        let time = option.time_to_maturity();
+        let rate = 0.005f64.ln_1p();
+        let volatility = 0.23 / 3.0f64.sqrt();
+        let carry = (rate - 0.01f64.ln_1p() - 0.23f64.powi(2) / 6.0) / 2.0;
+        let d1 = ((100.0f64 / 105.0).ln() + (carry + volatility.powi(2) / 2.0) * time)
+            / (volatility * time.sqrt());
+        let d2 = d1 - volatility * time.sqrt();
+        let call = 100.0 * ((carry - rate) * time).exp() * normal_cdf(d1)
+            - 105.0 * (-rate * time).exp() * normal_cdf(d2);
+
+        approx::assert_abs_diff_eq!(asian.geometric_call_value(), call, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn agrees_across_seeds() {
+        let option = create_test_option();
+        let asian = AsianOption::evenly_spaced(&option, 12);
+        let first = asian.arithmetic_put_value();
+        let second = asian.clone().with_seed(2).arithmetic_put_value();
+
+        approx::assert_abs_diff_eq!(
+            first.value(),
+            second.value(),
+            epsilon = 3.0 * (first.standard_error() + second.standard_error())
+        );
+    }
+}
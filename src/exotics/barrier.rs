@@ -0,0 +1,546 @@
+//! Single-barrier options.
+//!
+//! A knock-out option is a vanilla option that ceases to exist once the
+//! asset price touches the barrier, and a knock-in option only comes into
+//! existence once it does. The barrier is monitored continuously until
+//! maturity.
+//!
+//! Values follow the closed-form formulas of Reiner & Rubinstein (1991),
+//! "Breaking down the barriers", in the notation of Haug (2007), "The
+//! Complete Guide to Option Pricing Formulas". The formulas are evaluated on
+//! dual numbers, so greeks are exact rather than bumped.
+//!
+//! Options can carry a rebate: a knock-out option pays it when knocked out,
+//! either when the barrier is hit or at maturity, and a knock-in option pays
+//! it at maturity if it was never knocked in.
+
+use crate::option::dual::{Dual64, HyperDual64, Scalar};
+use crate::option::opcalc::op_calc::PricingInputs;
+use crate::option::{BSOption, OptionType};
+use wasm_bindgen::prelude::*;
+
+/// An enumeration of the supported barrier types.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BarrierKind {
+    /// Knocks in when the asset price falls to the barrier.
+    DownAndIn,
+    /// Knocks out when the asset price falls to the barrier.
+    DownAndOut,
+    /// Knocks in when the asset price rises to the barrier.
+    UpAndIn,
+    /// Knocks out when the asset price rises to the barrier.
+    UpAndOut,
+}
+
+impl BarrierKind {
+    fn is_down(self) -> bool {
+        self == BarrierKind::DownAndIn || self == BarrierKind::DownAndOut
+    }
+
+    fn is_in(self) -> bool {
+        self == BarrierKind::DownAndIn || self == BarrierKind::UpAndIn
+    }
+}
+
+/// An enumeration of when a knock-out option's rebate is paid.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum RebateTiming {
+    /// The rebate is paid as soon as the barrier is hit.
+    AtHit,
+    /// The rebate is paid at maturity.
+    AtExpiry,
+}
+
+/// A barrier option on the asset, strike and maturity of a `BSOption`.
+///
+/// Greeks follow the option's `GreekConventions`. If the option has a shift,
+/// it applies to the barrier as well.
+///
+/// ```rust
+/// use opcalc::exotics::barrier::{BarrierKind, BarrierOption};
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+///
+/// let knock_out = BarrierOption::new(&option, BarrierKind::DownAndOut, 90.0);
+/// assert!(knock_out.call_value() < option.call_value());
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct BarrierOption {
+    option: BSOption,
+    kind: BarrierKind,
+    barrier: f64,
+    rebate: f64,
+    rebate_timing: RebateTiming,
+}
+
+#[wasm_bindgen]
+impl BarrierOption {
+    /// Create a `BarrierOption` of `kind`, with its barrier at `barrier` and
+    /// no rebate.
+    #[wasm_bindgen(constructor)]
+    pub fn new(option: &BSOption, kind: BarrierKind, barrier: f64) -> BarrierOption {
+        BarrierOption {
+            option: *option,
+            kind,
+            barrier,
+            rebate: 0.0,
+            rebate_timing: RebateTiming::AtHit,
+        }
+    }
+
+    /// Set the rebate, and when it is paid if the option is knocked out.
+    /// Knock-in options always pay their rebate at maturity.
+    pub fn with_rebate(self, rebate: f64, rebate_timing: RebateTiming) -> BarrierOption {
+        BarrierOption {
+            rebate,
+            rebate_timing,
+            ..self
+        }
+    }
+
+    /// Get the barrier type.
+    pub fn kind(&self) -> BarrierKind {
+        self.kind
+    }
+
+    /// Get the barrier level.
+    pub fn barrier(&self) -> f64 {
+        self.barrier
+    }
+
+    /// Get the rebate.
+    pub fn rebate(&self) -> f64 {
+        self.rebate
+    }
+
+    /// Get when a knock-out option's rebate is paid.
+    pub fn rebate_timing(&self) -> RebateTiming {
+        self.rebate_timing
+    }
+
+    /// Check whether the asset price is already at or beyond the barrier,
+    /// i.e. whether a knock-in option has become a vanilla option and a
+    /// knock-out option has become worthless.
+    pub fn is_breached(&self) -> bool {
+        if self.kind.is_down() {
+            self.option.asset_price() <= self.barrier
+        } else {
+            self.option.asset_price() >= self.barrier
+        }
+    }
+
+    /// Get the call's value.
+    pub fn call_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("BarrierOption::call_value");
+
+        self.value(&PricingInputs::new(&self.option), OptionType::Call)
+    }
+
+    /// Get the put's value.
+    pub fn put_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("BarrierOption::put_value");
+
+        self.value(&PricingInputs::new(&self.option), OptionType::Put)
+    }
+
+    /// Get the call's delta.
+    pub fn call_delta(&self) -> f64 {
+        self.first_derivative(OptionType::Call, |inputs| &mut inputs.asset_price)
+    }
+
+    /// Get the put's delta.
+    pub fn put_delta(&self) -> f64 {
+        self.first_derivative(OptionType::Put, |inputs| &mut inputs.asset_price)
+    }
+
+    /// Get the call's gamma.
+    pub fn call_gamma(&self) -> f64 {
+        self.gamma(OptionType::Call)
+    }
+
+    /// Get the put's gamma.
+    pub fn put_gamma(&self) -> f64 {
+        self.gamma(OptionType::Put)
+    }
+
+    /// Get the call's vega.
+    pub fn call_vega(&self) -> f64 {
+        let unit = self.option.greek_conventions().volatility_unit();
+        self.first_derivative(OptionType::Call, |inputs| &mut inputs.volatility) * unit
+    }
+
+    /// Get the put's vega.
+    pub fn put_vega(&self) -> f64 {
+        let unit = self.option.greek_conventions().volatility_unit();
+        self.first_derivative(OptionType::Put, |inputs| &mut inputs.volatility) * unit
+    }
+}
+
+impl BarrierOption {
+    fn first_derivative<S>(&self, option_type: OptionType, select: S) -> f64
+    where
+        S: Fn(&mut PricingInputs<Dual64>) -> &mut Dual64,
+    {
+        let mut inputs = PricingInputs::new(&self.option);
+        let input = select(&mut inputs);
+        *input = Dual64::variable(input.re());
+
+        self.value(&inputs, option_type).eps()
+    }
+
+    fn gamma(&self, option_type: OptionType) -> f64 {
+        let mut inputs = PricingInputs::<HyperDual64>::new(&self.option);
+        inputs.asset_price = HyperDual64::new(inputs.asset_price.re(), 1.0, 1.0, 0.0);
+        let gamma = self.value(&inputs, option_type).eps1eps2();
+
+        self.option
+            .greek_conventions()
+            .scale_gamma(gamma, self.option.asset_price())
+    }
+
+    /// Value the option on `inputs`, in Haug's notation.
+    fn value<T: Scalar>(&self, inputs: &PricingInputs<T>, option_type: OptionType) -> T {
+        let zero = T::constant(0.0);
+        let one = T::constant(1.0);
+
+        if self.is_breached() {
+            if !self.kind.is_in() {
+                return self.rebate_value(inputs);
+            }
+
+            let (call, put) = inputs.values();
+            return match option_type {
+                OptionType::Call => call,
+                OptionType::Put => put,
+            };
+        }
+
+        let phi = match option_type {
+            OptionType::Call => 1.0,
+            OptionType::Put => -1.0,
+        };
+        let eta = if self.kind.is_down() { 1.0 } else { -1.0 };
+
+        let PricingInputs {
+            asset_price,
+            strike,
+            interest,
+            payout_rate,
+            time_to_maturity,
+            ..
+        } = *inputs;
+        let reflection = self.reflection(inputs);
+        let (barrier, mu, vol_sqrt_time) =
+            (reflection.barrier, reflection.mu, reflection.vol_sqrt_time);
+
+        let carried_asset_price = asset_price * (-payout_rate * time_to_maturity).exp();
+        let discounted_strike = strike * (-interest * time_to_maturity).exp();
+        let term = |x: T, sign: f64, asset_weight: T, strike_weight: T| {
+            let (phi, sign) = (T::constant(phi), T::constant(sign));
+            phi * carried_asset_price * asset_weight * (sign * x).normal_cdf()
+                - phi
+                    * discounted_strike
+                    * strike_weight
+                    * (sign * (x - vol_sqrt_time)).normal_cdf()
+        };
+
+        let x1 = reflection.standardized(asset_price / strike);
+        let x2 = reflection.standardized(asset_price / barrier);
+        let y1 = reflection.standardized(barrier * barrier / (asset_price * strike));
+        let y2 = reflection.standardized(barrier / asset_price);
+        let reflected_asset = reflection.power(T::constant(2.0) * (mu + one));
+        let reflected_strike = reflection.power(T::constant(2.0) * mu);
+
+        let a = term(x1, phi, one, one);
+        let b = term(x2, phi, one, one);
+        let c = term(y1, eta, reflected_asset, reflected_strike);
+        let d = term(y2, eta, reflected_asset, reflected_strike);
+
+        let strike_above_barrier = self.option.strike() > self.barrier;
+        let option_value = match (self.kind, option_type, strike_above_barrier) {
+            (BarrierKind::DownAndIn, OptionType::Call, true) => c,
+            (BarrierKind::DownAndIn, OptionType::Call, false) => a - b + d,
+            (BarrierKind::UpAndIn, OptionType::Call, true) => a,
+            (BarrierKind::UpAndIn, OptionType::Call, false) => b - c + d,
+            (BarrierKind::DownAndIn, OptionType::Put, true) => b - c + d,
+            (BarrierKind::DownAndIn, OptionType::Put, false) => a,
+            (BarrierKind::UpAndIn, OptionType::Put, true) => a - b + d,
+            (BarrierKind::UpAndIn, OptionType::Put, false) => c,
+            (BarrierKind::DownAndOut, OptionType::Call, true) => a - c,
+            (BarrierKind::DownAndOut, OptionType::Call, false) => b - d,
+            (BarrierKind::UpAndOut, OptionType::Call, true) => zero,
+            (BarrierKind::UpAndOut, OptionType::Call, false) => a - b + c - d,
+            (BarrierKind::DownAndOut, OptionType::Put, true) => a - b + c - d,
+            (BarrierKind::DownAndOut, OptionType::Put, false) => zero,
+            (BarrierKind::UpAndOut, OptionType::Put, true) => b - d,
+            (BarrierKind::UpAndOut, OptionType::Put, false) => a - c,
+        };
+
+        option_value + self.rebate_value(inputs)
+    }
+
+    /// Value the rebate on `inputs`: for knock-in options the rebate paid
+    /// at maturity if the barrier is never hit (Haug's E), and for knock-out
+    /// options the rebate paid when it is hit (F) or at maturity after it
+    /// was hit.
+    pub(crate) fn rebate_value<T: Scalar>(&self, inputs: &PricingInputs<T>) -> T {
+        let rebate = T::constant(self.rebate);
+        let discount_factor = (-inputs.interest * inputs.time_to_maturity).exp();
+
+        if self.is_breached() {
+            return match (self.kind.is_in(), self.rebate_timing) {
+                (true, _) => T::constant(0.0),
+                (false, RebateTiming::AtHit) => rebate,
+                (false, RebateTiming::AtExpiry) => rebate * discount_factor,
+            };
+        }
+
+        let eta = T::constant(if self.kind.is_down() { 1.0 } else { -1.0 });
+        let reflection = self.reflection(inputs);
+        let (barrier, asset_price) = (reflection.barrier, inputs.asset_price);
+        let (mu, vol_sqrt_time) = (reflection.mu, reflection.vol_sqrt_time);
+
+        let x2 = reflection.standardized(asset_price / barrier);
+        let y2 = reflection.standardized(barrier / asset_price);
+        let survival = rebate
+            * discount_factor
+            * ((eta * (x2 - vol_sqrt_time)).normal_cdf()
+                - reflection.power(T::constant(2.0) * mu)
+                    * (eta * (y2 - vol_sqrt_time)).normal_cdf());
+
+        if self.kind.is_in() {
+            return survival;
+        }
+
+        match self.rebate_timing {
+            RebateTiming::AtHit => {
+                let variance = inputs.volatility * inputs.volatility;
+                let lambda = (mu * mu + T::constant(2.0) * inputs.interest / variance).sqrt();
+                let z = (barrier / asset_price).ln() / vol_sqrt_time + lambda * vol_sqrt_time;
+                let reflected_z = z - T::constant(2.0) * lambda * vol_sqrt_time;
+
+                rebate
+                    * (reflection.power(mu + lambda) * (eta * z).normal_cdf()
+                        + reflection.power(mu - lambda) * (eta * reflected_z).normal_cdf())
+            }
+            RebateTiming::AtExpiry => rebate * discount_factor - survival,
+        }
+    }
+
+    fn reflection<T: Scalar>(&self, inputs: &PricingInputs<T>) -> Reflection<T> {
+        let variance = inputs.volatility * inputs.volatility;
+
+        Reflection {
+            asset_price: inputs.asset_price,
+            barrier: T::constant(self.barrier + self.option.shift()),
+            mu: (inputs.interest - inputs.payout_rate - variance / T::constant(2.0)) / variance,
+            vol_sqrt_time: inputs.volatility * inputs.time_to_maturity.sqrt(),
+        }
+    }
+}
+
+/// The quantities shared by the terms of the barrier formulas.
+struct Reflection<T> {
+    asset_price: T,
+    barrier: T,
+    /// `(r - q - vol^2 / 2) / vol^2`
+    mu: T,
+    vol_sqrt_time: T,
+}
+
+impl<T: Scalar> Reflection<T> {
+    /// Get `(H / S)^exponent`.
+    fn power(&self, exponent: T) -> T {
+        (exponent * (self.barrier / self.asset_price).ln()).exp()
+    }
+
+    /// Get `ln(ratio) / (vol * sqrt(T)) + (1 + mu) * vol * sqrt(T)`.
+    fn standardized(&self, ratio: T) -> T {
+        ratio.ln() / self.vol_sqrt_time + (T::constant(1.0) + self.mu) * self.vol_sqrt_time
+    }
+}
+
+#[cfg(test)]
+mod barrier_tests {
+    use crate::exotics::barrier::{BarrierKind, BarrierOption, RebateTiming};
+    use crate::option::BSOption;
+
+    /// Haug's barrier test case: r = 8%, q = 4%, vol = 25%, 6 months.
+    fn create_test_option(strike: f64) -> BSOption {
+        let time_maturity = 1610668800;
+        BSOption::new(
+            time_maturity - 15_768_000,
+            time_maturity,
+            100.0,
+            strike,
+            0.08f64.exp() - 1.0,
+            0.25,
+            0.04f64.exp() - 1.0,
+        )
+    }
+
+    #[test]
+    fn values_distant_barriers_like_vanilla_options() {
+        let option = create_test_option(100.0);
+        let down_and_out = BarrierOption::new(&option, BarrierKind::DownAndOut, 1.0);
+        let up_and_out = BarrierOption::new(&option, BarrierKind::UpAndOut, 10_000.0);
+
+        approx::assert_abs_diff_eq!(
+            down_and_out.call_value(),
+            option.call_value(),
+            epsilon = 1e-10
+        );
+        approx::assert_abs_diff_eq!(up_and_out.put_value(), option.put_value(), epsilon = 1e-10);
+        assert!(BarrierOption::new(&option, BarrierKind::DownAndIn, 1.0).call_value() < 1e-10);
+    }
+
+    #[test]
+    fn matches_reference_values_with_rebates() {
+        // Haug (2007), table 4-13, with a rebate of 3
+        let cases = [
+            (
+                BarrierKind::DownAndOut,
+                95.0,
+                [9.0246, 6.7924, 4.8759],
+                [2.2798, 2.2947, 2.6252],
+            ),
+            (
+                BarrierKind::UpAndOut,
+                105.0,
+                [2.6789, 2.3580, 2.3453],
+                [3.7760, 5.4932, 7.5187],
+            ),
+            (
+                BarrierKind::DownAndIn,
+                95.0,
+                [7.7627, 4.0109, 2.0576],
+                [2.9586, 6.5677, 11.9752],
+            ),
+            (
+                BarrierKind::UpAndIn,
+                105.0,
+                [14.1112, 8.4482, 4.5910],
+                [1.4653, 3.3721, 7.0846],
+            ),
+        ];
+
+        for &(kind, barrier, calls, puts) in &cases {
+            for (i, &strike) in [90.0, 100.0, 110.0].iter().enumerate() {
+                let option = BarrierOption::new(&create_test_option(strike), kind, barrier)
+                    .with_rebate(3.0, RebateTiming::AtHit);
+
+                approx::assert_abs_diff_eq!(option.call_value(), calls[i], epsilon = 1e-4);
+                approx::assert_abs_diff_eq!(option.put_value(), puts[i], epsilon = 1e-4);
+            }
+        }
+    }
+
+    #[test]
+    fn pays_rebates_at_expiry_later() {
+        let option = create_test_option(100.0);
+        let at_hit = BarrierOption::new(&option, BarrierKind::DownAndOut, 95.0)
+            .with_rebate(3.0, RebateTiming::AtHit);
+        let at_expiry = at_hit.with_rebate(3.0, RebateTiming::AtExpiry);
+        let without = at_hit.with_rebate(0.0, RebateTiming::AtHit);
+
+        assert!(at_expiry.call_value() > without.call_value());
+        assert!(at_expiry.call_value() < at_hit.call_value());
+
+        // a knock-out rebate paid at expiry and a knock-in rebate add up to
+        // a bond paying the rebate
+        let knock_in = BarrierOption::new(&option, BarrierKind::DownAndIn, 95.0)
+            .with_rebate(3.0, RebateTiming::AtExpiry);
+        let bond = 3.0 * (-0.08 * option.time_to_maturity()).exp();
+        approx::assert_abs_diff_eq!(
+            knock_in.call_value() + at_expiry.call_value(),
+            option.call_value() + bond,
+            epsilon = 1e-10
+        );
+
+        let mut breached = option;
+        breached.set_asset_price(95.0);
+        let at_hit = BarrierOption::new(&breached, BarrierKind::DownAndOut, 95.0)
+            .with_rebate(3.0, RebateTiming::AtHit);
+        approx::assert_abs_diff_eq!(at_hit.put_value(), 3.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn satisfies_in_out_parity() {
+        for &strike in &[90.0, 100.0, 110.0] {
+            let option = create_test_option(strike);
+
+            for &(knock_in, knock_out, barrier) in &[
+                (BarrierKind::DownAndIn, BarrierKind::DownAndOut, 95.0),
+                (BarrierKind::UpAndIn, BarrierKind::UpAndOut, 105.0),
+            ] {
+                let knock_in = BarrierOption::new(&option, knock_in, barrier);
+                let knock_out = BarrierOption::new(&option, knock_out, barrier);
+
+                approx::assert_abs_diff_eq!(
+                    knock_in.call_value() + knock_out.call_value(),
+                    option.call_value(),
+                    epsilon = 1e-10
+                );
+                approx::assert_abs_diff_eq!(
+                    knock_in.put_value() + knock_out.put_value(),
+                    option.put_value(),
+                    epsilon = 1e-10
+                );
+                approx::assert_abs_diff_eq!(
+                    knock_in.call_delta() + knock_out.call_delta(),
+                    option.call_delta(),
+                    epsilon = 1e-10
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn handles_breached_barriers() {
+        let option = create_test_option(100.0);
+        let knock_in = BarrierOption::new(&option, BarrierKind::UpAndIn, 100.0);
+        let knock_out = BarrierOption::new(&option, BarrierKind::UpAndOut, 100.0);
+
+        assert!(knock_in.is_breached());
+        approx::assert_abs_diff_eq!(knock_in.call_value(), option.call_value(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(knock_out.call_value(), 0.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn matches_bumped_greeks() {
+        let option = create_test_option(100.0);
+        let barrier = BarrierOption::new(&option, BarrierKind::DownAndOut, 90.0);
+
+        let reprice = |asset_price: f64, volatility: f64| {
+            let mut bumped = option;
+            bumped.set_asset_price(asset_price);
+            bumped.set_volatility(volatility);
+            BarrierOption::new(&bumped, BarrierKind::DownAndOut, 90.0).put_value()
+        };
+
+        let (up, mid, down) = (
+            reprice(100.01, 0.25),
+            reprice(100.0, 0.25),
+            reprice(99.99, 0.25),
+        );
+        approx::assert_abs_diff_eq!(barrier.put_delta(), (up - down) / 0.02, epsilon = 1e-6);
+        approx::assert_abs_diff_eq!(
+            barrier.put_gamma(),
+            (up - 2.0 * mid + down) / 1e-4,
+            epsilon = 1e-5
+        );
+
+        let (up, down) = (reprice(100.0, 0.2501), reprice(100.0, 0.2499));
+        approx::assert_abs_diff_eq!(
+            barrier.put_vega(),
+            (up - down) / 0.0002 * 0.01,
+            epsilon = 1e-6
+        );
+    }
+}
@@ -0,0 +1,299 @@
+//! Digital (binary) options.
+//!
+//! A cash-or-nothing option pays a fixed amount if it expires in the money,
+//! and is worth `cash * e^(-rT) * N(d2)` for a call. An asset-or-nothing
+//! option pays the asset itself, and is worth `S * e^(-qT) * N(d1)` for a
+//! call. A vanilla call is an asset-or-nothing call minus `K` cash-or-nothing
+//! calls paying `1`, which is how asset-or-nothing options are priced here.
+
+use crate::option::BSOption;
+use crate::utils::normal_pdf;
+use wasm_bindgen::prelude::*;
+
+/// An enumeration of the supported digital payoffs.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DigitalKind {
+    /// Pays a fixed cash amount if the option expires in the money.
+    CashOrNothing,
+    /// Pays the asset if the option expires in the money.
+    AssetOrNothing,
+}
+
+/// A digital option on the asset, strike and maturity of a `BSOption`.
+///
+/// Greeks follow the option's `GreekConventions`. If the option has a shift,
+/// the digital is priced under the same shifted model.
+///
+/// ```rust
+/// use opcalc::exotics::digital::DigitalOption;
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+///
+/// let binary_call = DigitalOption::cash_or_nothing(&option, 10.0).call_value();
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct DigitalOption {
+    option: BSOption,
+    kind: DigitalKind,
+    cash: f64,
+}
+
+#[wasm_bindgen]
+impl DigitalOption {
+    /// Create a cash-or-nothing option paying `cash` if it expires in the
+    /// money.
+    pub fn cash_or_nothing(option: &BSOption, cash: f64) -> DigitalOption {
+        DigitalOption {
+            option: *option,
+            kind: DigitalKind::CashOrNothing,
+            cash,
+        }
+    }
+
+    /// Create an asset-or-nothing option paying the asset if it expires in
+    /// the money.
+    pub fn asset_or_nothing(option: &BSOption) -> DigitalOption {
+        DigitalOption {
+            option: *option,
+            kind: DigitalKind::AssetOrNothing,
+            cash: 0.0,
+        }
+    }
+
+    /// Get the digital's payoff kind.
+    pub fn kind(&self) -> DigitalKind {
+        self.kind
+    }
+
+    /// Get the cash amount paid by a cash-or-nothing option. Asset-or-nothing
+    /// options return `0`.
+    pub fn cash(&self) -> f64 {
+        self.cash
+    }
+
+    /// Get the call's value.
+    pub fn call_value(&self) -> f64 {
+        let unit_call = self.discount_factor() * self.option.nd2();
+        match self.kind {
+            DigitalKind::CashOrNothing => self.cash * unit_call,
+            DigitalKind::AssetOrNothing => self.option.call_value() + self.strike() * unit_call,
+        }
+    }
+
+    /// Get the put's value.
+    pub fn put_value(&self) -> f64 {
+        let unit_put = self.discount_factor() * (1.0 - self.option.nd2());
+        match self.kind {
+            DigitalKind::CashOrNothing => self.cash * unit_put,
+            DigitalKind::AssetOrNothing => self.strike() * unit_put - self.option.put_value(),
+        }
+    }
+
+    /// Get the call's delta.
+    pub fn call_delta(&self) -> f64 {
+        let vol_sqrt_time = self.volatility_sqrt_time();
+        let unit_delta = self.unit_density() / (self.shifted_asset_price() * vol_sqrt_time);
+        match self.kind {
+            DigitalKind::CashOrNothing => self.cash * unit_delta,
+            DigitalKind::AssetOrNothing => self.option.call_delta() + self.strike() * unit_delta,
+        }
+    }
+
+    /// Get the put's delta.
+    pub fn put_delta(&self) -> f64 {
+        let vol_sqrt_time = self.volatility_sqrt_time();
+        let unit_delta = -self.unit_density() / (self.shifted_asset_price() * vol_sqrt_time);
+        match self.kind {
+            DigitalKind::CashOrNothing => self.cash * unit_delta,
+            DigitalKind::AssetOrNothing => self.strike() * unit_delta - self.option.put_delta(),
+        }
+    }
+
+    /// Get the call's gamma.
+    pub fn call_gamma(&self) -> f64 {
+        let unit_gamma = self.unit_call_gamma();
+        match self.kind {
+            DigitalKind::CashOrNothing => self.cash * unit_gamma,
+            DigitalKind::AssetOrNothing => self.option.call_gamma() + self.strike() * unit_gamma,
+        }
+    }
+
+    /// Get the put's gamma.
+    pub fn put_gamma(&self) -> f64 {
+        let unit_gamma = -self.unit_call_gamma();
+        match self.kind {
+            DigitalKind::CashOrNothing => self.cash * unit_gamma,
+            DigitalKind::AssetOrNothing => self.strike() * unit_gamma - self.option.put_gamma(),
+        }
+    }
+
+    /// Get the call's vega.
+    pub fn call_vega(&self) -> f64 {
+        let unit_vega = self.unit_call_vega();
+        match self.kind {
+            DigitalKind::CashOrNothing => self.cash * unit_vega,
+            DigitalKind::AssetOrNothing => self.option.call_vega() + self.strike() * unit_vega,
+        }
+    }
+
+    /// Get the put's vega.
+    pub fn put_vega(&self) -> f64 {
+        let unit_vega = -self.unit_call_vega();
+        match self.kind {
+            DigitalKind::CashOrNothing => self.cash * unit_vega,
+            DigitalKind::AssetOrNothing => self.strike() * unit_vega - self.option.put_vega(),
+        }
+    }
+}
+
+impl DigitalOption {
+    fn strike(&self) -> f64 {
+        self.option.strike()
+    }
+
+    fn shifted_asset_price(&self) -> f64 {
+        self.option.asset_price() + self.option.shift()
+    }
+
+    fn discount_factor(&self) -> f64 {
+        (-self.option.interest().ln_1p() * self.option.time_to_maturity()).exp()
+    }
+
+    fn volatility_sqrt_time(&self) -> f64 {
+        self.option.volatility() * self.option.time_to_maturity().sqrt()
+    }
+
+    /// `e^(-rT) * n(d2)`, shared by the greeks of a digital paying `1`.
+    fn unit_density(&self) -> f64 {
+        self.discount_factor() * normal_pdf(self.option.d2())
+    }
+
+    /// The gamma of a cash-or-nothing call paying `1`,
+    /// `-e^(-rT) * n(d2) * d1 / (S * vol * sqrt(T))^2`.
+    fn unit_call_gamma(&self) -> f64 {
+        let asset_price = self.shifted_asset_price();
+        let gamma = -self.unit_density() * self.option.d1()
+            / (asset_price * self.volatility_sqrt_time()).powi(2);
+
+        self.option
+            .greek_conventions()
+            .scale_gamma(gamma, self.option.asset_price())
+    }
+
+    /// The vega of a cash-or-nothing call paying `1`, `-e^(-rT) * n(d2) * d1 / vol`.
+    fn unit_call_vega(&self) -> f64 {
+        let vega = -self.unit_density() * self.option.d1() / self.option.volatility();
+
+        vega * self.option.greek_conventions().volatility_unit()
+    }
+}
+
+#[cfg(test)]
+mod digital_tests {
+    use crate::exotics::digital::DigitalOption;
+    use crate::option::BSOption;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.01)
+    }
+
+    #[test]
+    fn satisfies_parity() {
+        let option = create_test_option();
+        let time = option.time_to_maturity();
+        let discount = (-0.005f64.ln_1p() * time).exp();
+        let carry = (-0.01f64.ln_1p() * time).exp();
+
+        let cash = DigitalOption::cash_or_nothing(&option, 10.0);
+        approx::assert_abs_diff_eq!(
+            cash.call_value() + cash.put_value(),
+            10.0 * discount,
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            cash.call_value(),
+            10.0 * discount * option.call_itm_probability(),
+            epsilon = 1e-12
+        );
+
+        let asset = DigitalOption::asset_or_nothing(&option);
+        approx::assert_abs_diff_eq!(
+            asset.call_value() + asset.put_value(),
+            100.0 * carry,
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            asset.call_value(),
+            100.0 * carry * option.nd1(),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            asset.call_delta() + asset.put_delta(),
+            carry,
+            epsilon = 1e-10
+        );
+    }
+
+    #[test]
+    fn matches_bumped_greeks() {
+        let option = create_test_option();
+
+        for digital in [
+            DigitalOption::cash_or_nothing(&option, 10.0),
+            DigitalOption::asset_or_nothing(&option),
+        ]
+        .iter()
+        {
+            let reprice = |asset_price: f64, volatility: f64| {
+                let mut bumped = option;
+                bumped.set_asset_price(asset_price);
+                bumped.set_volatility(volatility);
+                let bumped = DigitalOption {
+                    option: bumped,
+                    ..*digital
+                };
+                (bumped.call_value(), bumped.put_value())
+            };
+
+            let (call_up, put_up) = reprice(100.01, 0.23);
+            let (call, put) = reprice(100.0, 0.23);
+            let (call_down, put_down) = reprice(99.99, 0.23);
+            approx::assert_abs_diff_eq!(
+                digital.call_delta(),
+                (call_up - call_down) / 0.02,
+                epsilon = 1e-6
+            );
+            approx::assert_abs_diff_eq!(
+                digital.put_delta(),
+                (put_up - put_down) / 0.02,
+                epsilon = 1e-6
+            );
+            approx::assert_abs_diff_eq!(
+                digital.call_gamma(),
+                (call_up - 2.0 * call + call_down) / 1e-4,
+                epsilon = 1e-5
+            );
+            approx::assert_abs_diff_eq!(
+                digital.put_gamma(),
+                (put_up - 2.0 * put + put_down) / 1e-4,
+                epsilon = 1e-5
+            );
+
+            let (call_up, put_up) = reprice(100.0, 0.2301);
+            let (call_down, put_down) = reprice(100.0, 0.2299);
+            approx::assert_abs_diff_eq!(
+                digital.call_vega(),
+                (call_up - call_down) / 0.0002 * 0.01,
+                epsilon = 1e-6
+            );
+            approx::assert_abs_diff_eq!(
+                digital.put_vega(),
+                (put_up - put_down) / 0.0002 * 0.01,
+                epsilon = 1e-6
+            );
+        }
+    }
+}
@@ -0,0 +1,375 @@
+//! Lookback options.
+//!
+//! A lookback option pays off on the extreme asset price over its life. A
+//! floating-strike call pays `S_T - min`, and a floating-strike put pays
+//! `max - S_T`. A fixed-strike call pays `max(max - K, 0)`, and a
+//! fixed-strike put pays `max(K - min, 0)`.
+//!
+//! With continuous monitoring, values follow the closed-form formulas of
+//! Goldman, Sosin & Gatto (1979) for floating strikes and Conze & Viswanathan
+//! (1991) for fixed strikes, in the notation of Haug (2007), "The Complete
+//! Guide to Option Pricing Formulas". Discretely monitored options have no
+//! closed form, and are priced by Monte Carlo simulation.
+
+use crate::montecarlo::{draw_normals, unshifted, MonteCarloEstimate, PathModel};
+use crate::option::{BSOption, OptionType};
+use crate::utils::{normal_cdf, normal_pdf};
+use wasm_bindgen::prelude::*;
+
+const SECONDS_PER_YEAR: f64 = 31_536_000.0;
+
+/// Below this cost of carry, the closed forms switch to their limits at zero
+/// carry, which they otherwise divide by.
+const CARRY_TOLERANCE: f64 = 1e-8;
+
+/// An enumeration of the supported lookback strikes.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum LookbackStrike {
+    /// The strike is the extreme asset price: the minimum for calls, the
+    /// maximum for puts.
+    Floating,
+    /// The strike is the option's strike, and the payoff is on the extreme
+    /// asset price: the maximum for calls, the minimum for puts.
+    Fixed,
+}
+
+/// A lookback option on the asset, strike and maturity of a `BSOption`.
+///
+/// Like their closed forms, lookback options ignore the option's shift.
+///
+/// ```rust
+/// use opcalc::exotics::lookback::{LookbackOption, LookbackStrike};
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+///
+/// let lookback = LookbackOption::new(&option, LookbackStrike::Fixed);
+/// assert!(lookback.call_value() > option.call_value());
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct LookbackOption {
+    option: BSOption,
+    strike_type: LookbackStrike,
+    minimum: f64,
+    maximum: f64,
+    paths: usize,
+    seed: u64,
+}
+
+#[wasm_bindgen]
+impl LookbackOption {
+    /// Create a `LookbackOption` that has not been monitored yet, so that
+    /// the extremes observed so far are the current asset price.
+    #[wasm_bindgen(constructor)]
+    pub fn new(option: &BSOption, strike_type: LookbackStrike) -> LookbackOption {
+        LookbackOption {
+            option: *option,
+            strike_type,
+            minimum: option.asset_price(),
+            maximum: option.asset_price(),
+            paths: 10_000,
+            seed: 1,
+        }
+    }
+
+    /// Set the minimum and maximum asset prices observed so far. They are
+    /// widened to include the current asset price.
+    pub fn with_observed_extremes(self, minimum: f64, maximum: f64) -> LookbackOption {
+        LookbackOption {
+            minimum: minimum.min(self.option.asset_price()),
+            maximum: maximum.max(self.option.asset_price()),
+            ..self
+        }
+    }
+
+    /// Set the number of paths simulated for discretely monitored options.
+    /// Must be at least two.
+    pub fn with_paths(self, paths: usize) -> LookbackOption {
+        LookbackOption {
+            paths: paths.max(2),
+            ..self
+        }
+    }
+
+    /// Set the seed of the random number generator.
+    pub fn with_seed(self, seed: u64) -> LookbackOption {
+        LookbackOption { seed, ..self }
+    }
+
+    /// Get the lookback's strike type.
+    pub fn strike_type(&self) -> LookbackStrike {
+        self.strike_type
+    }
+
+    /// Get the minimum asset price observed so far.
+    pub fn minimum(&self) -> f64 {
+        self.minimum
+    }
+
+    /// Get the maximum asset price observed so far.
+    pub fn maximum(&self) -> f64 {
+        self.maximum
+    }
+
+    /// Get the call's value, with continuous monitoring.
+    pub fn call_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("LookbackOption::call_value");
+
+        self.value(OptionType::Call)
+    }
+
+    /// Get the put's value, with continuous monitoring.
+    pub fn put_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("LookbackOption::put_value");
+
+        self.value(OptionType::Put)
+    }
+
+    /// Get the call's value, monitoring the asset price at
+    /// `monitoring_times` only, as Unix timestamps in seconds. The asset
+    /// price at maturity is always monitored.
+    pub fn discrete_call_value(&self, monitoring_times: Vec<u32>) -> MonteCarloEstimate {
+        crate::utils::set_panic_hook();
+        trace_scope!("LookbackOption::discrete_call_value");
+
+        self.discrete_value(&monitoring_times, OptionType::Call)
+    }
+
+    /// Get the put's value, monitoring the asset price at `monitoring_times`
+    /// only. See `discrete_call_value()`.
+    pub fn discrete_put_value(&self, monitoring_times: Vec<u32>) -> MonteCarloEstimate {
+        crate::utils::set_panic_hook();
+        trace_scope!("LookbackOption::discrete_put_value");
+
+        self.discrete_value(&monitoring_times, OptionType::Put)
+    }
+}
+
+impl LookbackOption {
+    fn discount_factor(&self) -> f64 {
+        (-self.option.interest().ln_1p() * self.option.time_to_maturity()).exp()
+    }
+
+    fn carried_asset_price(&self) -> f64 {
+        let time = self.option.time_to_maturity();
+        self.option.asset_price() * (-self.option.payout_rate().ln_1p() * time).exp()
+    }
+
+    fn value(&self, option_type: OptionType) -> f64 {
+        let strike = self.option.strike();
+        let discount_factor = self.discount_factor();
+
+        // every payoff splits into the part already locked in by the
+        // observed extremes, and an option on the extreme beyond them
+        match (self.strike_type, option_type) {
+            (LookbackStrike::Floating, OptionType::Call) => {
+                self.carried_asset_price() - discount_factor * self.minimum
+                    + self.minimum_put(self.minimum)
+            }
+            (LookbackStrike::Floating, OptionType::Put) => {
+                discount_factor * self.maximum - self.carried_asset_price()
+                    + self.maximum_call(self.maximum)
+            }
+            (LookbackStrike::Fixed, OptionType::Call) => {
+                discount_factor * (self.maximum - strike).max(0.0)
+                    + self.maximum_call(strike.max(self.maximum))
+            }
+            (LookbackStrike::Fixed, OptionType::Put) => {
+                discount_factor * (strike - self.minimum).max(0.0)
+                    + self.minimum_put(strike.min(self.minimum))
+            }
+        }
+    }
+
+    /// The value of a call on the maximum asset price struck at `level`, at
+    /// or above the current asset price.
+    fn maximum_call(&self, level: f64) -> f64 {
+        let (asset_price, carry, vol_sqrt_time) = self.parameters();
+        let d1 = self.standardized(level);
+        let d2 = d1 - vol_sqrt_time;
+        let reflection = if carry.abs() < CARRY_TOLERANCE {
+            vol_sqrt_time * (d1 * normal_cdf(d1) + normal_pdf(d1))
+        } else {
+            let (exponent, shift, scale) = self.reflection_terms(carry);
+            scale
+                * ((carry * self.option.time_to_maturity()).exp() * normal_cdf(d1)
+                    - (asset_price / level).powf(exponent) * normal_cdf(d1 - shift))
+        };
+
+        self.carried_asset_price() * normal_cdf(d1)
+            - self.discount_factor() * level * normal_cdf(d2)
+            + self.discount_factor() * asset_price * reflection
+    }
+
+    /// The value of a put on the minimum asset price struck at `level`, at
+    /// or below the current asset price.
+    fn minimum_put(&self, level: f64) -> f64 {
+        let (asset_price, carry, vol_sqrt_time) = self.parameters();
+        let d1 = self.standardized(level);
+        let d2 = d1 - vol_sqrt_time;
+        let reflection = if carry.abs() < CARRY_TOLERANCE {
+            vol_sqrt_time * (normal_pdf(d1) - d1 * normal_cdf(-d1))
+        } else {
+            let (exponent, shift, scale) = self.reflection_terms(carry);
+            scale
+                * ((asset_price / level).powf(exponent) * normal_cdf(-d1 + shift)
+                    - (carry * self.option.time_to_maturity()).exp() * normal_cdf(-d1))
+        };
+
+        self.discount_factor() * level * normal_cdf(-d2)
+            - self.carried_asset_price() * normal_cdf(-d1)
+            + self.discount_factor() * asset_price * reflection
+    }
+
+    /// The asset price, the continuous cost of carry and `vol * sqrt(T)`.
+    fn parameters(&self) -> (f64, f64, f64) {
+        let carry = self.option.interest().ln_1p() - self.option.payout_rate().ln_1p();
+        let vol_sqrt_time = self.option.volatility() * self.option.time_to_maturity().sqrt();
+
+        (self.option.asset_price(), carry, vol_sqrt_time)
+    }
+
+    /// Haug's `d1` for the asset price against `level`.
+    fn standardized(&self, level: f64) -> f64 {
+        let (asset_price, carry, vol_sqrt_time) = self.parameters();
+        let variance = self.option.volatility().powi(2);
+
+        ((asset_price / level).ln() + (carry + variance / 2.0) * self.option.time_to_maturity())
+            / vol_sqrt_time
+    }
+
+    /// The exponent `-2b / vol^2` of the reflected asset price, the shift
+    /// `2b * sqrt(T) / vol` of its argument, and the scale `vol^2 / 2b` of
+    /// the reflection term.
+    fn reflection_terms(&self, carry: f64) -> (f64, f64, f64) {
+        let volatility = self.option.volatility();
+        let exponent = -2.0 * carry / volatility.powi(2);
+        let shift = 2.0 * carry * self.option.time_to_maturity().sqrt() / volatility;
+
+        (exponent, shift, -1.0 / exponent)
+    }
+
+    fn discrete_value(
+        &self,
+        monitoring_times: &[u32],
+        option_type: OptionType,
+    ) -> MonteCarloEstimate {
+        let time_curr = self.option.time_curr() as f64;
+        let mut times: Vec<f64> = monitoring_times
+            .iter()
+            .filter(|&&time| time < self.option.time_maturity())
+            .map(|&time| (time as f64 - time_curr) / SECONDS_PER_YEAR)
+            .collect();
+        times.push(self.option.horizon());
+
+        let strike = self.option.strike();
+        let discount_factor = self.discount_factor();
+        let model = unshifted(&self.option);
+        let samples: Vec<f64> = draw_normals(self.paths, times.len(), 1, self.seed, false)
+            .map(|normals| {
+                let (mut price, mut time) = (model.initial_state(), 0.0);
+                let (mut minimum, mut maximum) = (self.minimum, self.maximum);
+                for (&monitoring_time, step) in times.iter().zip(&normals) {
+                    price = model.step(&price, monitoring_time - time, step);
+                    time = monitoring_time;
+                    minimum = minimum.min(price);
+                    maximum = maximum.max(price);
+                }
+
+                let payoff = match (self.strike_type, option_type) {
+                    (LookbackStrike::Floating, OptionType::Call) => price - minimum,
+                    (LookbackStrike::Floating, OptionType::Put) => maximum - price,
+                    (LookbackStrike::Fixed, OptionType::Call) => (maximum - strike).max(0.0),
+                    (LookbackStrike::Fixed, OptionType::Put) => (strike - minimum).max(0.0),
+                };
+                discount_factor * payoff
+            })
+            .collect();
+
+        MonteCarloEstimate::from_samples(&samples)
+    }
+}
+
+#[cfg(test)]
+mod lookback_tests {
+    use crate::exotics::lookback::{LookbackOption, LookbackStrike};
+    use crate::option::BSOption;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.0)
+    }
+
+    #[test]
+    fn matches_reference_values() {
+        // Haug (2007), section 4.15.1: six months, r = 10%, b = 4%
+        let option = BSOption::new(
+            0,
+            15_768_000,
+            120.0,
+            120.0,
+            0.1f64.exp_m1(),
+            0.3,
+            0.06f64.exp_m1(),
+        );
+        let lookback = LookbackOption::new(&option, LookbackStrike::Floating)
+            .with_observed_extremes(100.0, 120.0);
+        approx::assert_abs_diff_eq!(lookback.call_value(), 25.3533, epsilon = 1e-4);
+    }
+
+    #[test]
+    fn is_continuous_at_zero_carry() {
+        for &strike_type in &[LookbackStrike::Floating, LookbackStrike::Fixed] {
+            let value = |payout_rate: f64| {
+                let option = BSOption::new(
+                    1606780800,
+                    1610668800,
+                    100.0,
+                    105.0,
+                    0.005,
+                    0.23,
+                    payout_rate,
+                );
+                let lookback =
+                    LookbackOption::new(&option, strike_type).with_observed_extremes(95.0, 110.0);
+                (lookback.call_value(), lookback.put_value())
+            };
+
+            let (call, put) = value(0.005);
+            let (call_near, put_near) = value(0.005 + 1e-6);
+            approx::assert_abs_diff_eq!(call, call_near, epsilon = 1e-4);
+            approx::assert_abs_diff_eq!(put, put_near, epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn approaches_continuous_monitoring() {
+        let option = create_test_option();
+        let monitoring = |days: u32| -> Vec<u32> {
+            (1..45 / days)
+                .map(|i| 1606780800 + i * days * 86_400)
+                .collect()
+        };
+
+        for &strike_type in &[LookbackStrike::Floating, LookbackStrike::Fixed] {
+            let lookback =
+                LookbackOption::new(&option, strike_type).with_observed_extremes(98.0, 103.0);
+
+            // monitoring more often can only find more extreme prices
+            let at_maturity = lookback.discrete_call_value(vec![]);
+            let weekly = lookback.discrete_call_value(monitoring(7));
+            let daily = lookback.discrete_call_value(monitoring(1));
+            assert!(at_maturity.value() < weekly.value());
+            assert!(weekly.value() < daily.value());
+            assert!(daily.value() < lookback.call_value());
+
+            let weekly = lookback.discrete_put_value(monitoring(7));
+            let daily = lookback.discrete_put_value(monitoring(1));
+            assert!(weekly.value() < daily.value());
+            assert!(daily.value() < lookback.put_value());
+        }
+    }
+}
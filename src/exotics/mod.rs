@@ -0,0 +1,12 @@
+//! Pricing of exotic options.
+//!
+//! Like the models in `models`, exotic options take their market inputs
+//! (asset price, strike, rates, times and volatility) from a `BSOption`, and
+//! add the terms of their payoff on top.
+
+pub mod asian;
+pub mod barrier;
+pub mod digital;
+pub mod lookback;
+pub mod spread;
+pub mod touch;
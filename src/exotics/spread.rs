@@ -0,0 +1,217 @@
+//! Exchange and spread options on two assets.
+//!
+//! An exchange option gives the right to swap the second asset for the
+//! first at maturity, paying `max(S1 - S2, 0)`. Margrabe (1978) values it
+//! in closed form, measuring the first asset in units of the second: the
+//! ratio is lognormal with volatility
+//! `sqrt(vol1^2 - 2 * rho * vol1 * vol2 + vol2^2)`.
+//!
+//! A spread option adds a strike, paying `max(S1 - S2 - K, 0)` for a call.
+//! It has no closed form; Kirk (1995) approximates `S2 + K` as lognormal,
+//! which is accurate for strikes that are small relative to the second
+//! asset price, and exact for `K = 0`.
+
+use crate::option::BSOption;
+use crate::utils::normal_cdf;
+use wasm_bindgen::prelude::*;
+
+/// An exchange or spread option on two assets, each taking its asset price,
+/// volatility and payout rate from a `BSOption`. The strike, times and
+/// interest rate are the first option's.
+///
+/// Spread options ignore the options' shifts.
+///
+/// ```rust
+/// use opcalc::exotics::spread::SpreadOption;
+/// use opcalc::option::BSOption;
+///
+/// let first = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 5.0, 0.005, 0.23, 0.0);
+/// let second = BSOption::new(1_606_780_800, 1_610_668_800, 96.0, 5.0, 0.005, 0.18, 0.0);
+///
+/// let spread = SpreadOption::new(&first, &second, 0.6);
+/// assert!(spread.call_value() < spread.exchange_value());
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct SpreadOption {
+    first: BSOption,
+    second: BSOption,
+    correlation: f64,
+}
+
+#[wasm_bindgen]
+impl SpreadOption {
+    /// Create a `SpreadOption` on the assets of `first` and `second`, whose
+    /// returns have correlation `correlation`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(first: &BSOption, second: &BSOption, correlation: f64) -> SpreadOption {
+        SpreadOption {
+            first: *first,
+            second: *second,
+            correlation,
+        }
+    }
+
+    /// Get the correlation between the two assets' returns.
+    pub fn correlation(&self) -> f64 {
+        self.correlation
+    }
+
+    /// Get the value of the option to exchange the second asset for the
+    /// first, with Margrabe's formula. The strike is not used.
+    pub fn exchange_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("SpreadOption::exchange_value");
+
+        let time = self.first.time_to_maturity();
+        let (first, second) = (
+            self.carried_price(&self.first),
+            self.carried_price(&self.second),
+        );
+        let vol_sqrt_time = self.ratio_volatility(1.0) * time.sqrt();
+        let d1 = (first / second).ln() / vol_sqrt_time + vol_sqrt_time / 2.0;
+
+        first * normal_cdf(d1) - second * normal_cdf(d1 - vol_sqrt_time)
+    }
+
+    /// Get the value of the call on the spread, with Kirk's approximation.
+    pub fn call_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("SpreadOption::call_value");
+
+        let (discount_factor, first, second, d1, d2) = self.kirk_terms();
+        discount_factor * (first * normal_cdf(d1) - second * normal_cdf(d2))
+    }
+
+    /// Get the value of the put on the spread, with Kirk's approximation.
+    pub fn put_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("SpreadOption::put_value");
+
+        let (discount_factor, first, second, d1, d2) = self.kirk_terms();
+        discount_factor * (second * normal_cdf(-d2) - first * normal_cdf(-d1))
+    }
+}
+
+impl SpreadOption {
+    /// The asset price net of payouts until maturity, `S * e^(-qT)`.
+    fn carried_price(&self, option: &BSOption) -> f64 {
+        let time = self.first.time_to_maturity();
+        option.asset_price() * (-option.payout_rate().ln_1p() * time).exp()
+    }
+
+    /// The volatility of the first asset measured in units of the second,
+    /// with the second asset's volatility scaled by `weight`.
+    fn ratio_volatility(&self, weight: f64) -> f64 {
+        let (first, second) = (self.first.volatility(), self.second.volatility() * weight);
+
+        (first.powi(2) - 2.0 * self.correlation * first * second + second.powi(2)).sqrt()
+    }
+
+    /// The discount factor, the forward of the first asset, the forward of
+    /// the second asset plus the strike, and Kirk's `d1` and `d2`.
+    fn kirk_terms(&self) -> (f64, f64, f64, f64, f64) {
+        let time = self.first.time_to_maturity();
+        let rate = self.first.interest().ln_1p();
+        let growth = (rate * time).exp();
+
+        let first = self.carried_price(&self.first) * growth;
+        let second_forward = self.carried_price(&self.second) * growth;
+        let second = second_forward + self.first.strike();
+
+        let vol_sqrt_time = self.ratio_volatility(second_forward / second) * time.sqrt();
+        let d1 = (first / second).ln() / vol_sqrt_time + vol_sqrt_time / 2.0;
+
+        ((-rate * time).exp(), first, second, d1, d1 - vol_sqrt_time)
+    }
+}
+
+#[cfg(test)]
+mod spread_tests {
+    use crate::exotics::spread::SpreadOption;
+    use crate::montecarlo::{draw_normals, MonteCarloEstimate, PathModel};
+    use crate::option::BSOption;
+
+    fn create_test_options(strike: f64) -> (BSOption, BSOption) {
+        (
+            BSOption::new(1606780800, 1610668800, 100.0, strike, 0.005, 0.23, 0.01),
+            BSOption::new(1606780800, 1610668800, 96.0, strike, 0.005, 0.18, 0.02),
+        )
+    }
+
+    #[test]
+    fn values_riskless_second_assets_like_vanilla_options() {
+        let (first, mut second) = create_test_options(4.0);
+        second.set_volatility(1e-12);
+        let spread = SpreadOption::new(&first, &second, 0.6);
+
+        // the second asset's forward is known, and becomes part of the strike
+        let time = first.time_to_maturity();
+        let forward = 96.0 * ((0.005f64.ln_1p() - 0.02f64.ln_1p()) * time).exp();
+        let mut vanilla = first;
+        vanilla.set_strike(forward + 4.0);
+        approx::assert_abs_diff_eq!(spread.call_value(), vanilla.call_value(), epsilon = 1e-10);
+        approx::assert_abs_diff_eq!(spread.put_value(), vanilla.put_value(), epsilon = 1e-10);
+
+        vanilla.set_strike(forward);
+        approx::assert_abs_diff_eq!(
+            spread.exchange_value(),
+            vanilla.call_value(),
+            epsilon = 1e-10
+        );
+    }
+
+    #[test]
+    fn reduces_to_margrabe_without_a_strike() {
+        let (first, second) = create_test_options(0.0);
+        let spread = SpreadOption::new(&first, &second, -0.3);
+
+        approx::assert_abs_diff_eq!(
+            spread.call_value(),
+            spread.exchange_value(),
+            epsilon = 1e-10
+        );
+    }
+
+    #[test]
+    fn satisfies_parity() {
+        let (first, second) = create_test_options(4.0);
+        let spread = SpreadOption::new(&first, &second, 0.6);
+
+        let time = first.time_to_maturity();
+        let discount_factor = (-0.005f64.ln_1p() * time).exp();
+        let forwards =
+            100.0 * (-0.01f64.ln_1p() * time).exp() - 96.0 * (-0.02f64.ln_1p() * time).exp();
+        approx::assert_abs_diff_eq!(
+            spread.call_value() - spread.put_value(),
+            forwards - 4.0 * discount_factor,
+            epsilon = 1e-10
+        );
+    }
+
+    #[test]
+    fn approximates_simulated_spread_options() {
+        let (first, second) = create_test_options(4.0);
+        let correlation: f64 = 0.6;
+        let spread = SpreadOption::new(&first, &second, correlation);
+
+        let time = first.time_to_maturity();
+        let discount_factor = (-first.discount_rate() * time).exp();
+        let payoffs: Vec<f64> = draw_normals(100_000, 1, 2, 3, false)
+            .map(|normals| {
+                let (z1, z2) = (normals[0][0], normals[0][1]);
+                let z2 = correlation * z1 + (1.0 - correlation.powi(2)).sqrt() * z2;
+                let first_price = first.step(&first.initial_state(), time, &[z1]);
+                let second_price = second.step(&second.initial_state(), time, &[z2]);
+                discount_factor * (first_price - second_price - 4.0).max(0.0)
+            })
+            .collect();
+        let estimate = MonteCarloEstimate::from_samples(&payoffs);
+
+        approx::assert_abs_diff_eq!(
+            spread.call_value(),
+            estimate.value(),
+            epsilon = 3.0 * estimate.standard_error() + 0.01
+        );
+    }
+}
@@ -0,0 +1,192 @@
+//! One-touch and no-touch options.
+//!
+//! A one-touch option pays a fixed amount if the asset price touches the
+//! barrier before maturity, and a no-touch option pays it if the asset price
+//! never does. Both are the rebates of barrier options: a one-touch is the
+//! rebate of a knock-out option, a no-touch the rebate of a knock-in option.
+//! The barrier lies below or above the current asset price, and is
+//! monitored continuously.
+
+use crate::exotics::barrier::{BarrierKind, BarrierOption, RebateTiming};
+use crate::option::opcalc::op_calc::PricingInputs;
+use crate::option::BSOption;
+use wasm_bindgen::prelude::*;
+
+/// A one-touch or no-touch option on the asset and maturity of a `BSOption`.
+/// The option's strike is not used.
+///
+/// ```rust
+/// use opcalc::exotics::touch::TouchOption;
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+///
+/// let touch = TouchOption::new(&option, 110.0, 1_000.0);
+/// let probability = touch.touch_probability();
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct TouchOption {
+    option: BSOption,
+    barrier: f64,
+    payout: f64,
+    payment_timing: RebateTiming,
+}
+
+#[wasm_bindgen]
+impl TouchOption {
+    /// Create a `TouchOption` paying `payout` depending on whether the asset
+    /// price touches `barrier`. A one-touch pays as soon as the barrier is
+    /// touched.
+    #[wasm_bindgen(constructor)]
+    pub fn new(option: &BSOption, barrier: f64, payout: f64) -> TouchOption {
+        TouchOption {
+            option: *option,
+            barrier,
+            payout,
+            payment_timing: RebateTiming::AtHit,
+        }
+    }
+
+    /// Set when a one-touch pays out. No-touch options always pay at
+    /// maturity.
+    pub fn with_payment_timing(self, payment_timing: RebateTiming) -> TouchOption {
+        TouchOption {
+            payment_timing,
+            ..self
+        }
+    }
+
+    /// Get the barrier level.
+    pub fn barrier(&self) -> f64 {
+        self.barrier
+    }
+
+    /// Get the amount paid out.
+    pub fn payout(&self) -> f64 {
+        self.payout
+    }
+
+    /// Get when a one-touch pays out.
+    pub fn payment_timing(&self) -> RebateTiming {
+        self.payment_timing
+    }
+
+    /// Get the risk-neutral probability that the asset price touches the
+    /// barrier before maturity.
+    pub fn touch_probability(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("TouchOption::touch_probability");
+
+        let discount_factor =
+            (-self.option.interest().ln_1p() * self.option.time_to_maturity()).exp();
+        let no_touch = TouchOption {
+            payout: 1.0,
+            ..*self
+        }
+        .no_touch_value();
+
+        1.0 - no_touch / discount_factor
+    }
+
+    /// Get the value of the one-touch option.
+    pub fn one_touch_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("TouchOption::one_touch_value");
+
+        let kind = if self.is_below() {
+            BarrierKind::DownAndOut
+        } else {
+            BarrierKind::UpAndOut
+        };
+        self.barrier_option(kind)
+            .rebate_value(&PricingInputs::new(&self.option))
+    }
+
+    /// Get the value of the no-touch option.
+    pub fn no_touch_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("TouchOption::no_touch_value");
+
+        let kind = if self.is_below() {
+            BarrierKind::DownAndIn
+        } else {
+            BarrierKind::UpAndIn
+        };
+        self.barrier_option(kind)
+            .rebate_value(&PricingInputs::new(&self.option))
+    }
+}
+
+impl TouchOption {
+    fn is_below(&self) -> bool {
+        self.barrier < self.option.asset_price()
+    }
+
+    fn barrier_option(&self, kind: BarrierKind) -> BarrierOption {
+        BarrierOption::new(&self.option, kind, self.barrier)
+            .with_rebate(self.payout, self.payment_timing)
+    }
+}
+
+#[cfg(test)]
+mod touch_tests {
+    use crate::exotics::barrier::RebateTiming;
+    use crate::exotics::touch::TouchOption;
+    use crate::option::BSOption;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.0)
+    }
+
+    #[test]
+    fn values_one_touch_and_no_touch() {
+        let option = create_test_option();
+        let discount_factor = (-0.005f64.ln_1p() * option.time_to_maturity()).exp();
+
+        for &barrier in &[90.0, 110.0] {
+            let touch = TouchOption::new(&option, barrier, 10.0);
+            let at_expiry = touch.with_payment_timing(RebateTiming::AtExpiry);
+            let probability = touch.touch_probability();
+
+            assert!(probability > 0.0 && probability < 1.0);
+            approx::assert_abs_diff_eq!(
+                at_expiry.one_touch_value() + at_expiry.no_touch_value(),
+                10.0 * discount_factor,
+                epsilon = 1e-10
+            );
+            approx::assert_abs_diff_eq!(
+                at_expiry.one_touch_value(),
+                10.0 * discount_factor * probability,
+                epsilon = 1e-10
+            );
+            assert!(touch.one_touch_value() > at_expiry.one_touch_value());
+        }
+    }
+
+    #[test]
+    fn doubles_the_probability_of_finishing_beyond_the_barrier() {
+        // with a payout rate cancelling the drift of ln(S), the reflection
+        // principle makes touching twice as likely as finishing beyond the
+        // barrier
+        let payout_rate = (-0.23f64.powi(2) / 2.0).exp() - 1.0;
+        let option = BSOption::new(1606780800, 1610668800, 100.0, 110.0, 0.0, 0.23, payout_rate);
+        let touch = TouchOption::new(&option, 110.0, 1.0);
+
+        approx::assert_abs_diff_eq!(
+            touch.touch_probability(),
+            2.0 * option.call_itm_probability(),
+            epsilon = 1e-10
+        );
+    }
+
+    #[test]
+    fn pays_out_once_touched() {
+        let option = create_test_option();
+        let touch = TouchOption::new(&option, 100.0, 10.0);
+
+        approx::assert_abs_diff_eq!(touch.touch_probability(), 1.0, epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(touch.one_touch_value(), 10.0, epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(touch.no_touch_value(), 0.0, epsilon = 1e-12);
+    }
+}
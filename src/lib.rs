@@ -6,14 +6,26 @@ extern crate approx;
 extern crate statrs;
 extern crate web_sys;
 
-pub mod option;
+#[macro_use]
 mod utils;
+#[macro_use]
+pub mod logging;
+pub mod backtest;
+pub mod exotics;
+pub mod memory;
+pub mod models;
+pub mod montecarlo;
+pub mod option;
+pub mod transform;
+pub mod volatility;
 
+pub use utils::{normal_cdf, normal_pdf};
 use wasm_bindgen::prelude::*;
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
-// allocator.
-#[cfg(feature = "wee_alloc")]
+// allocator. With `heap_stats`, the allocator is wrapped and installed in
+// `memory` instead.
+#[cfg(all(feature = "wee_alloc", not(feature = "heap_stats")))]
 #[global_allocator]
 static ALLOC: wee_alloc::WeeAlloc = wee_alloc::WeeAlloc::INIT;
 
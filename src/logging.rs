@@ -0,0 +1,189 @@
+//! Leveled logging for diagnostic output.
+//!
+//! In WebAssembly, messages are written to the browser console
+//! (`console.error`, `console.warn`, `console.info` and `console.debug`).
+//! Natively, they are forwarded to the [`log`](https://docs.rs/log) crate
+//! under the `opcalc` target, so they reach whichever logger the host
+//! application installed.
+//!
+//! Leveled logging is off by default. It can be switched on at runtime,
+//! without recompiling, by calling `set_log_level()`:
+//!
+//! ```rust
+//! use opcalc::logging::{set_log_level, LogLevel};
+//!
+//! set_log_level(LogLevel::Debug);
+//! ```
+//!
+//! The older `log!` macro is not leveled: it always writes to `console.log`,
+//! or to `log` at the `Info` level natively.
+
+use std::fmt;
+use std::sync::atomic::{AtomicU8, Ordering};
+use wasm_bindgen::prelude::*;
+
+static LOG_LEVEL: AtomicU8 = AtomicU8::new(LogLevel::Off as u8);
+
+/// The verbosity of the library's diagnostic output.
+///
+/// Each level includes all the levels before it, e.g. `Warn` also emits
+/// errors.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum LogLevel {
+    /// Emit nothing.
+    Off = 0,
+    /// Failures that prevent a calculation from producing a result.
+    Error = 1,
+    /// Suspicious inputs or results that are still returned.
+    Warn = 2,
+    /// High-level progress of calculations.
+    Info = 3,
+    /// Detailed output, such as individual solver iterations.
+    Debug = 4,
+}
+
+impl LogLevel {
+    fn from_u8(level: u8) -> LogLevel {
+        match level {
+            1 => LogLevel::Error,
+            2 => LogLevel::Warn,
+            3 => LogLevel::Info,
+            4 => LogLevel::Debug,
+            _ => LogLevel::Off,
+        }
+    }
+}
+
+/// Set the maximum level of messages the library emits.
+#[wasm_bindgen]
+pub fn set_log_level(level: LogLevel) {
+    LOG_LEVEL.store(level as u8, Ordering::Relaxed);
+}
+
+/// Get the maximum level of messages the library currently emits.
+#[wasm_bindgen]
+pub fn log_level() -> LogLevel {
+    LogLevel::from_u8(LOG_LEVEL.load(Ordering::Relaxed))
+}
+
+/// Whether messages at `level` are currently emitted.
+pub fn enabled(level: LogLevel) -> bool {
+    level != LogLevel::Off && level <= log_level()
+}
+
+/// Emit a message at `level`. Prefer the `log_*!` macros over calling this
+/// directly.
+#[doc(hidden)]
+pub fn write(level: LogLevel, args: fmt::Arguments) {
+    if !enabled(level) {
+        return;
+    }
+
+    #[cfg(target_arch = "wasm32")]
+    {
+        use web_sys::console;
+
+        let message = JsValue::from(args.to_string());
+        match level {
+            LogLevel::Error => console::error_1(&message),
+            LogLevel::Warn => console::warn_1(&message),
+            LogLevel::Info => console::info_1(&message),
+            LogLevel::Debug => console::debug_1(&message),
+            LogLevel::Off => {}
+        }
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        let level = match level {
+            LogLevel::Error => log::Level::Error,
+            LogLevel::Warn => log::Level::Warn,
+            LogLevel::Info => log::Level::Info,
+            LogLevel::Debug => log::Level::Debug,
+            LogLevel::Off => return,
+        };
+        log::log!(target: "opcalc", level, "{}", args);
+    }
+}
+
+/// Log a message at the `Error` level, with `println!(..)`-style syntax.
+#[macro_export]
+macro_rules! log_error {
+    ( $( $t:tt )* ) => {
+        $crate::logging::write($crate::logging::LogLevel::Error, format_args!( $( $t )* ))
+    }
+}
+
+/// Log a message at the `Warn` level, with `println!(..)`-style syntax.
+#[macro_export]
+macro_rules! log_warn {
+    ( $( $t:tt )* ) => {
+        $crate::logging::write($crate::logging::LogLevel::Warn, format_args!( $( $t )* ))
+    }
+}
+
+/// Log a message at the `Info` level, with `println!(..)`-style syntax.
+#[macro_export]
+macro_rules! log_info {
+    ( $( $t:tt )* ) => {
+        $crate::logging::write($crate::logging::LogLevel::Info, format_args!( $( $t )* ))
+    }
+}
+
+/// Log a message at the `Debug` level, with `println!(..)`-style syntax.
+#[macro_export]
+macro_rules! log_debug {
+    ( $( $t:tt )* ) => {
+        $crate::logging::write($crate::logging::LogLevel::Debug, format_args!( $( $t )* ))
+    }
+}
+
+/// Emit a message whatever the log level, as `log!` always has. Prefer the
+/// leveled macros.
+#[doc(hidden)]
+pub fn write_unleveled(args: fmt::Arguments) {
+    #[cfg(target_arch = "wasm32")]
+    web_sys::console::log_1(&JsValue::from(args.to_string()));
+
+    #[cfg(not(target_arch = "wasm32"))]
+    log::info!(target: "opcalc", "{}", args);
+}
+
+/// A macro to provide `println!(..)`-style syntax for `console.log` logging,
+/// whatever the log level. Kept for compatibility; prefer `log_info!` and the
+/// other leveled macros.
+#[macro_export]
+macro_rules! log {
+    ( $( $t:tt )* ) => {
+        $crate::logging::write_unleveled(format_args!( $( $t )* ))
+    }
+}
+
+#[cfg(test)]
+mod logging_tests {
+    use crate::logging::{enabled, log_level, set_log_level, LogLevel};
+
+    #[test]
+    fn toggles_log_level_at_runtime() {
+        // the level is global, so leave it as other tests found it
+        let initial_level = log_level();
+
+        set_log_level(LogLevel::Warn);
+        assert!(enabled(LogLevel::Error));
+        assert!(enabled(LogLevel::Warn));
+        assert!(!enabled(LogLevel::Info));
+
+        // emitting below the threshold is a no-op, above it goes to `log`
+        log_debug!("not emitted: {}", 1);
+        log_warn!("emitted: {}", 2);
+
+        // the unleveled macro emits regardless
+        log!("emitted: {}", 3);
+
+        set_log_level(LogLevel::Off);
+        assert!(!enabled(LogLevel::Error));
+
+        set_log_level(initial_level);
+    }
+}
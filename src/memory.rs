@@ -0,0 +1,234 @@
+//! Heap usage reporting.
+//!
+//! When the `heap_stats` feature is enabled, the global allocator is wrapped
+//! in a `TrackingAllocator` that records the number of bytes currently
+//! allocated as well as the peak allocation since the last reset. This makes
+//! it possible to detect memory growth during large calculations and to
+//! compare allocators (e.g. `wee_alloc` vs the default one) with real data.
+//!
+//! Without `heap_stats`, `heap_usage()` still works but always reports zero
+//! bytes and `HeapUsage::tracking_enabled()` returns `false`.
+
+use std::alloc::{GlobalAlloc, Layout};
+use std::sync::atomic::{AtomicUsize, Ordering};
+use wasm_bindgen::prelude::*;
+
+// When the `heap_stats` feature is enabled, wrap the selected allocator so
+// that every allocation is counted.
+#[cfg(all(feature = "heap_stats", feature = "wee_alloc"))]
+#[global_allocator]
+static ALLOC: TrackingAllocator<wee_alloc::WeeAlloc<'static>> =
+    TrackingAllocator::new(wee_alloc::WeeAlloc::INIT);
+
+#[cfg(all(feature = "heap_stats", not(feature = "wee_alloc")))]
+#[global_allocator]
+static ALLOC: TrackingAllocator<std::alloc::System> = TrackingAllocator::new(std::alloc::System);
+
+/// An allocator wrapper that keeps track of current and peak heap usage.
+///
+/// All bookkeeping is done with atomics, so the wrapper adds no locking on
+/// top of the inner allocator.
+#[derive(Debug)]
+pub struct TrackingAllocator<A> {
+    inner: A,
+    current_bytes: AtomicUsize,
+    peak_bytes: AtomicUsize,
+    allocations: AtomicUsize,
+}
+
+impl<A> TrackingAllocator<A> {
+    /// Wrap `inner` in a `TrackingAllocator`.
+    pub const fn new(inner: A) -> TrackingAllocator<A> {
+        TrackingAllocator {
+            inner,
+            current_bytes: AtomicUsize::new(0),
+            peak_bytes: AtomicUsize::new(0),
+            allocations: AtomicUsize::new(0),
+        }
+    }
+
+    /// Get the number of bytes currently allocated.
+    pub fn current_bytes(&self) -> usize {
+        self.current_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Get the highest number of bytes allocated at once since creation or
+    /// the last call to `reset_peak()`.
+    pub fn peak_bytes(&self) -> usize {
+        self.peak_bytes.load(Ordering::Relaxed)
+    }
+
+    /// Get the total number of allocations performed.
+    pub fn allocations(&self) -> usize {
+        self.allocations.load(Ordering::Relaxed)
+    }
+
+    /// Reset the peak to the current usage, so the next batch run can be
+    /// measured on its own.
+    pub fn reset_peak(&self) {
+        self.peak_bytes.store(self.current_bytes(), Ordering::Relaxed);
+    }
+
+    fn record_alloc(&self, size: usize) {
+        self.allocations.fetch_add(1, Ordering::Relaxed);
+        let current = self.current_bytes.fetch_add(size, Ordering::Relaxed) + size;
+        self.peak_bytes.fetch_max(current, Ordering::Relaxed);
+    }
+
+    fn record_dealloc(&self, size: usize) {
+        self.current_bytes.fetch_sub(size, Ordering::Relaxed);
+    }
+}
+
+unsafe impl<A: GlobalAlloc> GlobalAlloc for TrackingAllocator<A> {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc(layout);
+        if !ptr.is_null() {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn alloc_zeroed(&self, layout: Layout) -> *mut u8 {
+        let ptr = self.inner.alloc_zeroed(layout);
+        if !ptr.is_null() {
+            self.record_alloc(layout.size());
+        }
+        ptr
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        self.inner.dealloc(ptr, layout);
+        self.record_dealloc(layout.size());
+    }
+
+    unsafe fn realloc(&self, ptr: *mut u8, layout: Layout, new_size: usize) -> *mut u8 {
+        let new_ptr = self.inner.realloc(ptr, layout, new_size);
+        if !new_ptr.is_null() {
+            self.record_dealloc(layout.size());
+            self.record_alloc(new_size);
+        }
+        new_ptr
+    }
+}
+
+/// A snapshot of the library's heap usage.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct HeapUsage {
+    tracking_enabled: bool,
+    allocator: &'static str,
+    current_bytes: usize,
+    peak_bytes: usize,
+    allocations: usize,
+}
+
+#[wasm_bindgen]
+impl HeapUsage {
+    /// Whether the `heap_stats` feature is enabled. When it is not, all
+    /// byte counts are zero.
+    pub fn tracking_enabled(&self) -> bool {
+        self.tracking_enabled
+    }
+
+    /// Get the name of the active allocator: `"wee_alloc"` or `"system"`.
+    pub fn allocator(&self) -> String {
+        self.allocator.to_string()
+    }
+
+    /// Get the number of bytes currently allocated.
+    pub fn current_bytes(&self) -> usize {
+        self.current_bytes
+    }
+
+    /// Get the peak number of bytes allocated since the last call to
+    /// `reset_peak_heap_usage()`.
+    pub fn peak_bytes(&self) -> usize {
+        self.peak_bytes
+    }
+
+    /// Get the total number of allocations performed.
+    pub fn allocations(&self) -> usize {
+        self.allocations
+    }
+}
+
+/// Get a snapshot of the current heap usage.
+///
+/// # Usage in JavaScript
+///
+/// ```js
+/// opcalc.reset_peak_heap_usage();
+/// // ... run a large batch of calculations ...
+/// const usage = opcalc.heap_usage();
+/// console.log(usage.allocator(), usage.peak_bytes());
+/// ```
+#[wasm_bindgen]
+pub fn heap_usage() -> HeapUsage {
+    let allocator = if cfg!(feature = "wee_alloc") {
+        "wee_alloc"
+    } else {
+        "system"
+    };
+
+    #[cfg(feature = "heap_stats")]
+    let (current_bytes, peak_bytes, allocations) = (
+        ALLOC.current_bytes(),
+        ALLOC.peak_bytes(),
+        ALLOC.allocations(),
+    );
+
+    #[cfg(not(feature = "heap_stats"))]
+    let (current_bytes, peak_bytes, allocations) = (0, 0, 0);
+
+    HeapUsage {
+        tracking_enabled: cfg!(feature = "heap_stats"),
+        allocator,
+        current_bytes,
+        peak_bytes,
+        allocations,
+    }
+}
+
+/// Reset the peak heap usage to the current usage.
+///
+/// Call this before a batch run to measure that run's peak allocation alone.
+#[wasm_bindgen]
+pub fn reset_peak_heap_usage() {
+    #[cfg(feature = "heap_stats")]
+    ALLOC.reset_peak();
+}
+
+#[cfg(test)]
+mod memory_tests {
+    use crate::memory::TrackingAllocator;
+    use std::alloc::{GlobalAlloc, Layout, System};
+
+    #[test]
+    fn tracks_current_and_peak_bytes() {
+        let allocator = TrackingAllocator::new(System);
+        let layout = Layout::from_size_align(1024, 8).unwrap();
+
+        unsafe {
+            let a = allocator.alloc(layout);
+            let b = allocator.alloc(layout);
+            assert_eq!(allocator.current_bytes(), 2048);
+
+            allocator.dealloc(a, layout);
+            assert_eq!(allocator.current_bytes(), 1024);
+            assert_eq!(allocator.peak_bytes(), 2048);
+
+            allocator.reset_peak();
+            assert_eq!(allocator.peak_bytes(), 1024);
+
+            let b = allocator.realloc(b, layout, 4096);
+            assert_eq!(allocator.current_bytes(), 4096);
+            assert_eq!(allocator.peak_bytes(), 4096);
+
+            allocator.dealloc(b, Layout::from_size_align(4096, 8).unwrap());
+        }
+
+        assert_eq!(allocator.current_bytes(), 0);
+        assert_eq!(allocator.allocations(), 3);
+    }
+}
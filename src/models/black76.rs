@@ -0,0 +1,332 @@
+//! The Black (1976) model for options on futures and forwards.
+//!
+//! A futures contract costs nothing to enter, so its price has no drift
+//! under the risk-neutral measure: the option is valued off the futures
+//! price with pure discounting, and no cost of carry. This is the
+//! Black-Scholes model with a payout rate equal to the interest rate, except
+//! that moving rates leaves the futures price in place, so rho only
+//! captures discounting.
+//!
+//! Some exchanges margin options like futures: the premium is not paid
+//! upfront, but settled daily through variation margin. Nothing is then
+//! financed, so futures-style options are valued without discounting, and
+//! calls and puts satisfy `C - P = F - K`.
+
+use crate::option::BSOption;
+use wasm_bindgen::prelude::*;
+
+/// How an option's premium is settled.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Settlement {
+    /// The premium is paid upfront, and values are discounted. This is the
+    /// default.
+    Premium,
+    /// The option is margined like a futures contract, and values are not
+    /// discounted.
+    FuturesStyle,
+}
+
+/// An option on a futures (or forward) price.
+///
+/// ```rust
+/// use opcalc::models::black76::Black76Option;
+///
+/// let option = Black76Option::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23);
+///
+/// let call = option.call_value();
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct Black76Option {
+    option: BSOption,
+    settlement: Settlement,
+}
+
+#[wasm_bindgen]
+impl Black76Option {
+    /// Create a `Black76Option`, whose premium is paid upfront.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `time_curr`: The current time, as a Unix timestamp in seconds.
+    /// - `time_maturity`: The option's maturity, as a Unix timestamp in
+    ///   seconds.
+    /// - `futures_price`: The price of the futures contract for the option's
+    ///   maturity.
+    /// - `strike`: The option's strike price.
+    /// - `interest`: The annual interest rate used for discounting.
+    /// - `volatility`: The volatility of the futures price.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        time_curr: u32,
+        time_maturity: u32,
+        futures_price: f64,
+        strike: f64,
+        interest: f64,
+        volatility: f64,
+    ) -> Black76Option {
+        // paying out the interest rate removes the drift
+        Black76Option {
+            option: BSOption::new(
+                time_curr,
+                time_maturity,
+                futures_price,
+                strike,
+                interest,
+                volatility,
+                interest,
+            ),
+            settlement: Settlement::Premium,
+        }
+    }
+
+    /// Get the option's call value.
+    pub fn call_value(&self) -> f64 {
+        self.settled(self.option.call_value())
+    }
+
+    /// Get the option's put value.
+    pub fn put_value(&self) -> f64 {
+        self.settled(self.option.put_value())
+    }
+
+    /// Get the option's call delta, with respect to the futures price.
+    pub fn call_delta(&self) -> f64 {
+        self.settled(self.option.call_delta())
+    }
+
+    /// Get the option's put delta, with respect to the futures price.
+    pub fn put_delta(&self) -> f64 {
+        self.settled(self.option.put_delta())
+    }
+
+    /// Get the option's gamma, with respect to the futures price. It is the
+    /// same for calls and puts.
+    pub fn gamma(&self) -> f64 {
+        self.settled(self.option.call_gamma())
+    }
+
+    /// Get the option's vega. It is the same for calls and puts.
+    pub fn vega(&self) -> f64 {
+        self.settled(self.option.call_vega())
+    }
+
+    /// Get the option's call theta, i.e. the change in its value over one
+    /// day with the futures price unchanged, for either settlement.
+    pub fn call_theta(&self) -> f64 {
+        match self.settlement {
+            Settlement::Premium => self.option.call_theta(),
+            Settlement::FuturesStyle => self.futures_style_thetas().0,
+        }
+    }
+
+    /// Get the option's put theta, i.e. the change in its value over one
+    /// day with the futures price unchanged, for either settlement.
+    pub fn put_theta(&self) -> f64 {
+        match self.settlement {
+            Settlement::Premium => self.option.put_theta(),
+            Settlement::FuturesStyle => self.futures_style_thetas().1,
+        }
+    }
+
+    /// Get the option's call rho. With the futures price unchanged, rates
+    /// only affect discounting, so rho is `-T * value` (per 1% by default,
+    /// see `GreekConventions`), and zero for futures-style options.
+    pub fn call_rho(&self) -> f64 {
+        self.discounting_rho(self.call_value())
+    }
+
+    /// Get the option's put rho. See `call_rho()`.
+    pub fn put_rho(&self) -> f64 {
+        self.discounting_rho(self.put_value())
+    }
+
+    /// Get how the option's premium is settled.
+    pub fn settlement(&self) -> Settlement {
+        self.settlement
+    }
+
+    /// Get the futures price.
+    pub fn futures_price(&self) -> f64 {
+        self.option.asset_price()
+    }
+
+    /// Get the option's strike price.
+    pub fn strike(&self) -> f64 {
+        self.option.strike()
+    }
+
+    /// Get the annual interest rate.
+    pub fn interest(&self) -> f64 {
+        self.option.interest()
+    }
+
+    /// Get the volatility of the futures price.
+    pub fn volatility(&self) -> f64 {
+        self.option.volatility()
+    }
+
+    /// Get the option's time to maturity, in years.
+    pub fn time_to_maturity(&self) -> f64 {
+        self.option.time_to_maturity()
+    }
+
+    /// Update the futures price.
+    pub fn set_futures_price(&mut self, new_futures_price: f64) {
+        self.option.set_asset_price(new_futures_price);
+    }
+
+    /// Update the volatility of the futures price.
+    pub fn set_volatility(&mut self, new_volatility: f64) {
+        self.option.set_volatility(new_volatility);
+    }
+
+    /// Update how the option's premium is settled.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `new_settlement`: `Settlement.Premium` (the default) or
+    ///      `Settlement.FuturesStyle`.
+    pub fn set_settlement(&mut self, new_settlement: Settlement) {
+        self.settlement = new_settlement;
+    }
+}
+
+impl Black76Option {
+    /// Undo the discounting of a premium-settled result for futures-style
+    /// options.
+    fn settled(&self, result: f64) -> f64 {
+        match self.settlement {
+            Settlement::Premium => result,
+            Settlement::FuturesStyle => result * self.undiscount_factor(),
+        }
+    }
+
+    fn undiscount_factor(&self) -> f64 {
+        (self.option.interest().ln_1p() * self.option.time_to_maturity()).exp()
+    }
+
+    /// The call and put thetas of the undiscounted values, repriced after
+    /// the same time bump as `BSOption::call_theta()`, and scaled to one day
+    /// passing.
+    fn futures_style_thetas(&self) -> (f64, f64) {
+        const TIMESTAMP_ONE_YEAR: f64 = 31_536_000.0;
+
+        let config = self.option.numerical_config();
+        let (call_theta, put_theta) = config.differentiate(
+            |time_curr| {
+                let later = Black76Option {
+                    option: self.option.value_at(time_curr as u32, None, None),
+                    ..*self
+                };
+                (later.call_value(), later.put_value())
+            },
+            f64::from(self.option.time_curr()),
+            f64::from(config.time_bump()),
+        );
+
+        let days_per_year = self.option.greek_conventions().days_per_year();
+        let seconds_per_day = TIMESTAMP_ONE_YEAR / days_per_year;
+        (call_theta * seconds_per_day, put_theta * seconds_per_day)
+    }
+
+    fn discounting_rho(&self, value: f64) -> f64 {
+        if self.settlement == Settlement::FuturesStyle {
+            return 0.0;
+        }
+
+        let rate_unit = self.option.greek_conventions().rate_unit();
+        -self.option.time_to_maturity() * value * rate_unit
+    }
+}
+
+#[cfg(test)]
+mod black76_tests {
+    use crate::models::black76::{Black76Option, Settlement};
+    use crate::utils::normal_cdf;
+
+    fn create_test_option() -> Black76Option {
+        Black76Option::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23)
+    }
+
+    #[test]
+    fn values_options_on_futures() {
+        let option = create_test_option();
+        let time = option.time_to_maturity();
+        let discount = (-0.005f64.ln_1p() * time).exp();
+        let d1 = ((100.0f64 / 105.0).ln() + 0.23f64.powi(2) / 2.0 * time) / (0.23 * time.sqrt());
+        let d2 = d1 - 0.23 * time.sqrt();
+
+        let call = discount * (100.0 * normal_cdf(d1) - 105.0 * normal_cdf(d2));
+        let put = discount * (105.0 * normal_cdf(-d2) - 100.0 * normal_cdf(-d1));
+        approx::assert_abs_diff_eq!(option.call_value(), call, epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(option.put_value(), put, epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(
+            option.call_delta(),
+            discount * normal_cdf(d1),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            option.put_delta(),
+            -discount * normal_cdf(-d1),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn discounts_with_rho() {
+        let option = create_test_option();
+
+        // bump the continuously compounded rate by 1 basis point
+        let bumped_interest = (0.005f64.ln_1p() + 1e-4).exp() - 1.0;
+        let bumped =
+            Black76Option::new(1606780800, 1610668800, 100.0, 105.0, bumped_interest, 0.23);
+
+        let call_rho = (bumped.call_value() - option.call_value()) / 1e-4 * 0.01;
+        let put_rho = (bumped.put_value() - option.put_value()) / 1e-4 * 0.01;
+        approx::assert_abs_diff_eq!(option.call_rho(), call_rho, epsilon = 1e-6);
+        approx::assert_abs_diff_eq!(option.put_rho(), put_rho, epsilon = 1e-6);
+    }
+
+    #[test]
+    fn values_futures_style_options_without_discounting() {
+        let premium = create_test_option();
+        let mut margined = premium;
+        margined.set_settlement(Settlement::FuturesStyle);
+
+        let time = premium.time_to_maturity();
+        let discount = (-0.005f64.ln_1p() * time).exp();
+        approx::assert_abs_diff_eq!(
+            margined.call_value() * discount,
+            premium.call_value(),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            margined.call_value() - margined.put_value(),
+            100.0 - 105.0,
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            margined.call_delta() - margined.put_delta(),
+            1.0,
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(margined.call_rho(), 0.0);
+
+        // theta against repricing a day later, like premium-settled options
+        let later = Black76Option::new(1606867200, 1610668800, 100.0, 105.0, 0.005, 0.23);
+        let mut later_margined = later;
+        later_margined.set_settlement(Settlement::FuturesStyle);
+        approx::assert_abs_diff_eq!(
+            margined.put_theta(),
+            later_margined.put_value() - margined.put_value(),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            premium.put_theta(),
+            later.put_value() - premium.put_value(),
+            epsilon = 1e-12
+        );
+    }
+}
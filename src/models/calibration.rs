@@ -0,0 +1,837 @@
+//! Calibration of model parameters to market quotes.
+//!
+//! Parameters are fitted with the Levenberg-Marquardt algorithm, which
+//! blends Gauss-Newton steps with gradient descent steps, and keeps them
+//! within their constraints by projecting them back after each step. Each
+//! step solves a damped linear least-squares problem, written as an ordinary
+//! one with one extra row per parameter.
+//!
+//! Quotes are implied volatilities. SVI smiles give implied volatilities in
+//! closed form, so they are fitted directly. For Heston, fitting them would
+//! take an implied volatility inversion per quote and per trial, so the
+//! out-of-the-money prices are fitted instead, weighted by the inverse of
+//! their Black-Scholes vegas: to first order, the weighted price errors are
+//! the implied volatility errors.
+
+use crate::models::heston::{HestonModel, HestonParameters};
+use crate::models::svi::{SviParameters, SviSmile};
+use crate::montecarlo::regression::least_squares;
+use crate::option::engine::Model;
+use crate::option::{implied, BSOption};
+use crate::transform::cos::CosMethod;
+use crate::transform::CharacteristicFunction;
+use crate::utils::normal_pdf;
+use wasm_bindgen::prelude::*;
+
+/// The number of times the damping can be raised within an iteration before
+/// the calibration is considered stuck.
+const MAX_DAMPING_INCREASES: usize = 12;
+/// The relative size of the parameter bumps of the Jacobian.
+const JACOBIAN_BUMP: f64 = 1e-6;
+
+/// A Heston calibration to implied volatility quotes, with bounds on the
+/// parameters.
+///
+/// ```rust
+/// use opcalc::models::calibration::HestonCalibration;
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 100.0, 0.005, 0.2, 0.0);
+/// let strikes = [90.0, 100.0, 110.0, 90.0, 100.0, 110.0];
+/// let (short, long) = (1_610_668_800, 1_638_316_800);
+/// let maturities = [short, short, short, long, long, long];
+/// let implied_volatilities = [0.25, 0.22, 0.2, 0.24, 0.22, 0.205];
+///
+/// let report = HestonCalibration::new()
+///     .calibrate(&option, &strikes, &maturities, &implied_volatilities);
+/// assert!(report.rmse() < 0.01);
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct HestonCalibration {
+    initial_guess: Option<HestonParameters>,
+    lower_bounds: HestonParameters,
+    upper_bounds: HestonParameters,
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+impl Default for HestonCalibration {
+    fn default() -> HestonCalibration {
+        HestonCalibration {
+            initial_guess: None,
+            lower_bounds: HestonParameters::new(1e-4, 1e-2, 1e-4, 1e-2, -0.999),
+            upper_bounds: HestonParameters::new(4.0, 20.0, 4.0, 5.0, 0.999),
+            max_iterations: 100,
+            tolerance: 1e-10,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl HestonCalibration {
+    /// Create a `HestonCalibration` of at most 100 iterations, within bounds
+    /// wide enough for most equity markets: variances up to 4, mean
+    /// reversion speeds up to 20 and volatilities of variance up to 5.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> HestonCalibration {
+        Default::default()
+    }
+
+    /// Set the parameters the calibration starts from. By default, both
+    /// variances start at the mean quoted variance, with `kappa = 1.5`,
+    /// `xi = 0.5` and `rho = -0.5`.
+    pub fn with_initial_guess(self, initial_guess: HestonParameters) -> HestonCalibration {
+        HestonCalibration {
+            initial_guess: Some(initial_guess),
+            ..self
+        }
+    }
+
+    /// Set the lowest values of the parameters.
+    pub fn with_lower_bounds(self, lower_bounds: HestonParameters) -> HestonCalibration {
+        HestonCalibration {
+            lower_bounds,
+            ..self
+        }
+    }
+
+    /// Set the highest values of the parameters.
+    pub fn with_upper_bounds(self, upper_bounds: HestonParameters) -> HestonCalibration {
+        HestonCalibration {
+            upper_bounds,
+            ..self
+        }
+    }
+
+    /// Set the maximum number of iterations.
+    pub fn with_max_iterations(self, max_iterations: usize) -> HestonCalibration {
+        HestonCalibration {
+            max_iterations,
+            ..self
+        }
+    }
+
+    /// Set the relative decrease of the sum of squared errors below which
+    /// the calibration stops.
+    pub fn with_tolerance(self, tolerance: f64) -> HestonCalibration {
+        HestonCalibration { tolerance, ..self }
+    }
+
+    /// Get the lowest values of the parameters.
+    pub fn lower_bounds(&self) -> HestonParameters {
+        self.lower_bounds
+    }
+
+    /// Get the highest values of the parameters.
+    pub fn upper_bounds(&self) -> HestonParameters {
+        self.upper_bounds
+    }
+
+    /// Get the maximum number of iterations.
+    pub fn max_iterations(&self) -> usize {
+        self.max_iterations
+    }
+
+    /// Get the relative decrease of the sum of squared errors below which
+    /// the calibration stops.
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    /// Fit the Heston parameters to implied volatility quotes.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `option`: the market inputs shared by the quotes, i.e. the asset
+    ///      price, rates and current time. Its strike, maturity and
+    ///      volatility are ignored.
+    /// - `strikes`: the quotes' strikes.
+    /// - `maturities`: the quotes' maturity timestamps, in seconds, in the
+    ///      same order.
+    /// - `implied_volatilities`: the quoted Black-Scholes implied
+    ///      volatilities, in the same order.
+    pub fn calibrate(
+        &self,
+        option: &BSOption,
+        strikes: &[f64],
+        maturities: &[u32],
+        implied_volatilities: &[f64],
+    ) -> HestonCalibrationReport {
+        crate::utils::set_panic_hook();
+        trace_scope!("HestonCalibration::calibrate");
+
+        let quotes: Vec<Quote> = strikes
+            .iter()
+            .zip(maturities)
+            .zip(implied_volatilities)
+            .map(|((&strike, &maturity), &volatility)| {
+                Quote::new(option, strike, maturity, volatility)
+            })
+            .collect();
+
+        let lower = to_array(self.lower_bounds);
+        let upper = to_array(self.upper_bounds);
+        let initial_guess = self
+            .initial_guess
+            .unwrap_or_else(|| default_heston_guess(implied_volatilities));
+
+        let fit = levenberg_marquardt(
+            to_array(initial_guess).to_vec(),
+            |parameters| weighted_errors(option, &quotes, parameters),
+            |parameters| {
+                for ((parameter, &low), &high) in parameters.iter_mut().zip(&lower).zip(&upper) {
+                    *parameter = parameter.max(low).min(high);
+                }
+            },
+            self.max_iterations,
+            self.tolerance,
+        );
+
+        let parameters = from_array(&fit.parameters);
+        HestonCalibrationReport {
+            parameters,
+            rmse: implied_volatility_rmse(option, &quotes, parameters),
+            iterations: fit.iterations,
+            converged: fit.converged,
+        }
+    }
+}
+
+/// The outcome of a `HestonCalibration`.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct HestonCalibrationReport {
+    parameters: HestonParameters,
+    rmse: f64,
+    iterations: usize,
+    converged: bool,
+}
+
+#[wasm_bindgen]
+impl HestonCalibrationReport {
+    /// Get the calibrated parameters.
+    pub fn parameters(&self) -> HestonParameters {
+        self.parameters
+    }
+
+    /// Get the root mean square error between the implied volatilities of
+    /// the calibrated model and the quoted ones. Model prices that no
+    /// volatility reaches count as `NaN`.
+    pub fn rmse(&self) -> f64 {
+        self.rmse
+    }
+
+    /// Get the number of iterations taken.
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Whether the calibration stopped at a minimum rather than after the
+    /// maximum number of iterations.
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+}
+
+/// An SVI calibration of one expiry's smile to implied volatility quotes.
+///
+/// The parameters are kept within the constraints of
+/// `SviParameters::satisfies_no_arbitrage_constraints()`.
+///
+/// ```rust
+/// use opcalc::models::calibration::SviCalibration;
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 100.0, 0.005, 0.2, 0.0);
+/// let strikes = [85.0, 90.0, 95.0, 100.0, 105.0, 110.0, 115.0];
+/// let implied_volatilities = [0.31, 0.27, 0.24, 0.22, 0.21, 0.205, 0.21];
+///
+/// let report = SviCalibration::new().calibrate(&option, &strikes, &implied_volatilities);
+/// let smile = report.smile();
+/// assert!(smile.parameters().satisfies_no_arbitrage_constraints());
+/// let volatility = smile.implied_volatility(102.5);
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct SviCalibration {
+    max_iterations: usize,
+    tolerance: f64,
+}
+
+impl Default for SviCalibration {
+    fn default() -> SviCalibration {
+        SviCalibration {
+            max_iterations: 100,
+            tolerance: 1e-10,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl SviCalibration {
+    /// Create an `SviCalibration` of at most 100 iterations.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> SviCalibration {
+        Default::default()
+    }
+
+    /// Set the maximum number of iterations.
+    pub fn with_max_iterations(self, max_iterations: usize) -> SviCalibration {
+        SviCalibration {
+            max_iterations,
+            ..self
+        }
+    }
+
+    /// Set the relative decrease of the sum of squared errors below which
+    /// the calibration stops.
+    pub fn with_tolerance(self, tolerance: f64) -> SviCalibration {
+        SviCalibration { tolerance, ..self }
+    }
+
+    /// Get the maximum number of iterations.
+    pub fn max_iterations(&self) -> usize {
+        self.max_iterations
+    }
+
+    /// Get the relative decrease of the sum of squared errors below which
+    /// the calibration stops.
+    pub fn tolerance(&self) -> f64 {
+        self.tolerance
+    }
+
+    /// Fit an SVI smile to the implied volatility quotes of one expiry.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `option`: the market inputs shared by the quotes, i.e. the asset
+    ///      price, rates and times. Its strike and volatility are ignored.
+    /// - `strikes`: the quotes' strikes.
+    /// - `implied_volatilities`: the quoted Black-Scholes implied
+    ///      volatilities, in the same order.
+    pub fn calibrate(
+        &self,
+        option: &BSOption,
+        strikes: &[f64],
+        implied_volatilities: &[f64],
+    ) -> SviCalibrationReport {
+        crate::utils::set_panic_hook();
+        trace_scope!("SviCalibration::calibrate");
+
+        let time = option.time_to_maturity();
+        let carry = option.interest().ln_1p() - option.payout_rate().ln_1p();
+        let forward = option.asset_price() * (carry * time).exp();
+        let log_moneyness: Vec<f64> = strikes
+            .iter()
+            .map(|strike| (strike / forward).ln())
+            .collect();
+        let (lowest, highest) = log_moneyness
+            .iter()
+            .fold((0.0f64, 0.0f64), |(low, high), &k| {
+                (low.min(k), high.max(k))
+            });
+
+        let fit = levenberg_marquardt(
+            default_svi_guess(&log_moneyness, implied_volatilities, time),
+            |parameters| {
+                let parameters = svi_from_slice(parameters);
+                log_moneyness
+                    .iter()
+                    .zip(implied_volatilities)
+                    .map(|(&k, volatility)| {
+                        (parameters.total_variance(k).max(0.0) / time).sqrt() - volatility
+                    })
+                    .collect()
+            },
+            |parameters| {
+                let (a, b, rho, m, sigma) = (0, 1, 2, 3, 4);
+                parameters[rho] = parameters[rho].clamp(-0.999, 0.999);
+                parameters[sigma] = parameters[sigma].max(1e-4);
+                parameters[m] = parameters[m].clamp(lowest - 1.0, highest + 1.0);
+                // Lee's bound on the wings' slopes
+                parameters[b] = parameters[b]
+                    .max(0.0)
+                    .min(2.0 / (1.0 + parameters[rho].abs()));
+                // a non-negative minimum variance
+                let minimum =
+                    parameters[b] * parameters[sigma] * (1.0 - parameters[rho].powi(2)).sqrt();
+                parameters[a] = parameters[a].max(-minimum);
+            },
+            self.max_iterations,
+            self.tolerance,
+        );
+
+        let smile = SviSmile::new(option, svi_from_slice(&fit.parameters));
+        let squared_errors: f64 = strikes
+            .iter()
+            .zip(implied_volatilities)
+            .map(|(&strike, volatility)| (smile.implied_volatility(strike) - volatility).powi(2))
+            .sum();
+
+        SviCalibrationReport {
+            smile,
+            rmse: (squared_errors / strikes.len() as f64).sqrt(),
+            iterations: fit.iterations,
+            converged: fit.converged,
+        }
+    }
+}
+
+/// The outcome of an `SviCalibration`.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct SviCalibrationReport {
+    smile: SviSmile,
+    rmse: f64,
+    iterations: usize,
+    converged: bool,
+}
+
+#[wasm_bindgen]
+impl SviCalibrationReport {
+    /// Get the calibrated smile.
+    pub fn smile(&self) -> SviSmile {
+        self.smile
+    }
+
+    /// Get the root mean square error between the smile's implied
+    /// volatilities and the quoted ones.
+    pub fn rmse(&self) -> f64 {
+        self.rmse
+    }
+
+    /// Get the number of iterations taken.
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Whether the calibration stopped at a minimum rather than after the
+    /// maximum number of iterations.
+    pub fn converged(&self) -> bool {
+        self.converged
+    }
+}
+
+/// An implied volatility quote, with its out-of-the-money price.
+struct Quote {
+    option: BSOption,
+    is_call: bool,
+    volatility: f64,
+    price: f64,
+    vega: f64,
+}
+
+impl Quote {
+    fn new(option: &BSOption, strike: f64, maturity: u32, volatility: f64) -> Quote {
+        let mut option = *option;
+        option.set_strike(strike);
+        option.set_time_maturity(maturity);
+        option.set_volatility(volatility);
+
+        let is_call = option.log_moneyness() < 0.0;
+        let price = black_scholes_value(&option, is_call);
+        let net_asset_price = option.asset_price()
+            * (-option.payout_rate().ln_1p() * option.time_to_maturity()).exp();
+        let vega = net_asset_price * normal_pdf(option.d1()) * option.time_to_maturity().sqrt();
+
+        Quote {
+            option,
+            is_call,
+            volatility,
+            price,
+            vega: vega.max(1e-8 * option.asset_price()),
+        }
+    }
+}
+
+/// The Black-Scholes value of the call or put, regardless of the option's
+/// model.
+fn black_scholes_value(option: &BSOption, is_call: bool) -> f64 {
+    let mut option = *option;
+    option.set_model(Model::black_scholes());
+    if is_call {
+        option.call_value()
+    } else {
+        option.put_value()
+    }
+}
+
+/// The Heston values of the quotes' out-of-the-money options, with the
+/// quotes of each maturity priced together.
+fn model_prices(option: &BSOption, quotes: &[Quote], parameters: HestonParameters) -> Vec<f64> {
+    let cos = CosMethod::new();
+    let mut prices = vec![0.0; quotes.len()];
+    let mut priced = vec![false; quotes.len()];
+
+    for first in 0..quotes.len() {
+        if priced[first] {
+            continue;
+        }
+
+        let maturity = quotes[first].option.time_maturity();
+        let indices: Vec<usize> = (first..quotes.len())
+            .filter(|&i| quotes[i].option.time_maturity() == maturity)
+            .collect();
+        let strikes: Vec<f64> = indices.iter().map(|&i| quotes[i].option.strike()).collect();
+
+        let mut expiry = *option;
+        expiry.set_time_maturity(maturity);
+        let model = HestonModel::new(&expiry, parameters);
+        let discount_factor = model.discount_factor();
+        let forward = model.forward();
+        let puts = cos.put_values(&model, &strikes);
+
+        for ((&i, put), strike) in indices.iter().zip(puts).zip(strikes) {
+            prices[i] = if quotes[i].is_call {
+                put + discount_factor * (forward - strike)
+            } else {
+                put
+            };
+            priced[i] = true;
+        }
+    }
+
+    prices
+}
+
+/// The quotes' price errors divided by their vegas, i.e. approximately
+/// their implied volatility errors.
+fn weighted_errors(option: &BSOption, quotes: &[Quote], parameters: &[f64]) -> Vec<f64> {
+    model_prices(option, quotes, from_array(parameters))
+        .iter()
+        .zip(quotes)
+        .map(|(price, quote)| (price - quote.price) / quote.vega)
+        .collect()
+}
+
+/// The outcome of `levenberg_marquardt()`.
+struct Fit {
+    parameters: Vec<f64>,
+    iterations: usize,
+    converged: bool,
+}
+
+/// Fit the parameters minimizing the sum of the squared `residuals`,
+/// starting from `initial_guess`. `project` moves parameters back within
+/// their constraints, and is applied after each step.
+///
+/// The fit stops after `max_iterations`, when an iteration decreases the sum
+/// by less than `tolerance` relative to it, or when no step decreases it.
+fn levenberg_marquardt<R, P>(
+    initial_guess: Vec<f64>,
+    residuals: R,
+    project: P,
+    max_iterations: usize,
+    tolerance: f64,
+) -> Fit
+where
+    R: Fn(&[f64]) -> Vec<f64>,
+    P: Fn(&mut [f64]),
+{
+    let cost = |residuals: &[f64]| residuals.iter().map(|r| r * r).sum::<f64>();
+
+    let mut parameters = initial_guess;
+    project(&mut parameters);
+    let mut current = residuals(&parameters);
+    let mut damping = 1e-3;
+    let mut iterations = 0;
+    let mut converged = false;
+
+    while iterations < max_iterations && !converged {
+        iterations += 1;
+        let jacobian = jacobian(&residuals, &project, &parameters, &current);
+        let current_cost = cost(&current);
+
+        let mut accepted = false;
+        for _ in 0..MAX_DAMPING_INCREASES {
+            let step = match damped_step(&jacobian, &current, damping) {
+                Some(step) => step,
+                None => {
+                    damping *= 10.0;
+                    continue;
+                }
+            };
+            let mut candidate: Vec<f64> = parameters.iter().zip(step).map(|(p, s)| p + s).collect();
+            project(&mut candidate);
+
+            let candidate_residuals = residuals(&candidate);
+            let candidate_cost = cost(&candidate_residuals);
+            if candidate_cost < current_cost {
+                converged = current_cost - candidate_cost <= tolerance * current_cost;
+                parameters = candidate;
+                current = candidate_residuals;
+                damping /= 10.0;
+                accepted = true;
+                break;
+            }
+            damping *= 10.0;
+        }
+
+        // no step improves the fit: a (possibly constrained) minimum
+        if !accepted {
+            converged = true;
+        }
+    }
+
+    Fit {
+        parameters,
+        iterations,
+        converged,
+    }
+}
+
+/// The derivatives of the residuals by the parameters, by forward
+/// differences, or backward ones where `project` would pull a forward bump
+/// back, e.g. at upper bounds. One row per residual.
+fn jacobian<R, P>(residuals: &R, project: &P, parameters: &[f64], current: &[f64]) -> Vec<Vec<f64>>
+where
+    R: Fn(&[f64]) -> Vec<f64>,
+    P: Fn(&mut [f64]),
+{
+    let size = parameters.len();
+    let mut jacobian = vec![vec![0.0; size]; current.len()];
+
+    for j in 0..size {
+        let mut bump = JACOBIAN_BUMP * parameters[j].abs().max(1e-2);
+        let mut projected = parameters.to_vec();
+        projected[j] += bump;
+        project(&mut projected);
+        if projected[j] < parameters[j] + bump {
+            bump = -bump;
+        }
+
+        let mut bumped = parameters.to_vec();
+        bumped[j] += bump;
+        let bumped_residuals = residuals(&bumped);
+        for ((row, bumped_residual), residual) in
+            jacobian.iter_mut().zip(bumped_residuals).zip(current)
+        {
+            row[j] = (bumped_residual - residual) / bump;
+        }
+    }
+
+    jacobian
+}
+
+/// The Levenberg-Marquardt step, minimizing
+/// `|J * step + r|^2 + damping * |D * step|^2` with `D` the scale of each
+/// column of `J`, as the least-squares solution of `J` stacked over
+/// `sqrt(damping) * D`.
+fn damped_step(jacobian: &[Vec<f64>], residuals: &[f64], damping: f64) -> Option<Vec<f64>> {
+    let size = jacobian.first()?.len();
+    let mut design = jacobian.to_vec();
+    let mut targets: Vec<f64> = residuals.iter().map(|r| -r).collect();
+
+    for j in 0..size {
+        let scale: f64 = jacobian.iter().map(|row| row[j].powi(2)).sum::<f64>();
+        let mut row = vec![0.0; size];
+        row[j] = (damping * scale.max(1e-12)).sqrt();
+        design.push(row);
+        targets.push(0.0);
+    }
+
+    least_squares(&design, &targets)
+}
+
+/// The root mean square error of the model's implied volatilities.
+fn implied_volatility_rmse(
+    option: &BSOption,
+    quotes: &[Quote],
+    parameters: HestonParameters,
+) -> f64 {
+    let squared_errors: f64 = model_prices(option, quotes, parameters)
+        .iter()
+        .zip(quotes)
+        .map(|(&price, quote)| {
+            let mut expiry = quote.option;
+            expiry.set_model(Model::black_scholes());
+            let volatility = implied::implied_vols(
+                &expiry,
+                &[quote.option.strike()],
+                &[price],
+                &[quote.is_call as u8],
+            )[0];
+            (volatility - quote.volatility).powi(2)
+        })
+        .sum();
+
+    (squared_errors / quotes.len() as f64).sqrt()
+}
+
+/// Start both variances at the mean quoted variance.
+fn default_heston_guess(implied_volatilities: &[f64]) -> HestonParameters {
+    let variance = implied_volatilities.iter().map(|v| v * v).sum::<f64>()
+        / implied_volatilities.len().max(1) as f64;
+
+    HestonParameters::new(variance, 1.5, variance, 0.5, -0.5)
+}
+
+/// Start with a symmetric smile, centered on the quote with the lowest
+/// variance and matching it.
+fn default_svi_guess(log_moneyness: &[f64], implied_volatilities: &[f64], time: f64) -> Vec<f64> {
+    let (center, lowest_variance) = log_moneyness
+        .iter()
+        .zip(implied_volatilities)
+        .map(|(&k, volatility)| (k, volatility * volatility * time))
+        .fold((0.0, f64::INFINITY), |lowest, quote| {
+            if quote.1 < lowest.1 {
+                quote
+            } else {
+                lowest
+            }
+        });
+    let (b, sigma) = (0.1, 0.1);
+
+    vec![lowest_variance - b * sigma, b, 0.0, center, sigma]
+}
+
+fn svi_from_slice(parameters: &[f64]) -> SviParameters {
+    SviParameters::new(
+        parameters[0],
+        parameters[1],
+        parameters[2],
+        parameters[3],
+        parameters[4],
+    )
+}
+
+fn to_array(parameters: HestonParameters) -> [f64; 5] {
+    [
+        parameters.v0(),
+        parameters.kappa(),
+        parameters.theta(),
+        parameters.xi(),
+        parameters.rho(),
+    ]
+}
+
+fn from_array(parameters: &[f64]) -> HestonParameters {
+    HestonParameters::new(
+        parameters[0],
+        parameters[1],
+        parameters[2],
+        parameters[3],
+        parameters[4],
+    )
+}
+
+#[cfg(test)]
+mod calibration_tests {
+    use crate::models::calibration::{HestonCalibration, SviCalibration};
+    use crate::models::heston::{HestonModel, HestonParameters};
+    use crate::models::svi::{SviParameters, SviSmile};
+    use crate::option::BSOption;
+
+    const MATURITIES: [u32; 3] = [1_610_668_800, 1_622_505_600, 1_638_316_800];
+    const STRIKES: [f64; 5] = [80.0, 90.0, 100.0, 110.0, 120.0];
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 100.0, 0.005, 0.2, 0.01)
+    }
+
+    /// The implied volatilities of `parameters`, for each maturity and strike.
+    fn quotes(parameters: HestonParameters) -> (Vec<f64>, Vec<u32>, Vec<f64>) {
+        let mut strikes = Vec::new();
+        let mut maturities = Vec::new();
+        let mut implied_volatilities = Vec::new();
+        for &maturity in &MATURITIES {
+            let mut option = create_test_option();
+            option.set_time_maturity(maturity);
+            let prices: Vec<f64> = STRIKES
+                .iter()
+                .map(|&strike| {
+                    let mut quoted = option;
+                    quoted.set_strike(strike);
+                    HestonModel::new(&quoted, parameters).put_value()
+                })
+                .collect();
+
+            strikes.extend_from_slice(&STRIKES);
+            maturities.extend(STRIKES.iter().map(|_| maturity));
+            implied_volatilities
+                .extend(option.chain_implied_volatilities(&STRIKES, &prices, &[0; 5]));
+        }
+
+        (strikes, maturities, implied_volatilities)
+    }
+
+    #[test]
+    fn recovers_the_parameters_of_model_quotes() {
+        let parameters = HestonParameters::new(0.04, 2.0, 0.06, 0.5, -0.7);
+        let (strikes, maturities, implied_volatilities) = quotes(parameters);
+
+        let report = HestonCalibration::new().calibrate(
+            &create_test_option(),
+            &strikes,
+            &maturities,
+            &implied_volatilities,
+        );
+
+        assert!(report.converged());
+        assert!(report.rmse() < 1e-4);
+        let fitted = report.parameters();
+        approx::assert_abs_diff_eq!(fitted.v0(), 0.04, epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(fitted.theta(), 0.06, epsilon = 5e-3);
+        approx::assert_abs_diff_eq!(fitted.rho(), -0.7, epsilon = 5e-2);
+    }
+
+    #[test]
+    fn stays_within_bounds() {
+        let parameters = HestonParameters::new(0.04, 2.0, 0.06, 0.5, -0.7);
+        let (strikes, maturities, implied_volatilities) = quotes(parameters);
+
+        let report = HestonCalibration::new()
+            .with_upper_bounds(HestonParameters::new(4.0, 20.0, 4.0, 5.0, -0.8))
+            .with_max_iterations(20)
+            .calibrate(
+                &create_test_option(),
+                &strikes,
+                &maturities,
+                &implied_volatilities,
+            );
+
+        assert!(report.parameters().rho() <= -0.8);
+        assert!(report.iterations() <= 20);
+        assert!(report.rmse() > 1e-4);
+    }
+
+    #[test]
+    fn recovers_svi_parameters() {
+        let option = create_test_option();
+        let parameters = SviParameters::new(0.004, 0.05, -0.5, 0.02, 0.1);
+        let smile = SviSmile::new(&option, parameters);
+        let strikes: Vec<f64> = (0..13).map(|i| 70.0 + 5.0 * i as f64).collect();
+        let implied_volatilities: Vec<f64> = strikes
+            .iter()
+            .map(|&strike| smile.implied_volatility(strike))
+            .collect();
+
+        let report = SviCalibration::new().calibrate(&option, &strikes, &implied_volatilities);
+
+        assert!(report.converged());
+        assert!(report.rmse() < 1e-6);
+        let fitted = report.smile().parameters();
+        approx::assert_abs_diff_eq!(fitted.a(), 0.004, epsilon = 1e-4);
+        approx::assert_abs_diff_eq!(fitted.b(), 0.05, epsilon = 1e-4);
+        approx::assert_abs_diff_eq!(fitted.rho(), -0.5, epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(fitted.m(), 0.02, epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(fitted.sigma(), 0.1, epsilon = 1e-3);
+    }
+
+    #[test]
+    fn keeps_svi_smiles_free_of_arbitrage() {
+        // wings too steep for Lee's moment formula
+        let option = create_test_option();
+        let strikes = [70.0, 85.0, 100.0, 115.0, 130.0];
+        let implied_volatilities = [3.0, 1.0, 0.2, 1.0, 3.0];
+
+        let report = SviCalibration::new().calibrate(&option, &strikes, &implied_volatilities);
+
+        assert!(report
+            .smile()
+            .parameters()
+            .satisfies_no_arbitrage_constraints());
+        assert!(report.rmse() > 0.1);
+    }
+}
@@ -0,0 +1,207 @@
+//! Employee stock options, after Hull & White (2004), "How to value
+//! employee stock options".
+//!
+//! Employee stock options are American calls with three twists, which a
+//! Cox-Ross-Rubinstein tree captures node by node:
+//!
+//! - They cannot be exercised before the vesting date, and are forfeited if
+//!   the employee leaves before then.
+//! - Employees tend to exercise early, once the asset price reaches a
+//!   multiple of the strike.
+//! - Employees leave at a constant rate per year. Leaving after vesting
+//!   forces exercise, or forfeiture if the option is out of the money.
+
+use crate::option::BSOption;
+use wasm_bindgen::prelude::*;
+
+const SECONDS_PER_YEAR: f64 = 31_536_000.0;
+
+/// An employee stock option on the asset, strike and maturity of a
+/// `BSOption`.
+///
+/// ```rust
+/// use opcalc::models::employee::EmployeeStockOption;
+/// use opcalc::option::BSOption;
+///
+/// // a ten-year grant at the money
+/// let option = BSOption::new(1_606_780_800, 1_922_140_800, 50.0, 50.0, 0.05, 0.3, 0.0);
+///
+/// // vesting after three years, exercised at twice the strike, 5% leavers a year
+/// let grant = EmployeeStockOption::new(&option, 1_701_388_800, 2.0, 0.05);
+/// assert!(grant.value() < option.call_value());
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct EmployeeStockOption {
+    option: BSOption,
+    vesting_time: u32,
+    exercise_multiple: f64,
+    exit_rate: f64,
+    steps: usize,
+}
+
+#[wasm_bindgen]
+impl EmployeeStockOption {
+    /// Create an `EmployeeStockOption`, valued on a 500-step tree.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `option`: The grant's asset, strike, maturity and market inputs.
+    /// - `vesting_time`: The vesting date, as a Unix timestamp in seconds.
+    /// - `exercise_multiple`: The multiple of the strike at which the
+    ///   employee exercises early, e.g. `2.0`. Use `f64::INFINITY` for an
+    ///   employee who only exercises when leaving or at maturity.
+    /// - `exit_rate`: The annual rate at which employees leave, e.g. `0.05`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        option: &BSOption,
+        vesting_time: u32,
+        exercise_multiple: f64,
+        exit_rate: f64,
+    ) -> EmployeeStockOption {
+        EmployeeStockOption {
+            option: *option,
+            vesting_time,
+            exercise_multiple,
+            exit_rate,
+            steps: 500,
+        }
+    }
+
+    /// Set the number of time steps of the tree. At least one step is
+    /// always used.
+    pub fn with_steps(self, steps: usize) -> EmployeeStockOption {
+        EmployeeStockOption {
+            steps: steps.max(1),
+            ..self
+        }
+    }
+
+    /// Get the vesting date, as a Unix timestamp in seconds.
+    pub fn vesting_time(&self) -> u32 {
+        self.vesting_time
+    }
+
+    /// Get the multiple of the strike at which the employee exercises.
+    pub fn exercise_multiple(&self) -> f64 {
+        self.exercise_multiple
+    }
+
+    /// Get the annual rate at which employees leave.
+    pub fn exit_rate(&self) -> f64 {
+        self.exit_rate
+    }
+
+    /// Get the number of time steps of the tree.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Get the option's value per granted option.
+    pub fn value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("EmployeeStockOption::value");
+
+        let option = &self.option;
+        let (asset_price, strike) = (option.asset_price(), option.strike());
+        let time = option.time_to_maturity();
+        if time <= 0.0 {
+            return (asset_price - strike).max(0.0);
+        }
+
+        let steps = self.steps;
+        let dt = time / steps as f64;
+        let rate = option.interest().ln_1p();
+        let carry = rate - option.payout_rate().ln_1p();
+        let up = (option.volatility() * dt.sqrt()).exp();
+        let down = 1.0 / up;
+        let probability_up = ((carry * dt).exp() - down) / (up - down);
+        let discount = (-rate * dt).exp();
+        let exit_probability = 1.0 - (-self.exit_rate * dt).exp();
+
+        let vesting_years =
+            (self.vesting_time as f64 - option.time_curr() as f64) / SECONDS_PER_YEAR;
+        let is_vested = |step: usize| step as f64 * dt >= vesting_years - dt / 2.0;
+        let exercise_price = self.exercise_multiple * strike;
+        let price = |step: usize, ups: usize| {
+            asset_price * up.powi(ups as i32) * down.powi((step - ups) as i32)
+        };
+
+        // at maturity, vested options are exercised if in the money
+        let mut values: Vec<f64> = (0..=steps)
+            .map(|ups| {
+                if is_vested(steps) {
+                    (price(steps, ups) - strike).max(0.0)
+                } else {
+                    0.0
+                }
+            })
+            .collect();
+
+        for step in (0..steps).rev() {
+            let vested = is_vested(step);
+            for ups in 0..=step {
+                let price = price(step, ups);
+                let payoff = (price - strike).max(0.0);
+
+                values[ups] = if vested && price >= exercise_price {
+                    payoff
+                } else {
+                    let continuation = discount
+                        * (probability_up * values[ups + 1] + (1.0 - probability_up) * values[ups]);
+                    let on_exit = if vested { payoff } else { 0.0 };
+                    exit_probability * on_exit + (1.0 - exit_probability) * continuation
+                };
+            }
+        }
+
+        values[0]
+    }
+}
+
+#[cfg(test)]
+mod employee_tests {
+    use crate::models::employee::EmployeeStockOption;
+    use crate::option::american::BinomialTree;
+    use crate::option::BSOption;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1922140800, 50.0, 50.0, 0.05, 0.3, 0.02)
+    }
+
+    #[test]
+    fn values_vested_options_without_exits_like_european_calls() {
+        // never exercised early, the grant is held until maturity
+        let option = create_test_option();
+        let grant = EmployeeStockOption::new(&option, 1606780800, f64::INFINITY, 0.0);
+
+        approx::assert_abs_diff_eq!(grant.value(), option.call_value(), epsilon = 2e-2);
+        assert!(grant.value() < BinomialTree::new().with_steps(500).call_value(&option));
+    }
+
+    #[test]
+    fn loses_value_to_vesting_exits_and_early_exercise() {
+        let option = create_test_option();
+        let held = EmployeeStockOption::new(&option, 1606780800, f64::INFINITY, 0.0);
+        let exercised_early = EmployeeStockOption::new(&option, 1606780800, 2.0, 0.0);
+        let leaving = EmployeeStockOption::new(&option, 1606780800, 2.0, 0.05);
+        let vesting = EmployeeStockOption::new(&option, 1701388800, 2.0, 0.05);
+
+        assert!(exercised_early.value() < held.value());
+        assert!(leaving.value() < exercised_early.value());
+        assert!(vesting.value() < leaving.value());
+    }
+
+    #[test]
+    fn exercises_at_the_multiple() {
+        let mut option = create_test_option();
+        option.set_asset_price(110.0);
+
+        let grant = EmployeeStockOption::new(&option, 1606780800, 2.0, 0.05);
+        approx::assert_abs_diff_eq!(grant.value(), 60.0, epsilon = 1e-12);
+
+        // unvested options wait, whatever the price
+        let unvested = EmployeeStockOption::new(&option, 1701388800, 2.0, 0.05);
+        assert!(unvested.value() < 60.0);
+    }
+}
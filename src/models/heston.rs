@@ -0,0 +1,307 @@
+//! The Heston (1993) stochastic volatility model.
+//!
+//! The variance follows a mean-reverting square-root process correlated
+//! with the asset price:
+//!
+//! ```text
+//! dS = (r - q) * S * dt + sqrt(v) * S * dW1
+//! dv = kappa * (theta - v) * dt + xi * sqrt(v) * dW2,   dW1 * dW2 = rho * dt
+//! ```
+//!
+//! European values are computed semi-analytically from the characteristic
+//! function of the log asset price, which is evaluated in the "little trap"
+//! form of Albrecher et al. (2007) to avoid branch cut discontinuities of the
+//! complex logarithm.
+
+use crate::option::BSOption;
+use crate::transform::{CharacteristicFunction, Complex64};
+use std::f64::consts::PI;
+use wasm_bindgen::prelude::*;
+
+/// The upper limit of the probability integrals. The integrands decay
+/// exponentially, so the tail beyond it is negligible.
+const INTEGRATION_LIMIT: f64 = 200.0;
+/// The number of Simpson intervals the probability integrals are split into.
+const INTEGRATION_INTERVALS: usize = 4000;
+
+/// The parameters of the Heston model.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HestonParameters {
+    v0: f64,
+    kappa: f64,
+    theta: f64,
+    xi: f64,
+    rho: f64,
+}
+
+#[wasm_bindgen]
+impl HestonParameters {
+    /// Create `HestonParameters`.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `v0`: The initial variance, e.g. `0.04` for a volatility of 20%.
+    /// - `kappa`: The speed at which the variance reverts to `theta`.
+    /// - `theta`: The long-run variance.
+    /// - `xi`: The volatility of the variance.
+    /// - `rho`: The correlation between the asset price and its variance,
+    ///   between `-1` and `1`. Negative values produce the downward sloping
+    ///   skew typical of equities.
+    #[wasm_bindgen(constructor)]
+    pub fn new(v0: f64, kappa: f64, theta: f64, xi: f64, rho: f64) -> HestonParameters {
+        HestonParameters {
+            v0,
+            kappa,
+            theta,
+            xi,
+            rho,
+        }
+    }
+
+    /// Get the initial variance.
+    pub fn v0(&self) -> f64 {
+        self.v0
+    }
+
+    /// Get the speed of mean reversion of the variance.
+    pub fn kappa(&self) -> f64 {
+        self.kappa
+    }
+
+    /// Get the long-run variance.
+    pub fn theta(&self) -> f64 {
+        self.theta
+    }
+
+    /// Get the volatility of the variance.
+    pub fn xi(&self) -> f64 {
+        self.xi
+    }
+
+    /// Get the correlation between the asset price and its variance.
+    pub fn rho(&self) -> f64 {
+        self.rho
+    }
+
+    /// Check the Feller condition `2 * kappa * theta > xi^2`, under which the
+    /// variance never reaches zero.
+    pub fn satisfies_feller_condition(&self) -> bool {
+        2.0 * self.kappa * self.theta > self.xi.powi(2)
+    }
+}
+
+/// An option priced under the Heston model.
+///
+/// The asset price, strike, rates and times are taken from a `BSOption`,
+/// whose volatility is ignored. Like the option's `CharacteristicFunction`
+/// implementation, the model ignores its shift.
+///
+/// ```rust
+/// use opcalc::models::heston::{HestonModel, HestonParameters};
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+/// let parameters = HestonParameters::new(0.04, 1.5, 0.05, 0.6, -0.7);
+///
+/// let call = HestonModel::new(&option, parameters).call_value();
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct HestonModel {
+    option: BSOption,
+    parameters: HestonParameters,
+}
+
+#[wasm_bindgen]
+impl HestonModel {
+    /// Create a `HestonModel` for the market inputs of `option`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(option: &BSOption, parameters: HestonParameters) -> HestonModel {
+        HestonModel {
+            option: *option,
+            parameters,
+        }
+    }
+
+    /// Get the option providing the market inputs.
+    pub fn option(&self) -> BSOption {
+        self.option
+    }
+
+    /// Get the model parameters.
+    pub fn parameters(&self) -> HestonParameters {
+        self.parameters
+    }
+
+    /// Get the value of the call option.
+    pub fn call_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("HestonModel::call_value");
+
+        let strike = self.option.strike();
+        if self.option.time_to_maturity() <= 0.0 {
+            return (self.option.asset_price() - strike).max(0.0);
+        }
+
+        // Gil-Pelaez: the probabilities of exercise under the stock and the
+        // money market measures
+        let forward = self.forward();
+        let log_strike = strike.ln();
+        let i = Complex64::new(0.0, 1.0);
+        let (stock_probability, exercise_probability) = integrate(|u| {
+            let phase = (-i * u * log_strike).exp() / (i * u);
+            let stock = phase * self.log_price_cf(Complex64::new(u, -1.0)) / forward;
+            let exercise = phase * self.log_price_cf(Complex64::new(u, 0.0));
+            (stock.re, exercise.re)
+        });
+
+        let discount_factor = self.discount_factor();
+        let call = discount_factor
+            * (forward * (0.5 + stock_probability / PI)
+                - strike * (0.5 + exercise_probability / PI));
+
+        call.max(0.0)
+    }
+
+    /// Get the value of the put option, derived from the call value through
+    /// put-call parity.
+    pub fn put_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("HestonModel::put_value");
+
+        let discount_factor = self.discount_factor();
+        let put = self.call_value() - discount_factor * (self.forward() - self.option.strike());
+
+        put.max(0.0)
+    }
+}
+
+impl CharacteristicFunction for HestonModel {
+    fn log_price_cf(&self, u: Complex64) -> Complex64 {
+        let HestonParameters {
+            v0,
+            kappa,
+            theta,
+            xi,
+            rho,
+        } = self.parameters;
+        let time = self.option.time_to_maturity();
+        let carry = self.option.interest().ln_1p() - self.option.payout_rate().ln_1p();
+
+        let i = Complex64::new(0.0, 1.0);
+        let beta = kappa - rho * xi * i * u;
+        let d = (beta * beta + xi * xi * (i * u + u * u)).sqrt();
+        let g = (beta - d) / (beta + d);
+        let decay = (-d * time).exp();
+
+        let drift = i * u * (self.option.asset_price().ln() + carry * time);
+        let mean_reversion = kappa * theta / (xi * xi)
+            * ((beta - d) * time - 2.0 * ((1.0 - g * decay) / (1.0 - g)).ln());
+        let variance = v0 * (beta - d) / (xi * xi) * (1.0 - decay) / (1.0 - g * decay);
+
+        (drift + mean_reversion + variance).exp()
+    }
+
+    fn discount_factor(&self) -> f64 {
+        (-self.option.interest().ln_1p() * self.option.time_to_maturity()).exp()
+    }
+
+    fn forward(&self) -> f64 {
+        let carry = self.option.interest().ln_1p() - self.option.payout_rate().ln_1p();
+        self.option.asset_price() * (carry * self.option.time_to_maturity()).exp()
+    }
+}
+
+/// Integrate both components of `f` over `(0, INTEGRATION_LIMIT]` with
+/// Simpson's rule. The integrands of the probability integrals have a finite
+/// limit at zero but cannot be evaluated there, so the first node is moved
+/// just off zero.
+fn integrate<F: Fn(f64) -> (f64, f64)>(f: F) -> (f64, f64) {
+    let width = INTEGRATION_LIMIT / INTEGRATION_INTERVALS as f64;
+
+    (0..=INTEGRATION_INTERVALS).fold((0.0, 0.0), |(first, second), k| {
+        let weight = if k == 0 || k == INTEGRATION_INTERVALS {
+            1.0
+        } else if k % 2 == 1 {
+            4.0
+        } else {
+            2.0
+        };
+        let (a, b) = f((k as f64 * width).max(1e-8));
+
+        (
+            first + weight * a * width / 3.0,
+            second + weight * b * width / 3.0,
+        )
+    })
+}
+
+#[cfg(test)]
+mod heston_tests {
+    use crate::models::heston::{HestonModel, HestonParameters};
+    use crate::option::BSOption;
+    use crate::transform::cos::CosMethod;
+
+    /// One year to maturity, without rates.
+    fn create_test_option(strike: f64) -> BSOption {
+        BSOption::new(
+            1610668800 - 31_536_000,
+            1610668800,
+            100.0,
+            strike,
+            0.0,
+            0.2,
+            0.0,
+        )
+    }
+
+    #[test]
+    fn matches_reference_values() {
+        // Fang & Oosterlee (2008), section 5.2
+        let parameters = HestonParameters::new(0.0175, 1.5768, 0.0398, 0.5751, -0.5711);
+        let model = HestonModel::new(&create_test_option(100.0), parameters);
+
+        approx::assert_abs_diff_eq!(model.call_value(), 5.785155450, epsilon = 1e-6);
+        approx::assert_abs_diff_eq!(model.put_value(), 5.785155450, epsilon = 1e-6);
+        assert!(!parameters.satisfies_feller_condition());
+    }
+
+    #[test]
+    fn reduces_to_black_scholes_without_volatility_of_variance() {
+        let parameters = HestonParameters::new(0.0529, 2.0, 0.0529, 1e-4, -0.5);
+
+        for &strike in &[80.0, 100.0, 120.0] {
+            let option = BSOption::new(1606780800, 1610668800, 100.0, strike, 0.005, 0.23, 0.01);
+            let model = HestonModel::new(&option, parameters);
+
+            approx::assert_abs_diff_eq!(model.call_value(), option.call_value(), epsilon = 1e-4);
+            approx::assert_abs_diff_eq!(model.put_value(), option.put_value(), epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn matches_transform_pricers() {
+        let parameters = HestonParameters::new(0.04, 1.5, 0.05, 0.6, -0.7);
+        let strikes = [80.0, 100.0, 120.0];
+        let puts = CosMethod::new().put_values(
+            &HestonModel::new(&create_test_option(100.0), parameters),
+            &strikes,
+        );
+
+        for (put, &strike) in puts.iter().zip(&strikes) {
+            let model = HestonModel::new(&create_test_option(strike), parameters);
+            approx::assert_abs_diff_eq!(model.put_value(), put, epsilon = 1e-6);
+        }
+
+        // the negative correlation skews implied volatilities downwards
+        let low = HestonModel::new(&create_test_option(80.0), parameters);
+        let high = HestonModel::new(&create_test_option(120.0), parameters);
+        let mut low_option = create_test_option(80.0);
+        let mut high_option = create_test_option(120.0);
+        low_option.set_volatility(0.2);
+        high_option.set_volatility(0.2);
+        assert!(low.put_value() > low_option.put_value());
+        assert!(high.call_value() < high_option.call_value());
+    }
+}
@@ -0,0 +1,15 @@
+//! Pricing models beyond flat-volatility Black-Scholes.
+//!
+//! Models take their market inputs (asset price, strike, rates and times)
+//! from a `BSOption`, and add their own parameters on top. Models with a
+//! known characteristic function implement `CharacteristicFunction`, so they
+//! can also be priced with the transform-based pricers in `transform`.
+
+pub mod black76;
+pub mod calibration;
+pub mod employee;
+pub mod heston;
+pub mod quanto;
+pub mod sabr;
+pub mod svi;
+pub mod warrant;
@@ -0,0 +1,179 @@
+//! Quanto options, paying in a different currency than the asset's.
+//!
+//! A quanto option pays its foreign-currency payoff in the domestic
+//! currency, at a rate fixed in advance: one unit of the foreign currency
+//! pays one unit of the domestic currency. Measured in the domestic
+//! currency, the asset's drift picks up the covariance between its returns
+//! and the exchange rate's, so the option is a Black-Scholes option
+//! discounted at the domestic rate, on an asset growing at
+//! `r_foreign - q - rho * vol * vol_fx`.
+
+use crate::option::BSOption;
+use wasm_bindgen::prelude::*;
+
+/// A quanto option on the asset, strike and maturity of a `BSOption`, whose
+/// interest rate is the foreign (asset currency) rate.
+///
+/// ```rust
+/// use opcalc::models::quanto::QuantoOption;
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+///
+/// // paid in a currency with 2% rates, 8% FX volatility and 30% correlation
+/// let quanto = QuantoOption::new(&option, 0.02, 0.08, 0.3);
+/// let call = quanto.call_value();
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct QuantoOption {
+    option: BSOption,
+    domestic_interest: f64,
+    fx_volatility: f64,
+    correlation: f64,
+}
+
+#[wasm_bindgen]
+impl QuantoOption {
+    /// Create a `QuantoOption`.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `option`: The option in the asset's currency, with the foreign
+    ///   interest rate.
+    /// - `domestic_interest`: The annual interest rate of the payout
+    ///   currency, used for discounting.
+    /// - `fx_volatility`: The volatility of the exchange rate, in domestic
+    ///   currency per unit of foreign currency.
+    /// - `correlation`: The correlation between the asset's returns and the
+    ///   exchange rate's.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        option: &BSOption,
+        domestic_interest: f64,
+        fx_volatility: f64,
+        correlation: f64,
+    ) -> QuantoOption {
+        QuantoOption {
+            option: *option,
+            domestic_interest,
+            fx_volatility,
+            correlation,
+        }
+    }
+
+    /// Get the domestic interest rate.
+    pub fn domestic_interest(&self) -> f64 {
+        self.domestic_interest
+    }
+
+    /// Get the volatility of the exchange rate.
+    pub fn fx_volatility(&self) -> f64 {
+        self.fx_volatility
+    }
+
+    /// Get the correlation between the asset's and the exchange rate's
+    /// returns.
+    pub fn correlation(&self) -> f64 {
+        self.correlation
+    }
+
+    /// Get the quanto drift correction `-rho * vol * vol_fx`, as a
+    /// continuously compounded rate added to the asset's drift.
+    pub fn drift_adjustment(&self) -> f64 {
+        -self.correlation * self.option.volatility() * self.fx_volatility
+    }
+
+    /// Get the equivalent `BSOption`, discounted at the domestic rate with a
+    /// payout rate that reproduces the quanto drift. Its values, deltas and
+    /// gammas are the quanto option's; its vega ignores that the drift
+    /// correction depends on the asset's volatility.
+    pub fn adjusted_option(&self) -> BSOption {
+        let foreign_rate = self.option.interest().ln_1p();
+        let domestic_rate = self.domestic_interest.ln_1p();
+        let payout_rate = self.option.payout_rate().ln_1p();
+
+        // r_domestic - q_adjusted = r_foreign - q - rho * vol * vol_fx
+        let adjusted_payout = domestic_rate - foreign_rate + payout_rate - self.drift_adjustment();
+
+        let mut adjusted = self.option;
+        adjusted.set_interest(self.domestic_interest);
+        adjusted.set_payout_rate(adjusted_payout.exp_m1());
+        adjusted
+    }
+
+    /// Get the call's value, in the domestic currency.
+    pub fn call_value(&self) -> f64 {
+        self.adjusted_option().call_value()
+    }
+
+    /// Get the put's value, in the domestic currency.
+    pub fn put_value(&self) -> f64 {
+        self.adjusted_option().put_value()
+    }
+
+    /// Get the call's delta, with respect to the asset price.
+    pub fn call_delta(&self) -> f64 {
+        self.adjusted_option().call_delta()
+    }
+
+    /// Get the put's delta, with respect to the asset price.
+    pub fn put_delta(&self) -> f64 {
+        self.adjusted_option().put_delta()
+    }
+
+    /// Get the option's gamma, with respect to the asset price. It is the
+    /// same for calls and puts.
+    pub fn gamma(&self) -> f64 {
+        self.adjusted_option().call_gamma()
+    }
+}
+
+#[cfg(test)]
+mod quanto_tests {
+    use crate::models::quanto::QuantoOption;
+    use crate::option::BSOption;
+    use crate::utils::normal_cdf;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.01)
+    }
+
+    #[test]
+    fn values_uncorrelated_quantos_at_equal_rates_like_vanilla_options() {
+        let option = create_test_option();
+        let quanto = QuantoOption::new(&option, 0.005, 0.1, 0.0);
+
+        approx::assert_abs_diff_eq!(quanto.call_value(), option.call_value(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(quanto.put_value(), option.put_value(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn corrects_the_forward() {
+        let option = create_test_option();
+        let quanto = QuantoOption::new(&option, 0.03, 0.1, 0.4);
+
+        let time = option.time_to_maturity();
+        let drift = 0.005f64.ln_1p() - 0.01f64.ln_1p() - 0.4 * 0.23 * 0.1;
+        let forward = 100.0 * (drift * time).exp();
+        let discount_factor = (-0.03f64.ln_1p() * time).exp();
+        let d1 = ((forward / 105.0).ln() + 0.23f64.powi(2) / 2.0 * time) / (0.23 * time.sqrt());
+        let d2 = d1 - 0.23 * time.sqrt();
+
+        approx::assert_abs_diff_eq!(
+            quanto.call_value(),
+            discount_factor * (forward * normal_cdf(d1) - 105.0 * normal_cdf(d2)),
+            epsilon = 1e-10
+        );
+        approx::assert_abs_diff_eq!(
+            quanto.call_value() - quanto.put_value(),
+            discount_factor * (forward - 105.0),
+            epsilon = 1e-10
+        );
+
+        // positive correlation lowers the asset's drift in domestic terms
+        let uncorrelated = QuantoOption::new(&option, 0.03, 0.1, 0.0);
+        assert!(quanto.call_value() < uncorrelated.call_value());
+        assert!(quanto.put_value() > uncorrelated.put_value());
+    }
+}
@@ -0,0 +1,246 @@
+//! The SABR stochastic volatility model.
+//!
+//! The forward price and its volatility follow
+//!
+//! ```text
+//! dF = a * F^beta * dW1
+//! da = nu * a * dW2,   dW1 * dW2 = rho * dt
+//! ```
+//!
+//! starting from `a = alpha`. Rather than being priced directly, options are
+//! priced with Black-Scholes at the implied volatility from the asymptotic
+//! expansion of Hagan et al. (2002), "Managing smile risk". Each strike gets
+//! its own volatility, so a single set of parameters prices a whole smile
+//! consistently.
+
+use crate::option::BSOption;
+use wasm_bindgen::prelude::*;
+
+/// The parameters of the SABR model.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SabrParameters {
+    alpha: f64,
+    beta: f64,
+    rho: f64,
+    nu: f64,
+}
+
+#[wasm_bindgen]
+impl SabrParameters {
+    /// Create `SabrParameters`.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `alpha`: The initial level of volatility. With `beta = 1`, this is
+    ///   roughly the at-the-money volatility.
+    /// - `beta`: The elasticity of the volatility to the forward price,
+    ///   between `0` (normal) and `1` (lognormal).
+    /// - `rho`: The correlation between the forward price and its
+    ///   volatility, between `-1` and `1`. Controls the skew.
+    /// - `nu`: The volatility of the volatility. Controls the curvature of
+    ///   the smile.
+    #[wasm_bindgen(constructor)]
+    pub fn new(alpha: f64, beta: f64, rho: f64, nu: f64) -> SabrParameters {
+        SabrParameters {
+            alpha,
+            beta,
+            rho,
+            nu,
+        }
+    }
+
+    /// Get the initial level of volatility.
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Get the elasticity of the volatility to the forward price.
+    pub fn beta(&self) -> f64 {
+        self.beta
+    }
+
+    /// Get the correlation between the forward price and its volatility.
+    pub fn rho(&self) -> f64 {
+        self.rho
+    }
+
+    /// Get the volatility of the volatility.
+    pub fn nu(&self) -> f64 {
+        self.nu
+    }
+
+    /// Get the Black-Scholes (lognormal) volatility equivalent to the SABR
+    /// model for an option on `forward` struck at `strike`, with Hagan's
+    /// approximation.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `forward`: The forward price of the asset for the option's maturity.
+    /// - `strike`: The option's strike price.
+    /// - `time_to_maturity`: The time to maturity, in years.
+    pub fn implied_volatility(&self, forward: f64, strike: f64, time_to_maturity: f64) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("SabrParameters::implied_volatility");
+
+        let SabrParameters {
+            alpha,
+            beta,
+            rho,
+            nu,
+        } = *self;
+        let one_minus_beta = 1.0 - beta;
+        let log_moneyness = (forward / strike).ln();
+        // (F * K)^((1 - beta) / 2)
+        let geometric_mean = (forward * strike).powf(one_minus_beta / 2.0);
+
+        let z = nu / alpha * geometric_mean * log_moneyness;
+        // z / x(z), which tends to 1 at the money
+        let smile = if z.abs() < 1e-8 {
+            1.0 - rho * z / 2.0
+        } else {
+            let x = (((1.0 - 2.0 * rho * z + z * z).sqrt() + z - rho) / (1.0 - rho)).ln();
+            z / x
+        };
+
+        let denominator = geometric_mean
+            * (1.0
+                + one_minus_beta.powi(2) / 24.0 * log_moneyness.powi(2)
+                + one_minus_beta.powi(4) / 1920.0 * log_moneyness.powi(4));
+        let correction = 1.0
+            + (one_minus_beta.powi(2) / 24.0 * alpha.powi(2) / geometric_mean.powi(2)
+                + rho * beta * nu * alpha / (4.0 * geometric_mean)
+                + (2.0 - 3.0 * rho.powi(2)) / 24.0 * nu.powi(2))
+                * time_to_maturity;
+
+        alpha / denominator * smile * correction
+    }
+}
+
+/// An option priced under the SABR model.
+///
+/// The asset price, strike, rates and times are taken from a `BSOption`,
+/// whose volatility is replaced by the SABR implied volatility. If the
+/// option has a shift, the forward and strike include it, i.e. the model is
+/// a shifted SABR model.
+///
+/// ```rust
+/// use opcalc::models::sabr::{SabrModel, SabrParameters};
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+/// let parameters = SabrParameters::new(0.25, 1.0, -0.4, 0.8);
+///
+/// let model = SabrModel::new(&option, parameters);
+/// let delta = model.black_scholes_option().call_delta();
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct SabrModel {
+    option: BSOption,
+    parameters: SabrParameters,
+}
+
+#[wasm_bindgen]
+impl SabrModel {
+    /// Create a `SabrModel` for the market inputs of `option`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(option: &BSOption, parameters: SabrParameters) -> SabrModel {
+        SabrModel {
+            option: *option,
+            parameters,
+        }
+    }
+
+    /// Get the model parameters.
+    pub fn parameters(&self) -> SabrParameters {
+        self.parameters
+    }
+
+    /// Get the SABR implied volatility for the option's strike and maturity.
+    pub fn implied_volatility(&self) -> f64 {
+        let carry = self.option.interest().ln_1p() - self.option.payout_rate().ln_1p();
+        let time_to_maturity = self.option.time_to_maturity();
+        let shift = self.option.shift();
+        let forward = (self.option.asset_price() + shift) * (carry * time_to_maturity).exp();
+
+        self.parameters
+            .implied_volatility(forward, self.option.strike() + shift, time_to_maturity)
+    }
+
+    /// Get a copy of the option with its volatility set to the SABR implied
+    /// volatility, to value it and compute its greeks with the Black-Scholes
+    /// pricer.
+    pub fn black_scholes_option(&self) -> BSOption {
+        let mut option = self.option;
+        option.set_volatility(self.implied_volatility());
+        option
+    }
+
+    /// Get the value of the call option.
+    pub fn call_value(&self) -> f64 {
+        self.black_scholes_option().call_value()
+    }
+
+    /// Get the value of the put option.
+    pub fn put_value(&self) -> f64 {
+        self.black_scholes_option().put_value()
+    }
+}
+
+#[cfg(test)]
+mod sabr_tests {
+    use crate::models::sabr::{SabrModel, SabrParameters};
+    use crate::option::BSOption;
+
+    #[test]
+    fn reduces_to_black_scholes_without_volatility_of_volatility() {
+        let parameters = SabrParameters::new(0.23, 1.0, -0.5, 0.0);
+
+        for &strike in &[80.0, 100.0, 120.0] {
+            approx::assert_abs_diff_eq!(
+                parameters.implied_volatility(100.0, strike, 1.0),
+                0.23,
+                epsilon = 1e-12
+            );
+        }
+
+        let option = BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.4, 0.0);
+        let mut expected = option;
+        expected.set_volatility(0.23);
+        let model = SabrModel::new(&option, parameters);
+        approx::assert_abs_diff_eq!(model.call_value(), expected.call_value(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(model.put_value(), expected.put_value(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn is_continuous_at_the_money() {
+        let parameters = SabrParameters::new(0.04, 0.5, -0.3, 0.6);
+        let at_the_money = parameters.implied_volatility(0.03, 0.03, 2.0);
+
+        approx::assert_abs_diff_eq!(
+            parameters.implied_volatility(0.03, 0.03 * (1.0 + 1e-7), 2.0),
+            at_the_money,
+            epsilon = 1e-7
+        );
+        approx::assert_abs_diff_eq!(
+            parameters.implied_volatility(0.03, 0.03 * (1.0 - 1e-7), 2.0),
+            at_the_money,
+            epsilon = 1e-7
+        );
+    }
+
+    #[test]
+    fn produces_skews_and_smiles() {
+        let skewed = SabrParameters::new(0.25, 1.0, -0.5, 0.5);
+        let low = skewed.implied_volatility(100.0, 80.0, 1.0);
+        let at_the_money = skewed.implied_volatility(100.0, 100.0, 1.0);
+        let high = skewed.implied_volatility(100.0, 120.0, 1.0);
+        assert!(low > at_the_money && at_the_money > high);
+
+        let smiling = SabrParameters::new(0.25, 1.0, 0.0, 0.8);
+        let at_the_money = smiling.implied_volatility(100.0, 100.0, 1.0);
+        assert!(smiling.implied_volatility(100.0, 80.0, 1.0) > at_the_money);
+        assert!(smiling.implied_volatility(100.0, 120.0, 1.0) > at_the_money);
+    }
+}
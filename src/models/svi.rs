@@ -0,0 +1,209 @@
+//! The raw SVI (stochastic volatility inspired) smile of Gatheral (2004).
+//!
+//! The total implied variance `w = vol^2 * T` of an expiry is parameterized
+//! by the log-moneyness `k = ln(K / F)`:
+//!
+//! ```text
+//! w(k) = a + b * (rho * (k - m) + sqrt((k - m)^2 + sigma^2))
+//! ```
+//!
+//! Options are priced with Black-Scholes at the smile's implied volatility
+//! for their strike, like with `SabrModel`. Smiles are usually fitted to an
+//! expiry's quotes with `SviCalibration`.
+
+use crate::option::BSOption;
+use wasm_bindgen::prelude::*;
+
+/// The parameters of a raw SVI smile.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct SviParameters {
+    a: f64,
+    b: f64,
+    rho: f64,
+    m: f64,
+    sigma: f64,
+}
+
+#[wasm_bindgen]
+impl SviParameters {
+    /// Create `SviParameters`.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `a`: The level of the total variance.
+    /// - `b`: The slope of the wings.
+    /// - `rho`: The skew, between `-1` and `1`: the left wing's slope is
+    ///   `b * (1 - rho)`, and the right wing's `b * (1 + rho)`.
+    /// - `m`: The log-moneyness of the smile's center.
+    /// - `sigma`: The curvature at the center; smaller values give a sharper
+    ///   smile.
+    #[wasm_bindgen(constructor)]
+    pub fn new(a: f64, b: f64, rho: f64, m: f64, sigma: f64) -> SviParameters {
+        SviParameters {
+            a,
+            b,
+            rho,
+            m,
+            sigma,
+        }
+    }
+
+    /// Get the level of the total variance.
+    pub fn a(&self) -> f64 {
+        self.a
+    }
+
+    /// Get the slope of the wings.
+    pub fn b(&self) -> f64 {
+        self.b
+    }
+
+    /// Get the skew.
+    pub fn rho(&self) -> f64 {
+        self.rho
+    }
+
+    /// Get the log-moneyness of the smile's center.
+    pub fn m(&self) -> f64 {
+        self.m
+    }
+
+    /// Get the curvature at the smile's center.
+    pub fn sigma(&self) -> f64 {
+        self.sigma
+    }
+
+    /// Get the total implied variance `vol^2 * T` at `log_moneyness`,
+    /// `ln(K / F)`.
+    pub fn total_variance(&self, log_moneyness: f64) -> f64 {
+        let k = log_moneyness - self.m;
+        self.a + self.b * (self.rho * k + (k * k + self.sigma * self.sigma).sqrt())
+    }
+
+    /// Check the parameter constraints ruling out the simplest arbitrages:
+    /// `b >= 0`, `|rho| < 1` and `sigma > 0` for a well-formed smile, a
+    /// non-negative minimum variance `a + b * sigma * sqrt(1 - rho^2) >= 0`,
+    /// and wings no steeper than Lee's moment formula allows,
+    /// `b * (1 + |rho|) <= 2`.
+    ///
+    /// These are necessary but not sufficient to rule out butterfly
+    /// arbitrage between close strikes.
+    pub fn satisfies_no_arbitrage_constraints(&self) -> bool {
+        self.b >= 0.0
+            && self.rho.abs() < 1.0
+            && self.sigma > 0.0
+            && self.a + self.b * self.sigma * (1.0 - self.rho * self.rho).sqrt() >= 0.0
+            && self.b * (1.0 + self.rho.abs()) <= 2.0
+    }
+}
+
+/// An SVI smile for the expiry of a `BSOption`, which can be queried for the
+/// implied volatility and value at any strike.
+///
+/// The asset price, rates and times are taken from the option. Its strike
+/// and volatility are ignored. Like `HestonModel`, the smile ignores the
+/// option's shift.
+///
+/// ```rust
+/// use opcalc::models::svi::{SviParameters, SviSmile};
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+/// let parameters = SviParameters::new(0.004, 0.05, -0.5, 0.0, 0.1);
+///
+/// let smile = SviSmile::new(&option, parameters);
+/// assert!(smile.implied_volatility(90.0) > smile.implied_volatility(110.0));
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct SviSmile {
+    option: BSOption,
+    parameters: SviParameters,
+}
+
+#[wasm_bindgen]
+impl SviSmile {
+    /// Create an `SviSmile` for the expiry of `option`.
+    #[wasm_bindgen(constructor)]
+    pub fn new(option: &BSOption, parameters: SviParameters) -> SviSmile {
+        SviSmile {
+            option: *option,
+            parameters,
+        }
+    }
+
+    /// Get the option providing the market inputs.
+    pub fn option(&self) -> BSOption {
+        self.option
+    }
+
+    /// Get the smile's parameters.
+    pub fn parameters(&self) -> SviParameters {
+        self.parameters
+    }
+
+    /// Get the forward price of the asset for the expiry.
+    pub fn forward(&self) -> f64 {
+        let carry = self.option.interest().ln_1p() - self.option.payout_rate().ln_1p();
+        self.option.asset_price() * (carry * self.option.time_to_maturity()).exp()
+    }
+
+    /// Get the smile's implied volatility at `strike`.
+    pub fn implied_volatility(&self, strike: f64) -> f64 {
+        let log_moneyness = (strike / self.forward()).ln();
+        let total_variance = self.parameters.total_variance(log_moneyness).max(0.0);
+
+        (total_variance / self.option.time_to_maturity()).sqrt()
+    }
+
+    /// Get a copy of the option struck at `strike`, with its volatility set
+    /// to the smile's, to value it and compute its greeks with the
+    /// Black-Scholes pricer.
+    pub fn black_scholes_option(&self, strike: f64) -> BSOption {
+        let mut option = self.option;
+        option.set_strike(strike);
+        option.set_volatility(self.implied_volatility(strike));
+        option
+    }
+
+    /// Get the value of the call struck at `strike`.
+    pub fn call_value(&self, strike: f64) -> f64 {
+        self.black_scholes_option(strike).call_value()
+    }
+
+    /// Get the value of the put struck at `strike`.
+    pub fn put_value(&self, strike: f64) -> f64 {
+        self.black_scholes_option(strike).put_value()
+    }
+}
+
+#[cfg(test)]
+mod svi_tests {
+    use crate::models::svi::{SviParameters, SviSmile};
+    use crate::option::BSOption;
+
+    #[test]
+    fn reduces_to_a_flat_smile_without_wings() {
+        let option = BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.01);
+        let variance = 0.23f64.powi(2) * option.time_to_maturity();
+        let smile = SviSmile::new(&option, SviParameters::new(variance, 0.0, 0.0, 0.0, 0.1));
+
+        approx::assert_abs_diff_eq!(smile.implied_volatility(80.0), 0.23, epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(
+            smile.call_value(105.0),
+            option.call_value(),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(smile.put_value(105.0), option.put_value(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn checks_the_no_arbitrage_constraints() {
+        assert!(SviParameters::new(0.01, 0.1, -0.5, 0.0, 0.1).satisfies_no_arbitrage_constraints());
+        // negative variance at the center
+        assert!(!SviParameters::new(-0.02, 0.1, 0.0, 0.0, 0.1).satisfies_no_arbitrage_constraints());
+        // a right wing steeper than 2
+        assert!(!SviParameters::new(0.01, 1.5, 0.5, 0.0, 0.1).satisfies_no_arbitrage_constraints());
+    }
+}
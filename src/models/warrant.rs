@@ -0,0 +1,159 @@
+//! Warrants, adjusted for the dilution their exercise causes.
+//!
+//! Exercising a warrant issues a new share at the strike, so the exercised
+//! warrants share the firm's value with the existing shares. With `N` shares
+//! and `M` warrants outstanding, a warrant is worth `N / (N + M)` calls on
+//! the firm's equity per share, `S + M / N * W`, which includes the value of
+//! the warrants themselves (Galai & Schneller, 1978). The value `W` appears
+//! on both sides, and is solved for by fixed-point iteration, which
+//! converges because each iteration shrinks the error by
+//! `M / (N + M) * delta < 1`.
+
+use crate::option::BSOption;
+use wasm_bindgen::prelude::*;
+
+const MAX_ITERATIONS: usize = 100;
+const TOLERANCE: f64 = 1e-12;
+
+/// A warrant on the asset, strike and maturity of a `BSOption`, whose
+/// volatility is the volatility of the firm's equity including warrants.
+///
+/// ```rust
+/// use opcalc::models::warrant::Warrant;
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+///
+/// // 10 million shares and 2 million warrants outstanding
+/// let warrant = Warrant::new(&option, 10_000_000.0, 2_000_000.0);
+/// assert!(warrant.value() < option.call_value());
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct Warrant {
+    option: BSOption,
+    shares_outstanding: f64,
+    warrants_issued: f64,
+}
+
+#[wasm_bindgen]
+impl Warrant {
+    /// Create a `Warrant`, each exercisable into one new share.
+    #[wasm_bindgen(constructor)]
+    pub fn new(option: &BSOption, shares_outstanding: f64, warrants_issued: f64) -> Warrant {
+        Warrant {
+            option: *option,
+            shares_outstanding,
+            warrants_issued,
+        }
+    }
+
+    /// Get the number of shares outstanding.
+    pub fn shares_outstanding(&self) -> f64 {
+        self.shares_outstanding
+    }
+
+    /// Get the number of warrants issued.
+    pub fn warrants_issued(&self) -> f64 {
+        self.warrants_issued
+    }
+
+    /// Get the dilution factor `N / (N + M)`.
+    pub fn dilution_factor(&self) -> f64 {
+        self.shares_outstanding / (self.shares_outstanding + self.warrants_issued)
+    }
+
+    /// Get the warrant's value.
+    pub fn value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("Warrant::value");
+
+        let mut value = self.dilution_factor() * self.option.call_value();
+        for _ in 0..MAX_ITERATIONS {
+            let next = self.dilution_factor() * self.equity_call(value).call_value();
+            let converged = (next - value).abs() < TOLERANCE;
+            value = next;
+            if converged {
+                break;
+            }
+        }
+
+        value
+    }
+
+    /// Get the warrant's delta with respect to the share price,
+    /// `k * delta / (1 - k * M / N * delta)` with `k = N / (N + M)` and the
+    /// delta of the call on the equity per share.
+    pub fn delta(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("Warrant::delta");
+
+        let dilution_factor = self.dilution_factor();
+        let call_delta = self.equity_call(self.value()).call_delta();
+
+        dilution_factor * call_delta / (1.0 - dilution_factor * self.dilution() * call_delta)
+    }
+}
+
+impl Warrant {
+    /// The warrants outstanding per share, `M / N`.
+    fn dilution(&self) -> f64 {
+        self.warrants_issued / self.shares_outstanding
+    }
+
+    /// The call on the equity per share, given the warrant's value.
+    fn equity_call(&self, warrant_value: f64) -> BSOption {
+        let mut call = self.option;
+        call.set_asset_price(self.option.asset_price() + self.dilution() * warrant_value);
+        call
+    }
+}
+
+#[cfg(test)]
+mod warrant_tests {
+    use crate::models::warrant::Warrant;
+    use crate::option::BSOption;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.0)
+    }
+
+    #[test]
+    fn values_undiluted_warrants_like_calls() {
+        let option = create_test_option();
+        let warrant = Warrant::new(&option, 1_000_000.0, 0.0);
+
+        approx::assert_abs_diff_eq!(warrant.value(), option.call_value(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(warrant.delta(), option.call_delta(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn solves_for_the_diluted_value() {
+        let option = create_test_option();
+        let warrant = Warrant::new(&option, 1_000_000.0, 250_000.0);
+        let value = warrant.value();
+
+        let mut equity_call = option;
+        equity_call.set_asset_price(100.0 + 0.25 * value);
+        approx::assert_abs_diff_eq!(value, 0.8 * equity_call.call_value(), epsilon = 1e-10);
+        assert!(value < option.call_value());
+        assert!(value > 0.8 * option.call_value());
+    }
+
+    #[test]
+    fn matches_bumped_deltas() {
+        let option = create_test_option();
+        let reprice = |asset_price: f64| {
+            let mut bumped = option;
+            bumped.set_asset_price(asset_price);
+            Warrant::new(&bumped, 1_000_000.0, 250_000.0).value()
+        };
+
+        let warrant = Warrant::new(&option, 1_000_000.0, 250_000.0);
+        approx::assert_abs_diff_eq!(
+            warrant.delta(),
+            (reprice(100.01) - reprice(99.99)) / 0.02,
+            epsilon = 1e-6
+        );
+    }
+}
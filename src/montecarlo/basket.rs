@@ -0,0 +1,408 @@
+//! Monte Carlo pricing of basket options.
+//!
+//! A basket option pays off on a weighted sum of several asset prices. Each
+//! asset follows its own geometric Brownian motion, taken from a
+//! `BSOption`, and their returns are correlated: independent normal draws
+//! are multiplied by the Cholesky factor `L` of the correlation matrix
+//! `C = L * L^T`, which gives draws with correlation `C`.
+//!
+//! Deltas are estimated pathwise, on the same paths as the value: the
+//! derivative of a call's payoff with respect to the `i`-th starting price
+//! is `w_i * S_i(T) / S_i(0)` when the basket finishes in the money, and `0`
+//! otherwise.
+
+use crate::montecarlo::{draw_normals, unshifted, MonteCarloEstimate, PathModel};
+use crate::option::{BSOption, OptionType};
+use std::fmt;
+
+/// An error that indicates the inputs of a `Basket` do not fit together.
+#[derive(Debug, Clone, PartialEq)]
+pub enum BasketError {
+    /// The weights or the correlation matrix do not have one row per asset.
+    DimensionMismatch,
+    /// The correlation matrix is not symmetric with a unit diagonal.
+    InvalidCorrelation,
+    /// The correlation matrix is not positive definite, so no assets can
+    /// have these correlations.
+    NotPositiveDefinite,
+}
+
+impl fmt::Display for BasketError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BasketError::DimensionMismatch => {
+                write!(f, "Weights and correlations must have one entry per asset.")
+            }
+            BasketError::InvalidCorrelation => write!(
+                f,
+                "Correlation matrix must be symmetric, with ones on the diagonal."
+            ),
+            BasketError::NotPositiveDefinite => {
+                write!(f, "Correlation matrix must be positive definite.")
+            }
+        }
+    }
+}
+
+/// A weighted basket of correlated assets.
+///
+/// Each asset's price, volatility, payout rate and interest rate are taken
+/// from a `BSOption`, whose strike is not used. The first asset's interest
+/// rate and time to maturity are used for discounting, and all assets should
+/// share the same times. Baskets ignore the options' shifts.
+#[derive(Debug, Clone)]
+pub struct Basket {
+    assets: Vec<BSOption>,
+    weights: Vec<f64>,
+    cholesky: Vec<Vec<f64>>,
+}
+
+impl Basket {
+    /// Create a `Basket` holding `weights[i]` units of the `i`-th asset,
+    /// whose returns are correlated by `correlation`.
+    pub fn new(
+        assets: &[BSOption],
+        weights: &[f64],
+        correlation: &[Vec<f64>],
+    ) -> Result<Basket, BasketError> {
+        let size = assets.len();
+        if size == 0
+            || weights.len() != size
+            || correlation.len() != size
+            || correlation.iter().any(|row| row.len() != size)
+        {
+            return Err(BasketError::DimensionMismatch);
+        }
+
+        let is_valid = (0..size).all(|i| {
+            (correlation[i][i] - 1.0).abs() < 1e-12
+                && (0..size).all(|j| {
+                    (correlation[i][j] - correlation[j][i]).abs() < 1e-12
+                        && correlation[i][j].abs() <= 1.0
+                })
+        });
+        if !is_valid {
+            return Err(BasketError::InvalidCorrelation);
+        }
+
+        Ok(Basket {
+            assets: assets.to_vec(),
+            weights: weights.to_vec(),
+            cholesky: cholesky(correlation).ok_or(BasketError::NotPositiveDefinite)?,
+        })
+    }
+
+    /// Create a `Basket` holding an equal share `1 / n` of each of the `n`
+    /// assets.
+    pub fn equally_weighted(
+        assets: &[BSOption],
+        correlation: &[Vec<f64>],
+    ) -> Result<Basket, BasketError> {
+        let weights = vec![1.0 / assets.len() as f64; assets.len()];
+        Basket::new(assets, &weights, correlation)
+    }
+
+    /// Get the basket's assets.
+    pub fn assets(&self) -> &[BSOption] {
+        &self.assets
+    }
+
+    /// Get the units held of each asset.
+    pub fn weights(&self) -> &[f64] {
+        &self.weights
+    }
+
+    /// Get the current value of the basket.
+    pub fn value(&self) -> f64 {
+        self.assets
+            .iter()
+            .zip(&self.weights)
+            .map(|(asset, weight)| weight * asset.asset_price())
+            .sum()
+    }
+
+    /// Simulate each asset's price at maturity from independent draws.
+    fn simulate_prices(&self, independent: &[f64]) -> Vec<f64> {
+        let time = self.assets[0].horizon();
+
+        self.assets
+            .iter()
+            .zip(&self.cholesky)
+            .map(|(asset, row)| {
+                let asset = unshifted(asset);
+                let normal: f64 = row.iter().zip(independent).map(|(l, z)| l * z).sum();
+                asset.step(&asset.initial_state(), time, &[normal])
+            })
+            .collect()
+    }
+}
+
+/// The lower triangular Cholesky factor of `matrix`, or `None` if it is not
+/// positive definite.
+fn cholesky(matrix: &[Vec<f64>]) -> Option<Vec<Vec<f64>>> {
+    let size = matrix.len();
+    let mut lower = vec![vec![0.0; size]; size];
+
+    for i in 0..size {
+        for j in 0..=i {
+            let sum: f64 = (0..j).map(|k| lower[i][k] * lower[j][k]).sum();
+            if i == j {
+                let pivot = matrix[i][i] - sum;
+                if pivot <= 1e-14 {
+                    return None;
+                }
+                lower[i][i] = pivot.sqrt();
+            } else {
+                lower[i][j] = (matrix[i][j] - sum) / lower[j][j];
+            }
+        }
+    }
+
+    Some(lower)
+}
+
+/// A Monte Carlo pricer for basket options.
+///
+/// ```rust
+/// use opcalc::montecarlo::basket::{Basket, BasketMonteCarlo};
+/// use opcalc::option::BSOption;
+///
+/// let first = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 0.0, 0.005, 0.23, 0.0);
+/// let second = BSOption::new(1_606_780_800, 1_610_668_800, 50.0, 0.0, 0.005, 0.31, 0.0);
+/// let correlation = vec![vec![1.0, 0.4], vec![0.4, 1.0]];
+///
+/// let basket = Basket::equally_weighted(&[first, second], &correlation).unwrap();
+/// let call = BasketMonteCarlo::new().call_value(&basket, 75.0);
+/// let deltas = BasketMonteCarlo::new().call_deltas(&basket, 75.0);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct BasketMonteCarlo {
+    paths: usize,
+    seed: u64,
+    antithetic: bool,
+}
+
+impl Default for BasketMonteCarlo {
+    fn default() -> BasketMonteCarlo {
+        BasketMonteCarlo {
+            paths: 10_000,
+            seed: 1,
+            antithetic: false,
+        }
+    }
+}
+
+impl BasketMonteCarlo {
+    /// Create a `BasketMonteCarlo` pricer with 10,000 paths and no variance
+    /// reduction.
+    pub fn new() -> BasketMonteCarlo {
+        Default::default()
+    }
+
+    /// Set the number of simulated paths. Must be at least two.
+    pub fn with_paths(self, paths: usize) -> BasketMonteCarlo {
+        BasketMonteCarlo {
+            paths: paths.max(2),
+            ..self
+        }
+    }
+
+    /// Set the seed of the random number generator.
+    pub fn with_seed(self, seed: u64) -> BasketMonteCarlo {
+        BasketMonteCarlo { seed, ..self }
+    }
+
+    /// Set whether paths are simulated in antithetic pairs.
+    pub fn with_antithetic(self, antithetic: bool) -> BasketMonteCarlo {
+        BasketMonteCarlo { antithetic, ..self }
+    }
+
+    /// Get the value of a European call on `basket`.
+    pub fn call_value(&self, basket: &Basket, strike: f64) -> MonteCarloEstimate {
+        crate::utils::set_panic_hook();
+        trace_scope!("basket::call_value");
+
+        self.simulate(basket, strike, OptionType::Call).0
+    }
+
+    /// Get the value of a European put on `basket`.
+    pub fn put_value(&self, basket: &Basket, strike: f64) -> MonteCarloEstimate {
+        crate::utils::set_panic_hook();
+        trace_scope!("basket::put_value");
+
+        self.simulate(basket, strike, OptionType::Put).0
+    }
+
+    /// Get the call's delta with respect to each asset's price.
+    pub fn call_deltas(&self, basket: &Basket, strike: f64) -> Vec<MonteCarloEstimate> {
+        crate::utils::set_panic_hook();
+        trace_scope!("basket::call_deltas");
+
+        self.simulate(basket, strike, OptionType::Call).1
+    }
+
+    /// Get the put's delta with respect to each asset's price.
+    pub fn put_deltas(&self, basket: &Basket, strike: f64) -> Vec<MonteCarloEstimate> {
+        crate::utils::set_panic_hook();
+        trace_scope!("basket::put_deltas");
+
+        self.simulate(basket, strike, OptionType::Put).1
+    }
+
+    /// Estimate the value and the deltas of the option on `basket`.
+    fn simulate(
+        &self,
+        basket: &Basket,
+        strike: f64,
+        option_type: OptionType,
+    ) -> (MonteCarloEstimate, Vec<MonteCarloEstimate>) {
+        let first = &basket.assets[0];
+        let discount = (-first.discount_rate() * first.horizon()).exp();
+        let sign = match option_type {
+            OptionType::Call => 1.0,
+            OptionType::Put => -1.0,
+        };
+
+        let size = basket.assets.len();
+        let mut payoffs = Vec::with_capacity(self.paths);
+        let mut deltas = vec![Vec::with_capacity(self.paths); size];
+        for normals in draw_normals(self.paths, 1, size, self.seed, self.antithetic) {
+            let prices = basket.simulate_prices(&normals[0]);
+            let basket_price: f64 = prices.iter().zip(&basket.weights).map(|(p, w)| p * w).sum();
+            let in_the_money = sign * (basket_price - strike) > 0.0;
+
+            payoffs.push(discount * (sign * (basket_price - strike)).max(0.0));
+            for (i, samples) in deltas.iter_mut().enumerate() {
+                let delta = if in_the_money {
+                    sign * discount * basket.weights[i] * prices[i] / basket.assets[i].asset_price()
+                } else {
+                    0.0
+                };
+                samples.push(delta);
+            }
+        }
+
+        let value = MonteCarloEstimate::from_paths(&payoffs, self.antithetic);
+        let deltas = deltas
+            .iter()
+            .map(|samples| MonteCarloEstimate::from_paths(samples, self.antithetic))
+            .collect();
+
+        (value, deltas)
+    }
+}
+
+#[cfg(test)]
+mod basket_tests {
+    use crate::montecarlo::basket::{Basket, BasketError, BasketMonteCarlo};
+    use crate::option::BSOption;
+
+    fn create_test_assets() -> Vec<BSOption> {
+        vec![
+            BSOption::new(1606780800, 1610668800, 100.0, 0.0, 0.005, 0.23, 0.0),
+            BSOption::new(1606780800, 1610668800, 50.0, 0.0, 0.005, 0.31, 0.01),
+            BSOption::new(1606780800, 1610668800, 80.0, 0.0, 0.005, 0.18, 0.0),
+        ]
+    }
+
+    fn create_test_correlation() -> Vec<Vec<f64>> {
+        vec![
+            vec![1.0, 0.4, 0.2],
+            vec![0.4, 1.0, -0.3],
+            vec![0.2, -0.3, 1.0],
+        ]
+    }
+
+    #[test]
+    fn values_single_assets_like_vanilla_options() {
+        let mut option = create_test_assets()[0];
+        option.set_strike(105.0);
+        let basket = Basket::new(&[option], &[1.0], &[vec![1.0]]).unwrap();
+        let pricer = BasketMonteCarlo::new().with_paths(50_000);
+
+        let call = pricer.call_value(&basket, 105.0);
+        assert!(call.lower_bound() < option.call_value());
+        assert!(call.upper_bound() > option.call_value());
+
+        let delta = &pricer.call_deltas(&basket, 105.0)[0];
+        approx::assert_abs_diff_eq!(
+            delta.value(),
+            option.call_delta(),
+            epsilon = 3.0 * delta.standard_error()
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_correlations() {
+        let assets = create_test_assets();
+
+        let mut asymmetric = create_test_correlation();
+        asymmetric[0][1] = 0.5;
+        let impossible = vec![
+            vec![1.0, 0.9, -0.9],
+            vec![0.9, 1.0, 0.9],
+            vec![-0.9, 0.9, 1.0],
+        ];
+
+        assert_eq!(
+            Basket::equally_weighted(&assets[..2], &create_test_correlation()).unwrap_err(),
+            BasketError::DimensionMismatch
+        );
+        assert_eq!(
+            Basket::equally_weighted(&assets, &asymmetric).unwrap_err(),
+            BasketError::InvalidCorrelation
+        );
+        assert_eq!(
+            Basket::equally_weighted(&assets, &impossible).unwrap_err(),
+            BasketError::NotPositiveDefinite
+        );
+    }
+
+    #[test]
+    fn matches_bumped_deltas() {
+        let assets = create_test_assets();
+        let correlation = create_test_correlation();
+        let weights = [0.5, 1.0, 0.25];
+        let basket = Basket::new(&assets, &weights, &correlation).unwrap();
+        let strike = basket.value();
+        let pricer = BasketMonteCarlo::new()
+            .with_paths(20_000)
+            .with_antithetic(true);
+
+        let call_deltas = pricer.call_deltas(&basket, strike);
+        let put_deltas = pricer.put_deltas(&basket, strike);
+        for i in 0..assets.len() {
+            let reprice = |bump: f64| {
+                let mut bumped = assets.clone();
+                bumped[i].set_asset_price(assets[i].asset_price() + bump);
+                let bumped = Basket::new(&bumped, &weights, &correlation).unwrap();
+                (
+                    pricer.call_value(&bumped, strike).value(),
+                    pricer.put_value(&bumped, strike).value(),
+                )
+            };
+
+            // the same draws drive the bumped baskets
+            let (call_up, put_up) = reprice(0.01);
+            let (call_down, put_down) = reprice(-0.01);
+            approx::assert_abs_diff_eq!(
+                call_deltas[i].value(),
+                (call_up - call_down) / 0.02,
+                epsilon = 1e-3
+            );
+            approx::assert_abs_diff_eq!(
+                put_deltas[i].value(),
+                (put_up - put_down) / 0.02,
+                epsilon = 1e-3
+            );
+
+            // delta parity: a call minus a put is a forward on the basket
+            let carry = (-assets[i].payout_rate().ln_1p() * assets[i].time_to_maturity()).exp();
+            approx::assert_abs_diff_eq!(
+                call_deltas[i].value() - put_deltas[i].value(),
+                weights[i] * carry,
+                epsilon = 3.0 * (call_deltas[i].standard_error() + put_deltas[i].standard_error())
+            );
+        }
+    }
+}
@@ -0,0 +1,301 @@
+//! Monte Carlo pricing of European options.
+//!
+//! Two variance reduction techniques are available:
+//!
+//! - Antithetic variates simulate paths in pairs driven by opposite normal
+//!   draws, which cancels out much of the noise of monotone payoffs.
+//! - The control variate simulates a Black-Scholes asset alongside the
+//!   model's, driven by the same draws, and corrects the estimate by how far
+//!   the simulated Black-Scholes payoffs miss their known analytic value.
+//!   The closer the model is to Black-Scholes, the larger the reduction.
+
+use crate::montecarlo::{
+    control_coefficient, draw_normals, pair_averages, simulate_path, MonteCarloEstimate, PathModel,
+};
+use crate::option::opcalc::op_calc::PricingInputs;
+use crate::option::OptionType;
+
+/// A Monte Carlo pricer for European options.
+///
+/// ```rust
+/// use opcalc::montecarlo::european::EuropeanMonteCarlo;
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+///
+/// let call = EuropeanMonteCarlo::new()
+///     .with_paths(20_000)
+///     .with_antithetic(true)
+///     .call_value(&option, 105.0);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct EuropeanMonteCarlo {
+    paths: usize,
+    steps: usize,
+    seed: u64,
+    antithetic: bool,
+    control_variate: bool,
+}
+
+impl Default for EuropeanMonteCarlo {
+    fn default() -> EuropeanMonteCarlo {
+        EuropeanMonteCarlo {
+            paths: 10_000,
+            steps: 1,
+            seed: 1,
+            antithetic: false,
+            control_variate: false,
+        }
+    }
+}
+
+impl EuropeanMonteCarlo {
+    /// Create a `EuropeanMonteCarlo` pricer with 10,000 paths of a single
+    /// step, and no variance reduction.
+    pub fn new() -> EuropeanMonteCarlo {
+        Default::default()
+    }
+
+    /// Set the number of simulated paths. Must be at least two.
+    pub fn with_paths(self, paths: usize) -> EuropeanMonteCarlo {
+        EuropeanMonteCarlo {
+            paths: paths.max(2),
+            ..self
+        }
+    }
+
+    /// Set the number of time steps per path. Models that are simulated
+    /// exactly, like Black-Scholes, need a single step.
+    pub fn with_steps(self, steps: usize) -> EuropeanMonteCarlo {
+        EuropeanMonteCarlo {
+            steps: steps.max(1),
+            ..self
+        }
+    }
+
+    /// Set the seed of the random number generator.
+    pub fn with_seed(self, seed: u64) -> EuropeanMonteCarlo {
+        EuropeanMonteCarlo { seed, ..self }
+    }
+
+    /// Set whether paths are simulated in antithetic pairs.
+    pub fn with_antithetic(self, antithetic: bool) -> EuropeanMonteCarlo {
+        EuropeanMonteCarlo { antithetic, ..self }
+    }
+
+    /// Set whether the model's `PathModel::control_model()` is used as a
+    /// control variate. Has no effect on models without a control model.
+    pub fn with_control_variate(self, control_variate: bool) -> EuropeanMonteCarlo {
+        EuropeanMonteCarlo {
+            control_variate,
+            ..self
+        }
+    }
+
+    /// Get the value of a European call on `model`'s asset.
+    pub fn call_value<M: PathModel>(&self, model: &M, strike: f64) -> MonteCarloEstimate {
+        crate::utils::set_panic_hook();
+        trace_scope!("european::call_value");
+
+        self.value(model, strike, OptionType::Call)
+    }
+
+    /// Get the value of a European put on `model`'s asset.
+    pub fn put_value<M: PathModel>(&self, model: &M, strike: f64) -> MonteCarloEstimate {
+        crate::utils::set_panic_hook();
+        trace_scope!("european::put_value");
+
+        self.value(model, strike, OptionType::Put)
+    }
+
+    fn value<M: PathModel>(
+        &self,
+        model: &M,
+        strike: f64,
+        option_type: OptionType,
+    ) -> MonteCarloEstimate {
+        let payoff = |price: f64| match option_type {
+            OptionType::Call => (price - strike).max(0.0),
+            OptionType::Put => (strike - price).max(0.0),
+        };
+
+        let discount = (-model.discount_rate() * model.horizon()).exp();
+        let dt = model.horizon() / self.steps as f64;
+        let control = model.control_model().filter(|_| self.control_variate);
+
+        let mut payoffs = Vec::with_capacity(self.paths);
+        let mut control_payoffs = Vec::with_capacity(self.paths);
+        for normals in draw_normals(
+            self.paths,
+            self.steps,
+            model.factors(),
+            self.seed,
+            self.antithetic,
+        ) {
+            let path = simulate_path(model, dt, &normals);
+            payoffs.push(discount * payoff(path[self.steps - 1]));
+
+            if let Some(control) = &control {
+                let first_factor: Vec<Vec<f64>> =
+                    normals.iter().map(|step| step[..1].to_vec()).collect();
+                let control_path = simulate_path(control, dt, &first_factor);
+                let control_discount = (-control.discount_rate() * control.horizon()).exp();
+                control_payoffs.push(control_discount * payoff(control_path[self.steps - 1]));
+            }
+        }
+
+        let control = match control {
+            Some(control) => control,
+            None => return MonteCarloEstimate::from_paths(&payoffs, self.antithetic),
+        };
+
+        // the analytic value of the control, at the pricer's strike
+        let mut inputs = PricingInputs::<f64>::new(&control);
+        inputs.strike = strike + control.shift();
+        let (call, put) = inputs.values();
+        let control_value = match option_type {
+            OptionType::Call => call,
+            OptionType::Put => put,
+        };
+
+        // antithetic pairs are the independent samples the regression needs
+        let (payoffs, control_payoffs) = if self.antithetic {
+            (pair_averages(&payoffs), pair_averages(&control_payoffs))
+        } else {
+            (payoffs, control_payoffs)
+        };
+
+        let coefficient = control_coefficient(&payoffs, &control_payoffs);
+        log_debug!("european: control variate coefficient {}", coefficient);
+        let adjusted: Vec<f64> = payoffs
+            .iter()
+            .zip(&control_payoffs)
+            .map(|(payoff, control_payoff)| payoff - coefficient * (control_payoff - control_value))
+            .collect();
+
+        MonteCarloEstimate {
+            paths: self.paths,
+            ..MonteCarloEstimate::from_samples(&adjusted)
+        }
+    }
+}
+
+#[cfg(test)]
+mod european_tests {
+    use crate::montecarlo::european::EuropeanMonteCarlo;
+    use crate::montecarlo::{draw_normals, PathModel};
+    use crate::option::BSOption;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.0)
+    }
+
+    /// Black-Scholes, hidden from the control variate.
+    struct Uncontrolled(BSOption);
+
+    impl PathModel for Uncontrolled {
+        type State = f64;
+
+        fn initial_state(&self) -> f64 {
+            self.0.initial_state()
+        }
+
+        fn price(&self, state: &f64) -> f64 {
+            self.0.price(state)
+        }
+
+        fn step(&self, state: &f64, dt: f64, normals: &[f64]) -> f64 {
+            self.0.step(state, dt, normals)
+        }
+
+        fn discount_rate(&self) -> f64 {
+            self.0.discount_rate()
+        }
+
+        fn horizon(&self) -> f64 {
+            self.0.horizon()
+        }
+    }
+
+    #[test]
+    fn mirrors_antithetic_draws() {
+        let draws: Vec<Vec<Vec<f64>>> = draw_normals(4, 3, 2, 5, true).collect();
+
+        for pair in draws.chunks(2) {
+            for (step, mirrored) in pair[0].iter().zip(&pair[1]) {
+                for (z, mirrored_z) in step.iter().zip(mirrored) {
+                    approx::assert_abs_diff_eq!(*z, -mirrored_z, epsilon = f64::EPSILON);
+                }
+            }
+        }
+        assert!((draws[0][0][0] - draws[2][0][0]).abs() > 0.0);
+    }
+
+    #[test]
+    fn reduces_variance_with_antithetic_variates() {
+        let option = create_test_option();
+        let pricer = EuropeanMonteCarlo::new().with_paths(20_000);
+
+        let plain = pricer.put_value(&option, 105.0);
+        let antithetic = pricer.with_antithetic(true).put_value(&option, 105.0);
+
+        assert_eq!(antithetic.paths(), 20_000);
+        assert!(antithetic.standard_error() < plain.standard_error() / 2.0);
+        approx::assert_abs_diff_eq!(
+            antithetic.value(),
+            option.put_value(),
+            epsilon = 3.0 * antithetic.standard_error()
+        );
+    }
+
+    #[test]
+    fn reduces_variance_with_control_variates() {
+        let option = create_test_option();
+        let pricer = EuropeanMonteCarlo::new()
+            .with_paths(20_000)
+            .with_control_variate(true);
+
+        // a Black-Scholes control is exact for Black-Scholes itself
+        let controlled = pricer.call_value(&option, 110.0);
+        let mut other_strike = option;
+        other_strike.set_strike(110.0);
+        approx::assert_abs_diff_eq!(
+            controlled.value(),
+            other_strike.call_value(),
+            epsilon = 1e-10
+        );
+        approx::assert_abs_diff_eq!(controlled.standard_error(), 0.0, epsilon = 1e-10);
+
+        // models without a control model are priced plainly
+        let uncontrolled = pricer.call_value(&Uncontrolled(option), 110.0);
+        assert!(uncontrolled.standard_error() > 0.01);
+        assert!(uncontrolled.lower_bound() < other_strike.call_value());
+        assert!(uncontrolled.upper_bound() > other_strike.call_value());
+    }
+
+    #[test]
+    fn simulates_shifted_options() {
+        let mut option = BSOption::new(1606780800, 1638316800, 0.5, 0.5, 0.005, 0.2, 0.0);
+        option.set_shift(2.0);
+        let pricer = EuropeanMonteCarlo::new()
+            .with_paths(20_000)
+            .with_antithetic(true);
+
+        let call = pricer.call_value(&Uncontrolled(option), 0.5);
+        let put = pricer.put_value(&Uncontrolled(option), 0.5);
+        approx::assert_abs_diff_eq!(
+            call.value(),
+            option.call_value(),
+            epsilon = 3.0 * call.standard_error()
+        );
+        approx::assert_abs_diff_eq!(
+            put.value(),
+            option.put_value(),
+            epsilon = 3.0 * put.standard_error()
+        );
+
+        // the control variate is exact for the shifted option itself
+        let controlled = pricer.with_control_variate(true).call_value(&option, 0.5);
+        approx::assert_abs_diff_eq!(controlled.value(), option.call_value(), epsilon = 1e-10);
+    }
+}
@@ -0,0 +1,325 @@
+//! Longstaff-Schwartz least-squares Monte Carlo.
+//!
+//! Implements the method from Longstaff & Schwartz (2001), "Valuing American
+//! options by simulation: a simple least-squares approach". Paths are
+//! simulated up to maturity, then rolled back over the exercise dates: at
+//! each date, the discounted future cash flows of in-the-money paths are
+//! regressed on basis functions of the asset price to estimate the value of
+//! continuing, and paths whose exercise value beats that estimate are
+//! exercised.
+
+use crate::montecarlo::regression::least_squares;
+use crate::montecarlo::{simulate_paths, MonteCarloEstimate, PathModel};
+use crate::option::bermudan::BermudanOption;
+use crate::option::OptionType;
+
+/// An enumeration of the supported regression basis families. Asset prices
+/// are divided by the strike before the basis is evaluated.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum BasisFunctions {
+    /// `1, x, x^2, ...`. This is the default.
+    Monomial,
+    /// Weighted Laguerre polynomials `e^(-x / 2) * L_n(x)`, as used by
+    /// Longstaff & Schwartz.
+    Laguerre,
+}
+
+/// A Longstaff-Schwartz pricer for American and Bermudan options.
+///
+/// ```rust
+/// use opcalc::montecarlo::lsm::LongstaffSchwartz;
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+///
+/// let put = LongstaffSchwartz::new()
+///     .with_paths(5_000)
+///     .with_exercise_dates(20)
+///     .put_value(&option, 105.0);
+/// ```
+#[derive(Debug, Copy, Clone)]
+pub struct LongstaffSchwartz {
+    paths: usize,
+    exercise_dates: usize,
+    basis: BasisFunctions,
+    degree: usize,
+    seed: u64,
+    antithetic: bool,
+}
+
+impl Default for LongstaffSchwartz {
+    fn default() -> LongstaffSchwartz {
+        LongstaffSchwartz {
+            paths: 10_000,
+            exercise_dates: 50,
+            basis: BasisFunctions::Monomial,
+            degree: 3,
+            seed: 1,
+            antithetic: false,
+        }
+    }
+}
+
+impl LongstaffSchwartz {
+    /// Create a `LongstaffSchwartz` pricer with 10,000 paths, 50 evenly
+    /// spaced exercise dates, and a cubic monomial basis.
+    pub fn new() -> LongstaffSchwartz {
+        Default::default()
+    }
+
+    /// Set the number of simulated paths. Must be at least two.
+    pub fn with_paths(self, paths: usize) -> LongstaffSchwartz {
+        LongstaffSchwartz {
+            paths: paths.max(2),
+            ..self
+        }
+    }
+
+    /// Set the number of evenly spaced exercise dates until maturity, the
+    /// last one being maturity itself. Many dates approximate American
+    /// exercise, few dates value Bermudan options.
+    pub fn with_exercise_dates(self, exercise_dates: usize) -> LongstaffSchwartz {
+        LongstaffSchwartz {
+            exercise_dates: exercise_dates.max(1),
+            ..self
+        }
+    }
+
+    /// Set the family of basis functions the continuation value is
+    /// regressed on.
+    pub fn with_basis(self, basis: BasisFunctions) -> LongstaffSchwartz {
+        LongstaffSchwartz { basis, ..self }
+    }
+
+    /// Set the highest degree of the basis functions, e.g. `2` for a
+    /// constant, linear and quadratic term.
+    pub fn with_degree(self, degree: usize) -> LongstaffSchwartz {
+        LongstaffSchwartz { degree, ..self }
+    }
+
+    /// Set the seed of the random number generator.
+    pub fn with_seed(self, seed: u64) -> LongstaffSchwartz {
+        LongstaffSchwartz { seed, ..self }
+    }
+
+    /// Set whether paths are simulated in antithetic pairs.
+    pub fn with_antithetic(self, antithetic: bool) -> LongstaffSchwartz {
+        LongstaffSchwartz { antithetic, ..self }
+    }
+
+    /// Get the value of an American call on `model`'s asset.
+    pub fn call_value<M: PathModel>(&self, model: &M, strike: f64) -> MonteCarloEstimate {
+        crate::utils::set_panic_hook();
+        trace_scope!("lsm::call_value");
+
+        self.value(model, strike, OptionType::Call)
+    }
+
+    /// Get the value of an American put on `model`'s asset.
+    pub fn put_value<M: PathModel>(&self, model: &M, strike: f64) -> MonteCarloEstimate {
+        crate::utils::set_panic_hook();
+        trace_scope!("lsm::put_value");
+
+        self.value(model, strike, OptionType::Put)
+    }
+
+    /// Get the value of a Bermudan call, exercisable on the option's own
+    /// schedule rather than on evenly spaced dates.
+    pub fn bermudan_call_value(&self, option: &BermudanOption) -> MonteCarloEstimate {
+        crate::utils::set_panic_hook();
+        trace_scope!("lsm::bermudan_call_value");
+
+        let (model, strike) = (option.option(), option.option().strike());
+        self.schedule_value(&model, strike, OptionType::Call, &option.exercise_years())
+    }
+
+    /// Get the value of a Bermudan put, exercisable on the option's own
+    /// schedule rather than on evenly spaced dates.
+    pub fn bermudan_put_value(&self, option: &BermudanOption) -> MonteCarloEstimate {
+        crate::utils::set_panic_hook();
+        trace_scope!("lsm::bermudan_put_value");
+
+        let (model, strike) = (option.option(), option.option().strike());
+        self.schedule_value(&model, strike, OptionType::Put, &option.exercise_years())
+    }
+
+    fn value<M: PathModel>(
+        &self,
+        model: &M,
+        strike: f64,
+        option_type: OptionType,
+    ) -> MonteCarloEstimate {
+        let dates = self.exercise_dates;
+        let times: Vec<f64> = (1..=dates)
+            .map(|date| model.horizon() * date as f64 / dates as f64)
+            .collect();
+        let estimate = self.schedule_value(model, strike, option_type, &times);
+
+        // exercising immediately is always an option
+        let immediate = payoff(option_type, strike, model.price(&model.initial_state()));
+        if immediate > estimate.value() {
+            log_debug!("lsm: immediate exercise ({}) beats holding on", immediate);
+            MonteCarloEstimate::exact(immediate, estimate.paths())
+        } else {
+            estimate
+        }
+    }
+
+    /// Roll the option back over the exercise dates at `times`, in years
+    /// from now. The last date is maturity.
+    fn schedule_value<M: PathModel>(
+        &self,
+        model: &M,
+        strike: f64,
+        option_type: OptionType,
+        times: &[f64],
+    ) -> MonteCarloEstimate {
+        let payoff = |price: f64| payoff(option_type, strike, price);
+        let discount = |from: f64, to: f64| (-model.discount_rate() * (to - from)).exp();
+
+        let dates = times.len();
+        let paths = simulate_paths(model, times, self.paths, self.seed, self.antithetic);
+
+        // each path's cash flow, discounted to the exercise date being visited
+        let mut cash_flows: Vec<f64> = paths.iter().map(|path| payoff(path[dates - 1])).collect();
+
+        for date in (0..dates - 1).rev() {
+            let step_discount = discount(times[date], times[date + 1]);
+            cash_flows
+                .iter_mut()
+                .for_each(|cash_flow| *cash_flow *= step_discount);
+
+            let in_the_money: Vec<usize> = (0..paths.len())
+                .filter(|&i| payoff(paths[i][date]) > 0.0)
+                .collect();
+            let design: Vec<Vec<f64>> = in_the_money
+                .iter()
+                .map(|&i| self.basis_values(paths[i][date] / strike))
+                .collect();
+            let targets: Vec<f64> = in_the_money.iter().map(|&i| cash_flows[i]).collect();
+
+            // without a usable regression, keep holding on to every path
+            let coefficients = match least_squares(&design, &targets) {
+                Some(coefficients) => coefficients,
+                None => continue,
+            };
+
+            for (&i, basis) in in_the_money.iter().zip(&design) {
+                let continuation: f64 = basis.iter().zip(&coefficients).map(|(b, c)| b * c).sum();
+                let exercise = payoff(paths[i][date]);
+                if exercise > continuation {
+                    cash_flows[i] = exercise;
+                }
+            }
+        }
+
+        let first_discount = discount(0.0, times[0]);
+        cash_flows
+            .iter_mut()
+            .for_each(|cash_flow| *cash_flow *= first_discount);
+
+        MonteCarloEstimate::from_paths(&cash_flows, self.antithetic)
+    }
+
+    fn basis_values(&self, x: f64) -> Vec<f64> {
+        match self.basis {
+            BasisFunctions::Monomial => (0..=self.degree).map(|n| x.powi(n as i32)).collect(),
+            BasisFunctions::Laguerre => {
+                let weight = (-x / 2.0).exp();
+                let mut values = Vec::with_capacity(self.degree + 1);
+                // L_0 = 1, L_1 = 1 - x, (n + 1) * L_(n+1) = (2n + 1 - x) * L_n - n * L_(n-1)
+                let (mut previous, mut current) = (0.0, 1.0);
+                for n in 0..=self.degree {
+                    values.push(weight * current);
+                    let next = ((2 * n + 1) as f64 - x) * current - n as f64 * previous;
+                    previous = current;
+                    current = next / (n + 1) as f64;
+                }
+                values
+            }
+        }
+    }
+}
+
+fn payoff(option_type: OptionType, strike: f64, price: f64) -> f64 {
+    match option_type {
+        OptionType::Call => (price - strike).max(0.0),
+        OptionType::Put => (strike - price).max(0.0),
+    }
+}
+
+#[cfg(test)]
+mod lsm_tests {
+    use crate::montecarlo::lsm::{BasisFunctions, LongstaffSchwartz};
+    use crate::option::american::BinomialTree;
+    use crate::option::bermudan::BermudanOption;
+    use crate::option::BSOption;
+
+    fn create_test_option() -> BSOption {
+        let mut option = BSOption::new(1606780800, 1610668800, 90.0, 105.0, 0.08, 0.23, 0.0);
+        option.set_time_curr(1610668800 - 31_536_000);
+        option
+    }
+
+    #[test]
+    fn values_american_puts() {
+        let option = create_test_option();
+        let tree_value = BinomialTree::new().with_steps(1000).put_value(&option);
+
+        for &basis in [BasisFunctions::Monomial, BasisFunctions::Laguerre].iter() {
+            let estimate = LongstaffSchwartz::new()
+                .with_paths(20_000)
+                .with_basis(basis)
+                .put_value(&option, 105.0);
+
+            // the regression biases the estimate slightly low
+            approx::assert_abs_diff_eq!(estimate.value(), tree_value, epsilon = 0.1);
+            assert!(estimate.value() > option.put_value());
+        }
+    }
+
+    #[test]
+    fn values_bermudan_puts_between_european_and_american() {
+        let option = create_test_option();
+        let american = BinomialTree::new().with_steps(1000).put_value(&option);
+
+        let bermudan = LongstaffSchwartz::new()
+            .with_paths(20_000)
+            .with_exercise_dates(4)
+            .put_value(&option, 105.0);
+        assert!(bermudan.value() > option.put_value());
+        assert!(bermudan.value() < american);
+
+        // out of the money, a single exercise date is a European option
+        let european = LongstaffSchwartz::new()
+            .with_paths(20_000)
+            .with_exercise_dates(1)
+            .call_value(&option, 105.0);
+        assert!(european.lower_bound() < option.call_value());
+        assert!(european.upper_bound() > option.call_value());
+    }
+
+    #[test]
+    fn values_bermudan_puts_on_their_schedule() {
+        let option = create_test_option();
+        let quarterly: Vec<u32> = (1..4)
+            .map(|quarter| option.time_curr() + quarter * 7_884_000)
+            .collect();
+        let bermudan = BermudanOption::new(&option, quarterly);
+        let tree_value = BinomialTree::new()
+            .with_steps(1000)
+            .bermudan_put_value(&bermudan);
+
+        // four evenly spaced dates make the same schedule, except that
+        // Bermudan options cannot be exercised immediately
+        let lsm = LongstaffSchwartz::new().with_paths(20_000);
+        let scheduled = lsm.bermudan_put_value(&bermudan);
+        let evenly_spaced = lsm.with_exercise_dates(4).put_value(&option, 105.0);
+        approx::assert_abs_diff_eq!(
+            scheduled.value().max(15.0),
+            evenly_spaced.value(),
+            epsilon = 1e-8
+        );
+        approx::assert_abs_diff_eq!(scheduled.value(), tree_value, epsilon = 0.1);
+    }
+}
@@ -0,0 +1,268 @@
+//! Option pricing by Monte Carlo simulation.
+//!
+//! Any model that can simulate its asset price forward in time implements
+//! `PathModel`, and can then be priced with the simulation-based pricers in
+//! this module. This makes it possible to value payoffs, such as American
+//! exercise, under models for which no tree or closed form is available.
+//!
+//! `BSOption` implements `PathModel` with geometric Brownian motion of its
+//! shifted asset price, so simulated prices can be cross-checked against the
+//! analytic ones.
+//!
+//! Simulations are seeded, so the same inputs always produce the same
+//! estimate. Antithetic variates and a Black-Scholes control variate shrink
+//! the standard error, so tight confidence intervals only need tens of
+//! thousands of paths.
+
+pub mod basket;
+pub mod european;
+pub mod lsm;
+pub(crate) mod random;
+pub(crate) mod regression;
+
+use crate::option::BSOption;
+use random::Random;
+use wasm_bindgen::prelude::*;
+
+/// A model whose asset price can be simulated under the risk-neutral measure.
+pub trait PathModel {
+    /// The model's state at a point in time, e.g. the asset price and, for
+    /// stochastic volatility models, the variance.
+    type State: Copy;
+
+    /// Get the state at the valuation time.
+    fn initial_state(&self) -> Self::State;
+
+    /// Get the asset price in `state`.
+    fn price(&self, state: &Self::State) -> f64;
+
+    /// Get the number of independent standard normal draws `step()` needs.
+    fn factors(&self) -> usize {
+        1
+    }
+
+    /// Advance `state` by `dt` years, driven by `factors()` independent
+    /// standard normal draws.
+    fn step(&self, state: &Self::State, dt: f64, normals: &[f64]) -> Self::State;
+
+    /// Get the continuously compounded interest rate used for discounting.
+    fn discount_rate(&self) -> f64;
+
+    /// Get the time until maturity, in years, over which paths are simulated.
+    fn horizon(&self) -> f64;
+
+    /// Get a Black-Scholes model close to this one, to be simulated with the
+    /// first normal draw of each step and used as a control variate. Returns
+    /// `None` if there is no such model, which disables the control variate.
+    fn control_model(&self) -> Option<BSOption> {
+        None
+    }
+}
+
+/// A Monte Carlo estimate and its statistical precision.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct MonteCarloEstimate {
+    value: f64,
+    standard_error: f64,
+    paths: usize,
+}
+
+#[wasm_bindgen]
+impl MonteCarloEstimate {
+    /// Get the estimated value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Get the standard error of the estimate, i.e. the standard deviation
+    /// of the discounted payoffs divided by the square root of the number of
+    /// paths.
+    pub fn standard_error(&self) -> f64 {
+        self.standard_error
+    }
+
+    /// Get the number of simulated paths.
+    pub fn paths(&self) -> usize {
+        self.paths
+    }
+
+    /// Get the lower bound of the 95% confidence interval.
+    pub fn lower_bound(&self) -> f64 {
+        self.value - 1.96 * self.standard_error
+    }
+
+    /// Get the upper bound of the 95% confidence interval.
+    pub fn upper_bound(&self) -> f64 {
+        self.value + 1.96 * self.standard_error
+    }
+}
+
+impl MonteCarloEstimate {
+    /// Report a value known without simulation error.
+    pub(crate) fn exact(value: f64, paths: usize) -> MonteCarloEstimate {
+        MonteCarloEstimate {
+            value,
+            standard_error: 0.0,
+            paths,
+        }
+    }
+
+    /// Estimate the mean of `samples`, which were simulated in antithetic
+    /// pairs if `antithetic` is set. The pairs are not independent, so each
+    /// pair's average counts as one sample for the standard error.
+    pub(crate) fn from_paths(samples: &[f64], antithetic: bool) -> MonteCarloEstimate {
+        if !antithetic {
+            return MonteCarloEstimate::from_samples(samples);
+        }
+
+        MonteCarloEstimate {
+            paths: samples.len(),
+            ..MonteCarloEstimate::from_samples(&pair_averages(samples))
+        }
+    }
+
+    /// Estimate the mean of `samples`.
+    pub(crate) fn from_samples(samples: &[f64]) -> MonteCarloEstimate {
+        let paths = samples.len();
+        let mean = samples.iter().sum::<f64>() / paths as f64;
+        let variance =
+            samples.iter().map(|x| (x - mean).powi(2)).sum::<f64>() / (paths as f64 - 1.0).max(1.0);
+
+        MonteCarloEstimate {
+            value: mean,
+            standard_error: (variance / paths as f64).sqrt(),
+            paths,
+        }
+    }
+}
+
+/// Average consecutive pairs of `samples`.
+pub(crate) fn pair_averages(samples: &[f64]) -> Vec<f64> {
+    samples
+        .chunks(2)
+        .map(|pair| pair.iter().sum::<f64>() / pair.len() as f64)
+        .collect()
+}
+
+/// Get the variance-minimizing control variate coefficient
+/// `cov(payoffs, controls) / var(controls)`, or `0` if the controls do not
+/// vary.
+pub(crate) fn control_coefficient(payoffs: &[f64], controls: &[f64]) -> f64 {
+    let count = payoffs.len() as f64;
+    let payoff_mean = payoffs.iter().sum::<f64>() / count;
+    let control_mean = controls.iter().sum::<f64>() / count;
+
+    let covariance: f64 = payoffs
+        .iter()
+        .zip(controls)
+        .map(|(payoff, control)| (payoff - payoff_mean) * (control - control_mean))
+        .sum();
+    let variance: f64 = controls
+        .iter()
+        .map(|control| (control - control_mean).powi(2))
+        .sum();
+
+    if variance > 0.0 {
+        covariance / variance
+    } else {
+        0.0
+    }
+}
+
+/// Draw the standard normals driving `paths` paths of `steps` steps with
+/// `factors` draws each. With `antithetic` set, every second path is driven
+/// by the negated draws of the path before it.
+pub(crate) fn draw_normals(
+    paths: usize,
+    steps: usize,
+    factors: usize,
+    seed: u64,
+    antithetic: bool,
+) -> impl Iterator<Item = Vec<Vec<f64>>> {
+    let mut random = Random::new(seed);
+    let mut previous: Vec<Vec<f64>> = Vec::new();
+
+    (0..paths).map(move |path| {
+        if antithetic && path % 2 == 1 {
+            previous
+                .iter()
+                .map(|step| step.iter().map(|z| -z).collect())
+                .collect()
+        } else {
+            previous = (0..steps)
+                .map(|_| (0..factors).map(|_| random.normal()).collect())
+                .collect();
+            previous.clone()
+        }
+    })
+}
+
+/// Get a copy of `option` without its shift, for pricers whose closed forms
+/// ignore it, so their simulated prices do too.
+pub(crate) fn unshifted(option: &BSOption) -> BSOption {
+    let mut unshifted = *option;
+    unshifted.set_shift(0.0);
+    unshifted
+}
+
+/// Simulate one path of `model` driven by `normals`, recording the asset
+/// price after each step of `dt` years.
+pub(crate) fn simulate_path<M: PathModel>(model: &M, dt: f64, normals: &[Vec<f64>]) -> Vec<f64> {
+    let mut state = model.initial_state();
+
+    normals
+        .iter()
+        .map(|step| {
+            state = model.step(&state, dt, step);
+            model.price(&state)
+        })
+        .collect()
+}
+
+/// Simulate `paths` paths of `model`, recording the asset price at each of
+/// `times`, in increasing years from now (excluding the valuation time).
+pub(crate) fn simulate_paths<M: PathModel>(
+    model: &M,
+    times: &[f64],
+    paths: usize,
+    seed: u64,
+    antithetic: bool,
+) -> Vec<Vec<f64>> {
+    draw_normals(paths, times.len(), model.factors(), seed, antithetic)
+        .map(|normals| {
+            let (mut state, mut previous) = (model.initial_state(), 0.0);
+            times
+                .iter()
+                .zip(&normals)
+                .map(|(&time, step)| {
+                    state = model.step(&state, time - previous, step);
+                    previous = time;
+                    model.price(&state)
+                })
+                .collect()
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod montecarlo_tests {
+    use crate::montecarlo::{simulate_paths, MonteCarloEstimate};
+    use crate::option::BSOption;
+
+    #[test]
+    fn simulates_european_values() {
+        let option = BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.0);
+        let discount = (-option.interest().ln_1p() * option.time_to_maturity()).exp();
+
+        let payoffs: Vec<f64> =
+            simulate_paths(&option, &[option.time_to_maturity()], 50_000, 7, false)
+                .iter()
+                .map(|path| discount * (path[0] - 105.0).max(0.0))
+                .collect();
+        let estimate = MonteCarloEstimate::from_samples(&payoffs);
+
+        assert!(estimate.lower_bound() < option.call_value());
+        assert!(estimate.upper_bound() > option.call_value());
+    }
+}
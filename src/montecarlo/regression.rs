@@ -0,0 +1,73 @@
+//! Least-squares regression for Longstaff-Schwartz continuation values.
+
+/// Fit `coefficients` minimizing `sum((row . coefficients - target)^2)` over
+/// the rows of `design`, by solving the normal equations.
+///
+/// Returns `None` if the design matrix is rank deficient, e.g. when there
+/// are fewer rows than basis functions.
+pub(crate) fn least_squares(design: &[Vec<f64>], targets: &[f64]) -> Option<Vec<f64>> {
+    let size = design.first()?.len();
+
+    // augmented normal equations [X'X | X'y]
+    let mut system = vec![vec![0.0; size + 1]; size];
+    for (row, &target) in design.iter().zip(targets) {
+        for i in 0..size {
+            for j in 0..size {
+                system[i][j] += row[i] * row[j];
+            }
+            system[i][size] += row[i] * target;
+        }
+    }
+
+    // Gaussian elimination with partial pivoting
+    for column in 0..size {
+        let pivot = (column..size).max_by(|&a, &b| {
+            system[a][column]
+                .abs()
+                .partial_cmp(&system[b][column].abs())
+                .unwrap_or(std::cmp::Ordering::Equal)
+        })?;
+        if system[pivot][column].abs() < 1e-12 {
+            return None;
+        }
+        system.swap(column, pivot);
+
+        let (upper, lower) = system.split_at_mut(column + 1);
+        let pivot_row = &upper[column];
+        for row in lower.iter_mut() {
+            let factor = row[column] / pivot_row[column];
+            for (entry, pivot_entry) in row.iter_mut().zip(pivot_row).skip(column) {
+                *entry -= factor * pivot_entry;
+            }
+        }
+    }
+
+    let mut coefficients = vec![0.0; size];
+    for row in (0..size).rev() {
+        let known: f64 = ((row + 1)..size)
+            .map(|k| system[row][k] * coefficients[k])
+            .sum();
+        coefficients[row] = (system[row][size] - known) / system[row][row];
+    }
+
+    Some(coefficients)
+}
+
+#[cfg(test)]
+mod regression_tests {
+    use crate::montecarlo::regression::least_squares;
+
+    #[test]
+    fn fits_quadratics() {
+        let xs = [0.0, 1.0, 2.0, 3.0, 4.0];
+        let design: Vec<Vec<f64>> = xs.iter().map(|x| vec![1.0, *x, x * x]).collect();
+        let targets: Vec<f64> = xs.iter().map(|x| 2.0 - x + 0.5 * x * x).collect();
+
+        let coefficients = least_squares(&design, &targets).unwrap();
+        approx::assert_abs_diff_eq!(coefficients[0], 2.0, epsilon = 1e-10);
+        approx::assert_abs_diff_eq!(coefficients[1], -1.0, epsilon = 1e-10);
+        approx::assert_abs_diff_eq!(coefficients[2], 0.5, epsilon = 1e-10);
+
+        assert!(least_squares(&design[..2], &targets[..2]).is_none());
+    }
+}
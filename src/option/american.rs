@@ -0,0 +1,775 @@
+//! American option pricing on binomial trees.
+//!
+//! The asset price is modelled with a recombining binomial tree: over each
+//! of `steps` time steps it moves up by a factor `u` or down by a factor `d`,
+//! with the risk-neutral probability of moving up chosen so that the tree
+//! grows at the cost of carry. Option values are rolled back from maturity,
+//! taking the larger of the continuation value and the exercise value at
+//! every node.
+//!
+//! Two parametrizations are supported, see `TreeKind`. Two refinements
+//! trade CPU time for accuracy:
+//!
+//! - Smoothing (Broadie & Detemple, 1996) values the last step before
+//!   maturity with the Black-Scholes formula instead of the kinked payoff,
+//!   which removes most of the oscillation of Cox-Ross-Rubinstein values.
+//! - Richardson extrapolation values the option on `n` and `2n` steps and
+//!   cancels the leading error term, of order `1 / n` for Cox-Ross-Rubinstein
+//!   trees and `1 / n^2` for Leisen-Reimer trees.
+//!
+//! Delta, gamma and theta are read off the nodes of the first two steps,
+//! which the valuation computes anyway. Only vega, rho and epsilon and their
+//! cross-derivatives need the tree to be re-run with bumped inputs.
+//!
+//! Options that never expire have a closed form (Merton, 1973; McDonald &
+//! Siegel, 1986): the value is `A * S^y` for a root `y` of the
+//! time-independent Black-Scholes equation, exercised at a constant asset
+//! price. Finite-maturity American options are worth less, which makes
+//! perpetual values an upper bound for the trees.
+
+use crate::option::bermudan::BermudanOption;
+use crate::option::dividends::DividendOption;
+use crate::option::greeks::Greeks;
+use crate::option::opcalc::op_calc::PricingInputs;
+use crate::option::{BSOption, OptionType};
+use wasm_bindgen::prelude::*;
+
+/// An enumeration of the supported binomial tree parametrizations.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum TreeKind {
+    /// Cox-Ross-Rubinstein (1979): `u = e^(vol * sqrt(dt))` and `d = 1 / u`.
+    /// This is the default. Values oscillate as the number of steps grows.
+    CoxRossRubinstein,
+    /// Leisen-Reimer (1996): moves and probabilities matched to `N(d1)` and
+    /// `N(d2)` with the Peizer-Pratt inversion. Values converge smoothly and
+    /// much faster, so far fewer steps are needed. Uses an odd number of
+    /// steps, rounding up if needed.
+    LeisenReimer,
+}
+
+/// A binomial tree pricer for American options.
+///
+/// ```rust
+/// use opcalc::option::american::BinomialTree;
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+///
+/// let put = BinomialTree::new().with_steps(500).put_value(&option);
+/// assert!(put >= option.put_value());
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct BinomialTree {
+    steps: usize,
+    kind: TreeKind,
+    smoothing: bool,
+    richardson: bool,
+}
+
+impl Default for BinomialTree {
+    fn default() -> BinomialTree {
+        BinomialTree {
+            steps: 200,
+            kind: TreeKind::CoxRossRubinstein,
+            smoothing: false,
+            richardson: false,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl BinomialTree {
+    /// Create a Cox-Ross-Rubinstein `BinomialTree` with 200 steps, without
+    /// smoothing or extrapolation.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> BinomialTree {
+        Default::default()
+    }
+
+    /// Set the number of time steps. More steps are more accurate, but the
+    /// work grows with the square of the number of steps. At least one step
+    /// is always used.
+    pub fn with_steps(self, steps: usize) -> BinomialTree {
+        BinomialTree {
+            steps: steps.max(1),
+            ..self
+        }
+    }
+
+    /// Set the tree's parametrization.
+    pub fn with_kind(self, kind: TreeKind) -> BinomialTree {
+        BinomialTree { kind, ..self }
+    }
+
+    /// Set whether to value the last step before maturity with the
+    /// Black-Scholes formula. Smoothed values converge monotonically, at a
+    /// negligible cost.
+    pub fn with_smoothing(self, smoothing: bool) -> BinomialTree {
+        BinomialTree { smoothing, ..self }
+    }
+
+    /// Set whether to extrapolate the values on `steps` and `2 * steps` steps
+    /// to an infinite number of steps. This costs five times as much as
+    /// valuing the tree once, and works best with smoothing or Leisen-Reimer
+    /// trees, whose errors shrink regularly.
+    pub fn with_richardson(self, richardson: bool) -> BinomialTree {
+        BinomialTree { richardson, ..self }
+    }
+
+    /// Get the number of time steps.
+    pub fn steps(&self) -> usize {
+        self.steps
+    }
+
+    /// Get the tree's parametrization.
+    pub fn kind(&self) -> TreeKind {
+        self.kind
+    }
+
+    /// Get whether the last step before maturity is smoothed.
+    pub fn smoothing(&self) -> bool {
+        self.smoothing
+    }
+
+    /// Get whether values are extrapolated across step counts.
+    pub fn richardson(&self) -> bool {
+        self.richardson
+    }
+
+    /// Get the value of `option` as an American call.
+    pub fn call_value(&self, option: &BSOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("BinomialTree::call_value");
+
+        self.value(option, OptionType::Call)
+    }
+
+    /// Get the value of `option` as an American put.
+    pub fn put_value(&self, option: &BSOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("BinomialTree::put_value");
+
+        self.value(option, OptionType::Put)
+    }
+
+    /// Get the value and greeks of `option` as an American call. Delta,
+    /// gamma and theta come from the tree's nodes, and the other greeks from
+    /// re-running the tree with the option's `NumericalConfig`. At least
+    /// three steps are used.
+    pub fn call_greeks(&self, option: &BSOption) -> Greeks {
+        crate::utils::set_panic_hook();
+        trace_scope!("BinomialTree::call_greeks");
+
+        self.lattice_greeks(option, OptionType::Call)
+    }
+
+    /// Get the value and greeks of `option` as an American put. See
+    /// `call_greeks()`.
+    pub fn put_greeks(&self, option: &BSOption) -> Greeks {
+        crate::utils::set_panic_hook();
+        trace_scope!("BinomialTree::put_greeks");
+
+        self.lattice_greeks(option, OptionType::Put)
+    }
+
+    /// Get the value of early exercise to a call on `option`: its American
+    /// value less its European value, both on this tree.
+    pub fn call_early_exercise_premium(&self, option: &BSOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("BinomialTree::call_early_exercise_premium");
+
+        self.early_exercise_premium(option, OptionType::Call)
+    }
+
+    /// Get the value of early exercise to a put on `option`. See
+    /// `call_early_exercise_premium()`.
+    pub fn put_early_exercise_premium(&self, option: &BSOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("BinomialTree::put_early_exercise_premium");
+
+        self.early_exercise_premium(option, OptionType::Put)
+    }
+
+    /// Get the value of early exercise to a call on an asset paying
+    /// discrete dividends. See `call_early_exercise_premium()`.
+    pub fn dividend_call_early_exercise_premium(&self, option: &DividendOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("BinomialTree::dividend_call_early_exercise_premium");
+
+        self.dividend_early_exercise_premium(option, OptionType::Call)
+    }
+
+    /// Get the value of early exercise to a put on an asset paying discrete
+    /// dividends. See `call_early_exercise_premium()`.
+    pub fn dividend_put_early_exercise_premium(&self, option: &DividendOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("BinomialTree::dividend_put_early_exercise_premium");
+
+        self.dividend_early_exercise_premium(option, OptionType::Put)
+    }
+
+    /// Get the value of an American call on an asset paying discrete
+    /// dividends. Each ex-dividend date is moved to the nearest step of the
+    /// tree.
+    pub fn dividend_call_value(&self, option: &DividendOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("BinomialTree::dividend_call_value");
+
+        self.dividend_value(option, OptionType::Call)
+    }
+
+    /// Get the value of an American put on an asset paying discrete
+    /// dividends. See `dividend_call_value()`.
+    pub fn dividend_put_value(&self, option: &DividendOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("BinomialTree::dividend_put_value");
+
+        self.dividend_value(option, OptionType::Put)
+    }
+
+    /// Get the value of a Bermudan call. Each exercise date is moved to the
+    /// nearest step of the tree, so more steps also place them more
+    /// accurately.
+    pub fn bermudan_call_value(&self, option: &BermudanOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("BinomialTree::bermudan_call_value");
+
+        self.bermudan_value(option, OptionType::Call)
+    }
+
+    /// Get the value of a Bermudan put. See `bermudan_call_value()`.
+    pub fn bermudan_put_value(&self, option: &BermudanOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("BinomialTree::bermudan_put_value");
+
+        self.bermudan_value(option, OptionType::Put)
+    }
+}
+
+impl BinomialTree {
+    fn total_steps(&self) -> usize {
+        match self.kind {
+            TreeKind::CoxRossRubinstein => self.steps,
+            TreeKind::LeisenReimer => self.steps | 1,
+        }
+    }
+
+    /// Apply `lattice` to this tree, or extrapolate it from this tree and
+    /// one with twice as many steps.
+    fn extrapolated<F: Fn(&BinomialTree) -> LatticeGreeks>(&self, lattice: F) -> LatticeGreeks {
+        let coarse = BinomialTree {
+            richardson: false,
+            ..*self
+        };
+        if !self.richardson {
+            return lattice(&coarse);
+        }
+
+        let fine = coarse.with_steps(2 * self.steps);
+        let order = match self.kind {
+            TreeKind::CoxRossRubinstein => 1,
+            TreeKind::LeisenReimer => 2,
+        };
+        let ratio = (fine.total_steps() as f64 / coarse.total_steps() as f64).powi(order);
+
+        let (fine, coarse) = (lattice(&fine), lattice(&coarse));
+        let extrapolate = |fine: f64, coarse: f64| (ratio * fine - coarse) / (ratio - 1.0);
+        LatticeGreeks {
+            value: extrapolate(fine.value, coarse.value),
+            delta: extrapolate(fine.delta, coarse.delta),
+            gamma: extrapolate(fine.gamma, coarse.gamma),
+            theta: extrapolate(fine.theta, coarse.theta),
+        }
+    }
+
+    /// The value, delta, gamma and theta of `option` as an American call or
+    /// put.
+    pub(crate) fn lattice(&self, option: &BSOption, option_type: OptionType) -> LatticeGreeks {
+        self.extrapolated(|tree| {
+            let exercisable = vec![true; tree.total_steps() + 1];
+            let income = vec![0.0; tree.total_steps() + 1];
+            tree.value_on_schedule(option, option_type, &exercisable, &income)
+        })
+    }
+
+    fn value(&self, option: &BSOption, option_type: OptionType) -> f64 {
+        self.lattice(option, option_type).value
+    }
+
+    /// The American value less the European value on the same tree, whose
+    /// discretization errors largely cancel out.
+    fn early_exercise_premium(&self, option: &BSOption, option_type: OptionType) -> f64 {
+        let european = self.extrapolated(|tree| {
+            let exercisable = vec![false; tree.total_steps() + 1];
+            let income = vec![0.0; tree.total_steps() + 1];
+            tree.value_on_schedule(option, option_type, &exercisable, &income)
+        });
+
+        (self.value(option, option_type) - european.value).max(0.0)
+    }
+
+    pub(crate) fn lattice_greeks(&self, option: &BSOption, option_type: OptionType) -> Greeks {
+        let tree = self.with_steps(self.steps.max(3));
+        Greeks::from_lattice(option, |option| tree.lattice(option, option_type))
+    }
+
+    fn bermudan_value(&self, bermudan: &BermudanOption, option_type: OptionType) -> f64 {
+        self.extrapolated(|tree| tree.bermudan_lattice(bermudan, option_type))
+            .value
+    }
+
+    fn bermudan_lattice(
+        &self,
+        bermudan: &BermudanOption,
+        option_type: OptionType,
+    ) -> LatticeGreeks {
+        let option = bermudan.option();
+        let steps = self.total_steps();
+        let dt = option.time_to_maturity / steps as f64;
+
+        let mut exercisable = vec![false; steps + 1];
+        for time in bermudan.exercise_years() {
+            let step = ((time / dt).round() as usize).max(1).min(steps);
+            exercisable[step] = true;
+        }
+
+        let income = vec![0.0; steps + 1];
+        self.value_on_schedule(&option, option_type, &exercisable, &income)
+    }
+
+    fn dividend_value(&self, option: &DividendOption, option_type: OptionType) -> f64 {
+        self.extrapolated(|tree| tree.dividend_lattice(option, option_type, true))
+            .value
+    }
+
+    fn dividend_early_exercise_premium(
+        &self,
+        option: &DividendOption,
+        option_type: OptionType,
+    ) -> f64 {
+        let european = self.extrapolated(|tree| tree.dividend_lattice(option, option_type, false));
+
+        (self.dividend_value(option, option_type) - european.value).max(0.0)
+    }
+
+    /// Value the option on a tree of the asset price net of the present
+    /// value of its dividends, adding back at each step the present value of
+    /// the dividends still to be paid. Each ex-dividend date is moved to the
+    /// nearest step, after which the asset price drops. Without
+    /// `early_exercise`, values the European option.
+    fn dividend_lattice(
+        &self,
+        dividend_option: &DividendOption,
+        option_type: OptionType,
+        early_exercise: bool,
+    ) -> LatticeGreeks {
+        let option = dividend_option.option();
+        let steps = self.total_steps();
+        let dt = option.time_to_maturity / steps as f64;
+        let rate = option.r_continuous();
+
+        let mut income = vec![0.0; steps + 1];
+        for (time, amount) in dividend_option.dividend_years() {
+            let ex_step = ((time / dt).round() as usize).max(1).min(steps);
+            for (step, income) in income.iter_mut().enumerate().take(ex_step) {
+                *income += amount * (-rate * (time - step as f64 * dt)).exp();
+            }
+        }
+
+        let exercisable = vec![early_exercise; steps + 1];
+        let escrowed = dividend_option.escrowed_option();
+        self.value_on_schedule(&escrowed, option_type, &exercisable, &income)
+    }
+
+    /// Value the option, allowing early exercise at the steps flagged in
+    /// `exercisable`. Exercise at maturity is always allowed. The asset
+    /// price at each step is the tree's price plus the step's `income`.
+    fn value_on_schedule(
+        &self,
+        option: &BSOption,
+        option_type: OptionType,
+        exercisable: &[bool],
+        income: &[f64],
+    ) -> LatticeGreeks {
+        let asset_price = option.shifted_asset_price();
+        let strike = option.shifted_strike();
+        let payoff = |price: f64| match option_type {
+            OptionType::Call => (price - strike).max(0.0),
+            OptionType::Put => (strike - price).max(0.0),
+        };
+
+        if option.time_to_maturity <= 0.0 {
+            return LatticeGreeks::without_nodes(payoff(asset_price));
+        }
+
+        let steps = self.total_steps();
+        let dt = option.time_to_maturity / steps as f64;
+        let growth = ((option.r_continuous() - option.div_continuous()) * dt).exp();
+        let discount = (-option.r_continuous() * dt).exp();
+
+        let (up, down, probability_up) = match self.kind {
+            TreeKind::CoxRossRubinstein => {
+                let up = (option.volatility * dt.sqrt()).exp();
+                let down = 1.0 / up;
+                (up, down, (growth - down) / (up - down))
+            }
+            TreeKind::LeisenReimer => {
+                let probability_up = peizer_pratt(option.d2(), steps);
+                let up = growth * peizer_pratt(option.d1(), steps) / probability_up;
+                let down = (growth - probability_up * up) / (1.0 - probability_up);
+                (up, down, probability_up)
+            }
+        };
+
+        let node_price = |step: usize, ups: usize| {
+            asset_price * up.powi(ups as i32) * down.powi((step - ups) as i32)
+        };
+
+        // from the lowest node upwards, payoffs at maturity, or closed-form
+        // European values one step before
+        let (mut values, last_step): (Vec<f64>, usize) = if self.smoothing {
+            let mut inputs = PricingInputs::<f64>::new(option);
+            inputs.time_to_maturity = dt;
+            let last_step = steps - 1;
+            let values = (0..=last_step)
+                .map(|ups| {
+                    inputs.asset_price = node_price(last_step, ups);
+                    let european = match (option_type, inputs.values()) {
+                        (OptionType::Call, (call, _)) => call,
+                        (OptionType::Put, (_, put)) => put,
+                    };
+                    if exercisable[last_step] {
+                        european.max(payoff(inputs.asset_price + income[last_step]))
+                    } else {
+                        european
+                    }
+                })
+                .collect();
+            (values, last_step)
+        } else {
+            let values = (0..=steps)
+                .map(|ups| payoff(node_price(steps, ups) + income[steps]))
+                .collect();
+            (values, steps)
+        };
+
+        // the values of the first two steps, kept for the greeks
+        let mut first_nodes = [[f64::NAN; 3]; 3];
+        let mut keep = |step: usize, values: &[f64]| {
+            if step <= 2 {
+                first_nodes[step][..=step].copy_from_slice(&values[..=step]);
+            }
+        };
+        keep(last_step, &values);
+
+        for step in (0..last_step).rev() {
+            for ups in 0..=step {
+                let continuation = discount
+                    * (probability_up * values[ups + 1] + (1.0 - probability_up) * values[ups]);
+                values[ups] = if exercisable[step] {
+                    continuation.max(payoff(node_price(step, ups) + income[step]))
+                } else {
+                    continuation
+                };
+            }
+            keep(step, &values);
+        }
+
+        let slope = |step: usize, ups: usize| {
+            (first_nodes[step][ups + 1] - first_nodes[step][ups])
+                / (node_price(step, ups + 1) - node_price(step, ups))
+        };
+        let delta = slope(1, 0);
+        let gamma = (slope(2, 1) - slope(2, 0)) / ((node_price(2, 2) - node_price(2, 0)) / 2.0);
+        // the middle node is only at the asset price on Cox-Ross-Rubinstein
+        // trees, so its value is moved there along the delta
+        let later_value = first_nodes[2][1] - delta * (node_price(2, 1) - asset_price);
+
+        LatticeGreeks {
+            value: values[0],
+            delta,
+            gamma,
+            theta: (later_value - values[0]) / (2.0 * dt),
+        }
+    }
+}
+
+/// The value of an option on a tree, with the delta, gamma and annual theta
+/// read off its first two steps. The greeks are NaN on trees of fewer than
+/// three steps, and at or after maturity.
+#[derive(Copy, Clone, Debug)]
+pub(crate) struct LatticeGreeks {
+    pub(crate) value: f64,
+    pub(crate) delta: f64,
+    pub(crate) gamma: f64,
+    pub(crate) theta: f64,
+}
+
+impl LatticeGreeks {
+    pub(crate) fn without_nodes(value: f64) -> LatticeGreeks {
+        LatticeGreeks {
+            value,
+            delta: f64::NAN,
+            gamma: f64::NAN,
+            theta: f64::NAN,
+        }
+    }
+}
+
+/// The value of `option` as a perpetual American call or put, ignoring its
+/// maturity.
+pub(crate) fn perpetual_value(option: &BSOption, option_type: OptionType) -> f64 {
+    let asset_price = option.shifted_asset_price();
+    let strike = option.shifted_strike();
+    let rate = option.r_continuous();
+    let variance = option.volatility.powi(2);
+
+    // roots of (vol^2 / 2) y (y - 1) + (r - q) y - r = 0
+    let drift = 0.5 - (rate - option.div_continuous()) / variance;
+    let root = (drift.powi(2) + 2.0 * rate / variance).sqrt();
+
+    match option_type {
+        OptionType::Call => {
+            let y = drift + root;
+            // without a payout, a call is never exercised
+            if y <= 1.0 {
+                return asset_price;
+            }
+
+            let boundary = strike * y / (y - 1.0);
+            if asset_price >= boundary {
+                asset_price - strike
+            } else {
+                (boundary - strike) * (asset_price / boundary).powf(y)
+            }
+        }
+        OptionType::Put => {
+            let y = drift - root;
+            // without interest, a put is never exercised
+            if y >= 0.0 {
+                return strike;
+            }
+
+            let boundary = strike * y / (y - 1.0);
+            if asset_price <= boundary {
+                strike - asset_price
+            } else {
+                (strike - boundary) * (asset_price / boundary).powf(y)
+            }
+        }
+    }
+}
+
+/// The Peizer-Pratt method 2 inversion, which approximates `N(z)` by a
+/// binomial probability over `steps` (odd) steps.
+fn peizer_pratt(z: f64, steps: usize) -> f64 {
+    let n = steps as f64;
+    let exponent = -(z / (n + 1.0 / 3.0 + 0.1 / (n + 1.0))).powi(2) * (n + 1.0 / 6.0);
+
+    0.5 + z.signum() * (0.25 - 0.25 * exponent.exp()).sqrt()
+}
+
+#[cfg(test)]
+mod american_tests {
+    use crate::option::american::{BinomialTree, TreeKind};
+    use crate::option::greeks::Greeks;
+    use crate::option::numerical::{DifferenceScheme, NumericalConfig};
+    use crate::option::BSOption;
+    use crate::option::OptionType;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.0)
+    }
+
+    #[test]
+    fn values_american_calls_without_payouts_like_european_ones() {
+        let option = create_test_option();
+        let tree = BinomialTree::new().with_steps(1000);
+
+        approx::assert_abs_diff_eq!(
+            tree.call_value(&option),
+            option.call_value(),
+            epsilon = 5e-3
+        );
+        approx::assert_abs_diff_eq!(
+            option.american_call_value(),
+            option.call_value(),
+            epsilon = 2e-2
+        );
+    }
+
+    #[test]
+    fn converges_faster_with_leisen_reimer_trees() {
+        let option = create_test_option();
+        let crr = BinomialTree::new().with_steps(51);
+        let leisen_reimer = crr.with_kind(TreeKind::LeisenReimer);
+
+        let crr_error = (crr.call_value(&option) - option.call_value()).abs();
+        let leisen_reimer_error = (leisen_reimer.call_value(&option) - option.call_value()).abs();
+        assert!(leisen_reimer_error < 1e-3);
+        assert!(leisen_reimer_error < crr_error / 10.0);
+
+        // even step counts are rounded up
+        approx::assert_abs_diff_eq!(
+            leisen_reimer.with_steps(50).put_value(&option),
+            leisen_reimer.put_value(&option),
+            epsilon = f64::EPSILON
+        );
+    }
+
+    #[test]
+    fn refines_values_with_smoothing_and_extrapolation() {
+        let option = create_test_option();
+        let crr = BinomialTree::new().with_steps(100);
+        let error = |tree: BinomialTree| (tree.call_value(&option) - option.call_value()).abs();
+
+        let smoothed = crr.with_smoothing(true);
+        assert!(error(smoothed) < error(crr));
+        assert!(error(smoothed.with_richardson(true)) < error(smoothed) / 10.0);
+        assert!(error(smoothed.with_richardson(true)) < 1e-3);
+
+        let leisen_reimer = crr.with_kind(TreeKind::LeisenReimer).with_steps(25);
+        assert!(error(leisen_reimer.with_richardson(true)) < error(leisen_reimer) / 4.0);
+
+        // American puts converge to a reference computed on a fine tree
+        let mut option = option;
+        option.interest = 0.08;
+        let reference = BinomialTree::new()
+            .with_steps(5000)
+            .with_smoothing(true)
+            .put_value(&option);
+        let put_error = |tree: BinomialTree| (tree.put_value(&option) - reference).abs();
+        assert!(put_error(smoothed.with_richardson(true)) < put_error(crr));
+        assert!(put_error(smoothed.with_richardson(true)) < 2e-3);
+    }
+
+    #[test]
+    fn reads_greeks_off_the_nodes() {
+        let mut option = create_test_option();
+        option.set_numerical_config(
+            NumericalConfig::new()
+                .with_volatility_bump(0.005)
+                .with_difference_scheme(DifferenceScheme::Central),
+        );
+
+        // without a payout, American calls are European ones
+        let tree = BinomialTree::new().with_steps(1000).with_smoothing(true);
+        let lattice = tree.call_greeks(&option);
+        let analytic = option.greeks(OptionType::Call);
+        approx::assert_abs_diff_eq!(lattice.value(), analytic.value(), epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(lattice.delta(), analytic.delta(), epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(lattice.gamma(), analytic.gamma(), epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(lattice.theta(), analytic.theta(), epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(lattice.vega(), analytic.vega(), epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(lattice.rho(), analytic.rho(), epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(lattice.epsilon(), analytic.epsilon(), epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(lattice.vanna(), analytic.vanna(), epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(lattice.volga(), analytic.volga(), epsilon = 1e-2);
+        approx::assert_abs_diff_eq!(lattice.veta(), analytic.veta(), epsilon = 1e-3);
+
+        // American puts, against bumping whole trees
+        option.interest = 0.08;
+        option.set_numerical_config(
+            option
+                .numerical_config()
+                .with_price_bump(0.5)
+                .with_time_bump(86_400 * 7),
+        );
+        let lattice = tree.put_greeks(&option);
+        let bumped = Greeks::bumped(&option, OptionType::Put, |option| {
+            (tree.call_value(option), tree.put_value(option))
+        });
+        approx::assert_abs_diff_eq!(lattice.value(), bumped.value(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(lattice.delta(), bumped.delta(), epsilon = 2e-3);
+        approx::assert_abs_diff_eq!(lattice.gamma(), bumped.gamma(), epsilon = 2e-3);
+        approx::assert_abs_diff_eq!(lattice.theta(), bumped.theta(), epsilon = 2e-3);
+        approx::assert_abs_diff_eq!(lattice.vega(), bumped.vega(), epsilon = 1e-6);
+    }
+
+    #[test]
+    fn values_the_early_exercise_of_puts() {
+        let mut option = create_test_option();
+        option.interest = 0.08;
+        option.set_asset_price(90.0);
+
+        // without a payout, calls are never exercised early
+        let tree = BinomialTree::new();
+        approx::assert_abs_diff_eq!(
+            tree.call_early_exercise_premium(&option),
+            0.0,
+            epsilon = 1e-12
+        );
+        let premium = tree.put_early_exercise_premium(&option);
+        approx::assert_abs_diff_eq!(
+            premium,
+            option.american_put_value() - option.put_value(),
+            epsilon = 2e-2
+        );
+        approx::assert_abs_diff_eq!(
+            option.put_early_exercise_premium(),
+            premium,
+            epsilon = 1e-12
+        );
+
+        let put = BinomialTree::new().with_steps(1000).put_value(&option);
+        assert!(put > option.put_value());
+        assert!(put >= 15.0);
+        approx::assert_abs_diff_eq!(option.american_put_value(), put, epsilon = 2e-2);
+    }
+
+    #[test]
+    fn bounds_trees_with_perpetual_values() {
+        let mut option = create_test_option();
+        option.interest = 0.08;
+        option.payout_rate = 0.03;
+
+        let tree = BinomialTree::new().with_steps(500);
+        let (call, put) = (tree.call_value(&option), tree.put_value(&option));
+        assert!(option.perpetual_call_value() > call);
+        assert!(option.perpetual_put_value() > put);
+
+        // at very long maturities, trees converge to the perpetual values
+        let mut long_dated = option;
+        long_dated.time_to_maturity = 200.0;
+        let tree = tree.with_steps(4000);
+        approx::assert_abs_diff_eq!(
+            tree.call_value(&long_dated),
+            option.perpetual_call_value(),
+            epsilon = 5e-2
+        );
+        approx::assert_abs_diff_eq!(
+            tree.put_value(&long_dated),
+            option.perpetual_put_value(),
+            epsilon = 5e-2
+        );
+    }
+
+    #[test]
+    fn values_perpetual_options_at_the_boundary() {
+        let mut option = create_test_option();
+        option.interest = 0.08;
+
+        // without a payout, the call is worth the asset
+        approx::assert_abs_diff_eq!(option.perpetual_call_value(), 100.0, epsilon = 1e-12);
+
+        // smooth pasting: the put meets its payoff with slope -1 at the
+        // exercise boundary y K / (y - 1), where y = -2r / vol^2
+        let r = 0.08f64.ln_1p();
+        let y = -2.0 * r / 0.23f64.powi(2);
+        let boundary = 105.0 * y / (y - 1.0);
+        option.set_asset_price(boundary);
+        let at_boundary = super::perpetual_value(&option, OptionType::Put);
+        approx::assert_abs_diff_eq!(at_boundary, 105.0 - boundary, epsilon = 1e-10);
+        option.set_asset_price(boundary + 1e-4);
+        let above = option.perpetual_put_value();
+        approx::assert_abs_diff_eq!((above - at_boundary) / 1e-4, -1.0, epsilon = 1e-3);
+        option.set_asset_price(boundary - 1.0);
+        approx::assert_abs_diff_eq!(
+            option.perpetual_put_value(),
+            106.0 - boundary,
+            epsilon = 1e-10
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! P&L attribution between two snapshots of an option.
+
+use crate::option::conventions::GreekConventions;
+use crate::option::BSOption;
+use wasm_bindgen::prelude::*;
+
+/// A decomposition of an option's value change into greek contributions.
+///
+/// Each contribution is the option's greek at the start snapshot multiplied
+/// by the matching market move: `delta * dS`, `gamma * dS^2 / 2`,
+/// `theta * days`, `vega * dVol` and `rho * dR`. Whatever the greeks do not
+/// explain is reported as the `residual()`, so all contributions add up to
+/// `total()`.
+///
+/// See `BSOption::call_pnl_attribution()` and
+/// `BSOption::put_pnl_attribution()`.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct PnlAttribution {
+    total: f64,
+    delta: f64,
+    gamma: f64,
+    theta: f64,
+    vega: f64,
+    rho: f64,
+}
+
+#[wasm_bindgen]
+impl PnlAttribution {
+    /// Get the realized change in the option's value.
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+
+    /// Get the P&L explained by delta, i.e. the part a delta hedge offsets.
+    pub fn delta(&self) -> f64 {
+        self.delta
+    }
+
+    /// Get the P&L explained by gamma.
+    pub fn gamma(&self) -> f64 {
+        self.gamma
+    }
+
+    /// Get the P&L explained by the passage of time.
+    pub fn theta(&self) -> f64 {
+        self.theta
+    }
+
+    /// Get the P&L explained by the change in volatility.
+    pub fn vega(&self) -> f64 {
+        self.vega
+    }
+
+    /// Get the P&L explained by the change in interest rate.
+    pub fn rho(&self) -> f64 {
+        self.rho
+    }
+
+    /// Get the P&L not explained by any of the greeks.
+    pub fn residual(&self) -> f64 {
+        self.total - self.explained()
+    }
+
+    /// Get the sum of all greek contributions.
+    pub fn explained(&self) -> f64 {
+        self.delta + self.gamma + self.theta + self.vega + self.rho
+    }
+
+    /// Get the P&L of the option together with its delta hedge, i.e. the
+    /// total without the delta contribution.
+    pub fn hedged_total(&self) -> f64 {
+        self.total - self.delta
+    }
+}
+
+/// A decomposition of an option's value change into the contribution of
+/// each input's move, obtained by full repricing.
+///
+/// Starting from the original option, inputs are moved to their new values
+/// one at a time, in this order: current time, asset price, volatility,
+/// interest rate, payout rate. Each step's change in value is attributed to
+/// the input that moved. Any remaining difference (e.g. from a changed
+/// strike or maturity) is reported as `other()`, so all contributions add up
+/// to `total()` exactly.
+///
+/// See `BSOption::call_explain()` and `BSOption::put_explain()`.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct PnlExplain {
+    total: f64,
+    time: f64,
+    asset_price: f64,
+    volatility: f64,
+    interest: f64,
+    payout_rate: f64,
+}
+
+#[wasm_bindgen]
+impl PnlExplain {
+    /// Get the change in the option's value.
+    pub fn total(&self) -> f64 {
+        self.total
+    }
+
+    /// Get the value change caused by the passage of time.
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// Get the value change caused by the asset price's move.
+    pub fn asset_price(&self) -> f64 {
+        self.asset_price
+    }
+
+    /// Get the value change caused by the volatility's move.
+    pub fn volatility(&self) -> f64 {
+        self.volatility
+    }
+
+    /// Get the value change caused by the interest rate's move.
+    pub fn interest(&self) -> f64 {
+        self.interest
+    }
+
+    /// Get the value change caused by the payout rate's move.
+    pub fn payout_rate(&self) -> f64 {
+        self.payout_rate
+    }
+
+    /// Get the value change not caused by any of the market inputs, such as
+    /// a change in strike or maturity.
+    pub fn other(&self) -> f64 {
+        self.total
+            - self.time
+            - self.asset_price
+            - self.volatility
+            - self.interest
+            - self.payout_rate
+    }
+}
+
+impl PnlExplain {
+    pub(crate) fn new(from: &BSOption, to: &BSOption, value: fn(&BSOption) -> f64) -> PnlExplain {
+        let mut state = *from;
+        let mut previous_value = value(&state);
+        let mut step = |state: &BSOption| {
+            let current_value = value(state);
+            let change = current_value - previous_value;
+            previous_value = current_value;
+            change
+        };
+
+        state.set_time_curr(to.time_curr);
+        let time = step(&state);
+
+        state.set_asset_price(to.asset_price);
+        let asset_price = step(&state);
+
+        state.set_volatility(to.volatility);
+        let volatility = step(&state);
+
+        state.interest = to.interest;
+        let interest = step(&state);
+
+        state.set_payout_rate(to.payout_rate);
+        let payout_rate = step(&state);
+
+        PnlExplain {
+            total: value(to) - value(from),
+            time,
+            asset_price,
+            volatility,
+            interest,
+            payout_rate,
+        }
+    }
+}
+
+/// Greeks and value of one side (call or put) of an option.
+struct Sensitivities {
+    value: f64,
+    delta: f64,
+    gamma: f64,
+    theta: f64,
+    vega: f64,
+    rho: f64,
+}
+
+impl PnlAttribution {
+    pub(crate) fn for_call(start: &BSOption, end: &BSOption) -> PnlAttribution {
+        let start = &Self::with_default_conventions(start);
+        let sensitivities = Sensitivities {
+            value: start.call_value(),
+            delta: start.call_delta(),
+            gamma: start.call_gamma(),
+            theta: start.call_theta(),
+            vega: start.call_vega(),
+            rho: Self::rate_sensitivity(start, BSOption::call_value),
+        };
+
+        Self::attribute(start, end, sensitivities, end.call_value())
+    }
+
+    pub(crate) fn for_put(start: &BSOption, end: &BSOption) -> PnlAttribution {
+        let start = &Self::with_default_conventions(start);
+        let sensitivities = Sensitivities {
+            value: start.put_value(),
+            delta: start.put_delta(),
+            gamma: start.put_gamma(),
+            theta: start.put_theta(),
+            vega: start.put_vega(),
+            rho: Self::rate_sensitivity(start, BSOption::put_value),
+        };
+
+        Self::attribute(start, end, sensitivities, end.put_value())
+    }
+
+    fn attribute(
+        start: &BSOption,
+        end: &BSOption,
+        greeks: Sensitivities,
+        end_value: f64,
+    ) -> PnlAttribution {
+        const TIMESTAMP_ONE_DAY: f64 = 86_400.0;
+        // vega is quoted per 1% change in volatility
+        const VEGA_UNIT: f64 = 0.01;
+
+        let spot_move = end.asset_price - start.asset_price;
+        let elapsed_days = (end.time_curr as f64 - start.time_curr as f64) / TIMESTAMP_ONE_DAY;
+
+        PnlAttribution {
+            total: end_value - greeks.value,
+            delta: greeks.delta * spot_move,
+            gamma: greeks.gamma * spot_move.powi(2) / 2.0,
+            theta: greeks.theta * elapsed_days,
+            vega: greeks.vega * (end.volatility - start.volatility) / VEGA_UNIT,
+            rho: greeks.rho * (end.interest - start.interest),
+        }
+    }
+
+    /// The greeks below assume the default scaling, e.g. theta per calendar
+    /// day, whatever conventions the caller reports greeks in.
+    fn with_default_conventions(option: &BSOption) -> BSOption {
+        let mut option = *option;
+        option.set_greek_conventions(GreekConventions::new());
+        option
+    }
+
+    /// Central-difference sensitivity of `value` to the interest rate.
+    fn rate_sensitivity(option: &BSOption, value: fn(&BSOption) -> f64) -> f64 {
+        const INTEREST_DELTA: f64 = 0.0001;
+
+        let mut option_up = *option;
+        option_up.interest += INTEREST_DELTA;
+        let mut option_down = *option;
+        option_down.interest -= INTEREST_DELTA;
+
+        (value(&option_up) - value(&option_down)) / (2.0 * INTEREST_DELTA)
+    }
+}
+
+#[cfg(test)]
+mod attribution_tests {
+    use crate::option::BSOption;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.0)
+    }
+
+    #[test]
+    fn explains_small_moves() {
+        let start = create_test_option();
+        let mut end = start;
+        end.set_time_curr(start.time_curr() + 86_400);
+        end.set_asset_price(101.0);
+        end.set_volatility(0.235);
+
+        let attribution = start.call_pnl_attribution(&end);
+
+        approx::assert_abs_diff_eq!(
+            attribution.total(),
+            end.call_value() - start.call_value(),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            attribution.explained() + attribution.residual(),
+            attribution.total(),
+            epsilon = 1e-12
+        );
+        assert!(attribution.residual().abs() < 0.01 * attribution.total().abs());
+        assert!(attribution.delta() > 0.0);
+        assert!(attribution.theta() < 0.0);
+        assert!(attribution.vega() > 0.0);
+        approx::assert_abs_diff_eq!(attribution.rho(), 0.0);
+    }
+
+    #[test]
+    fn explains_moves_by_repricing() {
+        let from = create_test_option();
+        let mut to = from;
+        to.set_time_curr(from.time_curr() + 7 * 86_400);
+        to.set_asset_price(95.0);
+        to.set_volatility(0.3);
+        to.set_payout_rate(0.01);
+
+        let explain = from.put_explain(&to);
+
+        approx::assert_abs_diff_eq!(
+            explain.total(),
+            to.put_value() - from.put_value(),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(explain.other(), 0.0, epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(explain.interest(), 0.0);
+        assert!(explain.time() < 0.0);
+        assert!(explain.asset_price() > 0.0);
+        assert!(explain.volatility() > 0.0);
+
+        let mut moved_strike = to;
+        moved_strike.set_strike(110.0);
+        let explain = from.put_explain(&moved_strike);
+        assert!(explain.other() > 0.0);
+    }
+
+    #[test]
+    fn attributes_rate_moves_to_rho() {
+        let start = create_test_option();
+        let mut end = start;
+        end.interest = 0.015;
+
+        let attribution = start.put_pnl_attribution(&end);
+
+        assert!(attribution.rho() < 0.0);
+        assert!(attribution.residual().abs() < 0.01 * attribution.total().abs());
+    }
+}
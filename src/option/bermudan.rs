@@ -0,0 +1,139 @@
+//! Bermudan options, exercisable on a schedule of dates.
+//!
+//! A Bermudan option sits between a European option, exercisable at
+//! maturity only, and an American option, exercisable at any time. Its
+//! exercise dates are given as Unix timestamps, and it is priced on a
+//! `BinomialTree` or with `LongstaffSchwartz`.
+
+use crate::option::american::BinomialTree;
+use crate::option::BSOption;
+use wasm_bindgen::prelude::*;
+
+/// A Bermudan option on the asset, strike and maturity of a `BSOption`.
+///
+/// ```rust
+/// use opcalc::option::bermudan::BermudanOption;
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+///
+/// // exercisable twice a month, and at maturity
+/// let bermudan = BermudanOption::new(&option, vec![1_608_076_800, 1_609_372_800]);
+/// assert!(bermudan.put_value() <= option.american_put_value());
+/// ```
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct BermudanOption {
+    option: BSOption,
+    exercise_times: Vec<u32>,
+}
+
+#[wasm_bindgen]
+impl BermudanOption {
+    /// Create a `BermudanOption` exercisable at `exercise_times`, as Unix
+    /// timestamps in seconds, and at maturity. Times that are not after the
+    /// option's current time, or that are after its maturity, are ignored.
+    #[wasm_bindgen(constructor)]
+    pub fn new(option: &BSOption, exercise_times: Vec<u32>) -> BermudanOption {
+        let mut exercise_times: Vec<u32> = exercise_times
+            .into_iter()
+            .filter(|&time| time > option.time_curr() && time < option.time_maturity())
+            .collect();
+        exercise_times.push(option.time_maturity());
+        exercise_times.sort_unstable();
+        exercise_times.dedup();
+
+        BermudanOption {
+            option: *option,
+            exercise_times,
+        }
+    }
+
+    /// Get the underlying option's market inputs and terms.
+    pub fn option(&self) -> BSOption {
+        self.option
+    }
+
+    /// Get the exercise times, as Unix timestamps in seconds. The last one
+    /// is the option's maturity.
+    pub fn exercise_times(&self) -> Vec<u32> {
+        self.exercise_times.clone()
+    }
+
+    /// Get the call's value, on a 200-step binomial tree. Use
+    /// `BinomialTree` to choose the number of steps.
+    pub fn call_value(&self) -> f64 {
+        BinomialTree::new().bermudan_call_value(self)
+    }
+
+    /// Get the put's value, on a 200-step binomial tree. Use `BinomialTree`
+    /// to choose the number of steps.
+    pub fn put_value(&self) -> f64 {
+        BinomialTree::new().bermudan_put_value(self)
+    }
+}
+
+impl BermudanOption {
+    /// Get the exercise times in years from now.
+    pub(crate) fn exercise_years(&self) -> Vec<f64> {
+        let (time_curr, time_maturity) = (
+            self.option.time_curr() as f64,
+            self.option.time_maturity() as f64,
+        );
+
+        self.exercise_times
+            .iter()
+            .map(|&time| {
+                self.option.time_to_maturity() * (time as f64 - time_curr)
+                    / (time_maturity - time_curr)
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod bermudan_tests {
+    use crate::option::american::BinomialTree;
+    use crate::option::bermudan::BermudanOption;
+    use crate::option::BSOption;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 90.0, 105.0, 0.08, 0.23, 0.0)
+    }
+
+    #[test]
+    fn normalizes_exercise_times() {
+        let option = create_test_option();
+        let bermudan = BermudanOption::new(
+            &option,
+            vec![1609459200, 1606780800, 1620000000, 1608076800],
+        );
+
+        assert_eq!(
+            bermudan.exercise_times(),
+            vec![1608076800, 1609459200, 1610668800]
+        );
+        let years = bermudan.exercise_years();
+        approx::assert_abs_diff_eq!(years[2], option.time_to_maturity(), epsilon = 1e-12);
+    }
+
+    #[test]
+    fn values_puts_between_european_and_american() {
+        let option = create_test_option();
+        let tree = BinomialTree::new().with_steps(900);
+
+        // only exercisable at maturity
+        let european = BermudanOption::new(&option, vec![]);
+        approx::assert_abs_diff_eq!(
+            tree.bermudan_put_value(&european),
+            option.put_value(),
+            epsilon = 0.02
+        );
+
+        let weekly: Vec<u32> = (1..7).map(|week| 1606780800 + week * 604_800).collect();
+        let bermudan = BermudanOption::new(&option, weekly);
+        let bermudan_value = tree.bermudan_put_value(&bermudan);
+        assert!(bermudan_value > option.put_value());
+        assert!(bermudan_value < tree.put_value(&option));
+    }
+}
@@ -1,6 +1,10 @@
 //! Use a builder to create `BSOption` more conveniently.
 
-use crate::option::{BSOption, OptionTimeDefinition};
+use crate::option::conventions::GreekConventions;
+use crate::option::engine::Model;
+use crate::option::implied::ImpliedVolConfig;
+use crate::option::numerical::NumericalConfig;
+use crate::option::{BSOption, GreekMethod, OptionTimeDefinition};
 use std::fmt;
 use wasm_bindgen::prelude::*;
 
@@ -60,6 +64,8 @@ pub struct BSOptionBuilder {
     interest: Option<f64>,
     volatility: Option<f64>,
     payout_rate: f64,
+    shift: f64,
+    model: Model,
 }
 
 impl BSOptionBuilder {
@@ -67,6 +73,7 @@ impl BSOptionBuilder {
     pub fn new() -> BSOptionBuilder {
         BSOptionBuilder {
             payout_rate: 0.0,
+            shift: 0.0,
             ..Default::default()
         }
     }
@@ -156,6 +163,20 @@ impl BSOptionBuilder {
         }
     }
 
+    /// Set a displaced-diffusion shift, pricing the option under a shifted
+    /// lognormal model. See `BSOption::set_shift()`.
+    /// This setting is optional.
+    pub fn with_shift(self, shift: f64) -> BSOptionBuilder {
+        BSOptionBuilder { shift, ..self }
+    }
+
+    /// Set the model used to value the option, e.g. `Model::crr(500)` for
+    /// American values. See `BSOption::set_model()`.
+    /// This setting is optional, and defaults to Black-Scholes.
+    pub fn with_model(self, model: Model) -> BSOptionBuilder {
+        BSOptionBuilder { model, ..self }
+    }
+
     /// Returns a `BSOption` if all required build steps are called, or
     /// an `OptionMissingBuildStepError`.
     pub fn finalize(self) -> Result<BSOption, OptionMissingBuildStepError> {
@@ -218,6 +239,12 @@ impl BSOptionBuilder {
                 interest,
                 volatility,
                 payout_rate: self.payout_rate,
+                shift: self.shift,
+                greek_method: GreekMethod::Analytic,
+                numerical_config: NumericalConfig::new(),
+                greek_conventions: GreekConventions::new(),
+                implied_vol_config: ImpliedVolConfig::new(),
+                model: self.model,
             }),
         }
     }
@@ -327,6 +354,22 @@ impl WasmBSOptionBuilder {
         self
     }
 
+    /// Set a displaced-diffusion shift, pricing the option under a shifted
+    /// lognormal model. See `BSOption::set_shift()`.
+    /// This setting is optional.
+    pub fn with_shift(mut self, shift: f64) -> WasmBSOptionBuilder {
+        self._inner_builder = self._inner_builder.with_shift(shift);
+        self
+    }
+
+    /// Set the model used to value the option, e.g. `Model.crr(500)` for
+    /// American values. See `BSOption::set_model()`.
+    /// This setting is optional, and defaults to Black-Scholes.
+    pub fn with_model(mut self, model: Model) -> WasmBSOptionBuilder {
+        self._inner_builder = self._inner_builder.with_model(model);
+        self
+    }
+
     /// Returns a `BSOption` if all required build steps are taken.
     ///
     /// Throws an error if some required build steps are missing.
@@ -0,0 +1,110 @@
+//! Intermediate quantities of the Black-Scholes valuation.
+
+use crate::option::BSOption;
+use wasm_bindgen::prelude::*;
+
+/// The intermediate quantities used to value a `BSOption`.
+///
+/// With `S` the asset price, `K` the strike, `r` and `q` the continuously
+/// compounded interest and payout rates, and `T` the time to maturity:
+///
+/// - call value: `S * carry_factor * N(d1) - K * discount_factor * N(d2)`
+/// - put value: `K * discount_factor * N(-d2) - S * carry_factor * N(-d1)`
+///
+/// If the option has a displaced-diffusion shift, `S` and `K` include it.
+///
+/// See `BSOption::pricing_components()`.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct PricingComponents {
+    d1: f64,
+    d2: f64,
+    nd1: f64,
+    nd2: f64,
+    carry_factor: f64,
+    discount_factor: f64,
+}
+
+#[wasm_bindgen]
+impl PricingComponents {
+    /// Get `d1 = (ln(S / K) + (r - q + vol^2 / 2) * T) / (vol * sqrt(T))`.
+    pub fn d1(&self) -> f64 {
+        self.d1
+    }
+
+    /// Get `d2 = d1 - vol * sqrt(T)`.
+    pub fn d2(&self) -> f64 {
+        self.d2
+    }
+
+    /// Get `N(d1)`, the standard normal cumulative distribution at `d1`.
+    pub fn nd1(&self) -> f64 {
+        self.nd1
+    }
+
+    /// Get `N(d2)`, the standard normal cumulative distribution at `d2`.
+    pub fn nd2(&self) -> f64 {
+        self.nd2
+    }
+
+    /// Get the carry factor `e^(-q * T)` applied to the asset price.
+    pub fn carry_factor(&self) -> f64 {
+        self.carry_factor
+    }
+
+    /// Get the discount factor `e^(-r * T)` applied to the strike.
+    pub fn discount_factor(&self) -> f64 {
+        self.discount_factor
+    }
+}
+
+impl PricingComponents {
+    pub(crate) fn new(option: &BSOption) -> PricingComponents {
+        let d1 = option.d1();
+        let d2 = option.d2();
+
+        PricingComponents {
+            d1,
+            d2,
+            nd1: BSOption::normdist(d1),
+            nd2: BSOption::normdist(d2),
+            carry_factor: (-option.div_continuous() * option.time_to_maturity).exp(),
+            discount_factor: (-option.r_continuous() * option.time_to_maturity).exp(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod components_tests {
+    use crate::option::BSOption;
+
+    #[test]
+    fn matches_option_getters() {
+        let option = BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.02);
+        let components = option.pricing_components();
+
+        approx::assert_abs_diff_eq!(components.d1(), option.d1(), epsilon = f64::EPSILON);
+        approx::assert_abs_diff_eq!(components.d2(), option.d2(), epsilon = f64::EPSILON);
+        approx::assert_abs_diff_eq!(components.nd1(), option.nd1(), epsilon = f64::EPSILON);
+        approx::assert_abs_diff_eq!(components.nd2(), option.nd2(), epsilon = f64::EPSILON);
+    }
+
+    #[test]
+    fn reconstructs_option_values() {
+        let option = BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.02);
+        let components = option.pricing_components();
+
+        let call = 100.0 * components.carry_factor() * components.nd1()
+            - 105.0 * components.discount_factor() * components.nd2();
+        let put = 105.0 * components.discount_factor() * (1.0 - components.nd2())
+            - 100.0 * components.carry_factor() * (1.0 - components.nd1());
+
+        approx::assert_abs_diff_eq!(call, option.call_value(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(put, option.put_value(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(
+            components.d1() - components.d2(),
+            0.23 * option.time_to_maturity().sqrt(),
+            epsilon = 1e-12
+        );
+    }
+}
@@ -0,0 +1,129 @@
+//! Probability cones of the underlying's price.
+
+use crate::option::{BSOption, OptionTimeDefinition};
+use crate::utils::normal_inverse_cdf;
+use wasm_bindgen::prelude::*;
+
+/// The expected range of the underlying's price for each day until an
+/// option's maturity, at one or more confidence levels.
+///
+/// Prices are assumed to be lognormally distributed with the option's
+/// volatility. See `BSOption::probability_cone()`.
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct ProbabilityCone {
+    times: Vec<u32>,
+    confidence_levels: Vec<f64>,
+    lower_bands: Vec<Vec<f64>>,
+    upper_bands: Vec<Vec<f64>>,
+}
+
+#[wasm_bindgen]
+impl ProbabilityCone {
+    /// Get the timestamps, in seconds, at which the bands are evaluated.
+    /// The first timestamp is the option's current time and the last one
+    /// its maturity time, with one entry per day in between.
+    pub fn times(&self) -> Vec<u32> {
+        self.times.clone()
+    }
+
+    /// Get the confidence levels the cone was created with.
+    pub fn confidence_levels(&self) -> Vec<f64> {
+        self.confidence_levels.clone()
+    }
+
+    /// Get the lower price band for the confidence level at `level_index`,
+    /// with one price per entry in `times()`.
+    pub fn lower_band(&self, level_index: usize) -> Option<Vec<f64>> {
+        self.lower_bands.get(level_index).cloned()
+    }
+
+    /// Get the upper price band for the confidence level at `level_index`,
+    /// with one price per entry in `times()`.
+    pub fn upper_band(&self, level_index: usize) -> Option<Vec<f64>> {
+        self.upper_bands.get(level_index).cloned()
+    }
+}
+
+impl ProbabilityCone {
+    pub(crate) fn new(option: &BSOption, confidence_levels: &[f64], drift: f64) -> ProbabilityCone {
+        const TIMESTAMP_ONE_DAY: u32 = 86_400;
+
+        let mut times: Vec<u32> = (option.time_curr..option.time_maturity)
+            .step_by(TIMESTAMP_ONE_DAY as usize)
+            .collect();
+        times.push(option.time_maturity);
+
+        let years: Vec<f64> = times
+            .iter()
+            .map(|&time| {
+                BSOption::calc_time_to_maturity(OptionTimeDefinition {
+                    time_curr: option.time_curr,
+                    time_maturity: time,
+                })
+            })
+            .collect();
+
+        let log_drift = drift.ln_1p() - option.volatility.powi(2) / 2.0;
+        let band = |z_score: f64| -> Vec<f64> {
+            years
+                .iter()
+                .map(|&t| {
+                    option.asset_price
+                        * (log_drift * t + z_score * option.volatility * t.sqrt()).exp()
+                })
+                .collect()
+        };
+
+        // a two-sided interval leaves (1 - level) / 2 in each tail
+        let z_scores: Vec<f64> = confidence_levels
+            .iter()
+            .map(|level| normal_inverse_cdf((1.0 + level) / 2.0))
+            .collect();
+
+        ProbabilityCone {
+            times,
+            confidence_levels: confidence_levels.to_vec(),
+            lower_bands: z_scores.iter().map(|z| band(-z)).collect(),
+            upper_bands: z_scores.iter().map(|z| band(*z)).collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod cone_tests {
+    use crate::option::BSOption;
+
+    #[test]
+    fn creates_daily_bands_until_maturity() {
+        // 2020/12/01 00:00:00 until 2020/12/31 12:00:00
+        let option = BSOption::new(1606780800, 1609416000, 100.0, 105.0, 0.005, 0.2, 0.0);
+        let cone = option.probability_cone(&[0.68, 0.95], 0.0);
+
+        let times = cone.times();
+        assert_eq!(times.len(), 32);
+        assert_eq!(times[0], 1606780800);
+        assert_eq!(times[31], 1609416000);
+
+        let lower = cone.lower_band(1).unwrap();
+        let upper = cone.upper_band(1).unwrap();
+        approx::assert_abs_diff_eq!(lower[0], 100.0);
+        approx::assert_abs_diff_eq!(upper[0], 100.0);
+
+        // 30.5 days of 20% volatility and no drift, at a 95% confidence level
+        let t: f64 = 30.5 / 365.0;
+        approx::assert_abs_diff_eq!(
+            upper[31],
+            100.0 * (-0.02 * t + 1.959963984540054 * 0.2 * t.sqrt()).exp(),
+            epsilon = 1e-9
+        );
+        approx::assert_abs_diff_eq!(
+            lower[31],
+            100.0 * (-0.02 * t - 1.959963984540054 * 0.2 * t.sqrt()).exp(),
+            epsilon = 1e-9
+        );
+
+        assert!(cone.lower_band(0).unwrap()[31] > lower[31]);
+        assert_eq!(cone.lower_band(2), None);
+    }
+}
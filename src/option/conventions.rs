@@ -0,0 +1,117 @@
+//! Scaling conventions for reported greeks.
+
+use wasm_bindgen::prelude::*;
+
+/// An enumeration of the supported ways to quote gamma.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GammaUnit {
+    /// The change in delta per unit change in the asset price. This is the
+    /// default.
+    Absolute,
+    /// The change in delta for a 1% move in the asset price.
+    Percent,
+}
+
+/// The units in which greeks are reported.
+///
+/// The defaults quote vega, vanna and volga per 1% change in volatility, rho
+/// and epsilon per 1% change in rates, theta and veta per calendar day, and
+/// gamma per unit of the asset price. Desks quoting rho per basis point or
+/// theta per trading day can change them with `BSOption::set_greek_conventions()`:
+///
+/// ```rust
+/// use opcalc::option::conventions::GreekConventions;
+///
+/// let conventions = GreekConventions::new()
+///     .with_rate_unit(0.0001)
+///     .with_days_per_year(252.0);
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct GreekConventions {
+    volatility_unit: f64,
+    rate_unit: f64,
+    days_per_year: f64,
+    gamma_unit: GammaUnit,
+}
+
+impl Default for GreekConventions {
+    fn default() -> GreekConventions {
+        GreekConventions {
+            volatility_unit: 0.01,
+            rate_unit: 0.01,
+            days_per_year: 365.0,
+            gamma_unit: GammaUnit::Absolute,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl GreekConventions {
+    /// Create `GreekConventions` with a volatility unit of 1%, a rate unit of
+    /// 1%, 365 days per year, and absolute gamma.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> GreekConventions {
+        Default::default()
+    }
+
+    /// Set the change in volatility vega, vanna and volga are quoted for,
+    /// e.g. `0.01` for 1%.
+    pub fn with_volatility_unit(self, volatility_unit: f64) -> GreekConventions {
+        GreekConventions {
+            volatility_unit,
+            ..self
+        }
+    }
+
+    /// Set the change in interest and payout rates rho and epsilon are
+    /// quoted for, e.g. `0.0001` for one basis point.
+    pub fn with_rate_unit(self, rate_unit: f64) -> GreekConventions {
+        GreekConventions { rate_unit, ..self }
+    }
+
+    /// Set the number of days per year theta and veta are quoted for, e.g.
+    /// `252.0` to quote them per trading day.
+    pub fn with_days_per_year(self, days_per_year: f64) -> GreekConventions {
+        GreekConventions {
+            days_per_year,
+            ..self
+        }
+    }
+
+    /// Set the unit gamma is quoted in.
+    pub fn with_gamma_unit(self, gamma_unit: GammaUnit) -> GreekConventions {
+        GreekConventions { gamma_unit, ..self }
+    }
+
+    /// Get the change in volatility vega, vanna and volga are quoted for.
+    pub fn volatility_unit(&self) -> f64 {
+        self.volatility_unit
+    }
+
+    /// Get the change in rates rho and epsilon are quoted for.
+    pub fn rate_unit(&self) -> f64 {
+        self.rate_unit
+    }
+
+    /// Get the number of days per year theta and veta are quoted for.
+    pub fn days_per_year(&self) -> f64 {
+        self.days_per_year
+    }
+
+    /// Get the unit gamma is quoted in.
+    pub fn gamma_unit(&self) -> GammaUnit {
+        self.gamma_unit
+    }
+}
+
+impl GreekConventions {
+    /// Scale a gamma per unit of the asset price to the configured unit.
+    pub(crate) fn scale_gamma(&self, gamma: f64, asset_price: f64) -> f64 {
+        match self.gamma_unit {
+            GammaUnit::Absolute => gamma,
+            GammaUnit::Percent => gamma * asset_price * 0.01,
+        }
+    }
+}
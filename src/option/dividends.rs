@@ -0,0 +1,403 @@
+//! Options on assets paying discrete cash dividends.
+//!
+//! A flat payout rate spreads dividends evenly over the option's life, but
+//! single stocks pay a few known amounts, and their prices drop on the
+//! ex-dividend dates. The escrowed dividend model splits the asset price
+//! into the present value of the dividends paid before maturity, which is
+//! certain, and the rest, which follows a geometric Brownian motion with the
+//! option's volatility:
+//!
+//! - European options are valued with the Black-Scholes formula on the
+//!   asset price net of the dividends' present value.
+//! - American options are valued on a binomial tree of that net price,
+//!   adding back the present value of the dividends still to be paid when
+//!   comparing the exercise and continuation values, so the asset price
+//!   drops at the step of each ex-dividend date.
+//!
+//! The assignment risk of short options is checked analytically. A call is
+//! exercised right before an ex-dividend date when the dividend exceeds the
+//! extrinsic value the call has left after the asset price drops, and a put
+//! is exercised when the interest earned on the strike until maturity
+//! exceeds its extrinsic value.
+
+use crate::option::american::BinomialTree;
+use crate::option::exercise::AssignmentRisk;
+use crate::option::BSOption;
+use wasm_bindgen::prelude::*;
+
+/// An option on the asset, strike and maturity of a `BSOption`, whose asset
+/// pays discrete cash dividends on top of the option's payout rate.
+///
+/// ```rust
+/// use opcalc::option::dividends::DividendOption;
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+///
+/// // a dividend of 1.5 going ex on 2020/12/20
+/// let dividend_option = DividendOption::new(&option, vec![1_608_422_400], vec![1.5]);
+/// assert!(dividend_option.call_value() < option.call_value());
+/// ```
+#[wasm_bindgen]
+#[derive(Clone, Debug)]
+pub struct DividendOption {
+    option: BSOption,
+    ex_dividend_times: Vec<u32>,
+    amounts: Vec<f64>,
+}
+
+#[wasm_bindgen]
+impl DividendOption {
+    /// Create a `DividendOption`.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `option`: The option's terms and market inputs. Its asset price
+    ///   includes the upcoming dividends.
+    /// - `ex_dividend_times`: The ex-dividend dates, as Unix timestamps in
+    ///   seconds. Dates that are not after the option's current time, or that
+    ///   are after its maturity, are ignored.
+    /// - `amounts`: The cash amount of each dividend, paired with
+    ///   `ex_dividend_times` in order. Unpaired entries are ignored.
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        option: &BSOption,
+        ex_dividend_times: Vec<u32>,
+        amounts: Vec<f64>,
+    ) -> DividendOption {
+        let mut dividends: Vec<(u32, f64)> = ex_dividend_times
+            .into_iter()
+            .zip(amounts)
+            .filter(|&(time, _)| time > option.time_curr() && time <= option.time_maturity())
+            .collect();
+        dividends.sort_by_key(|&(time, _)| time);
+
+        DividendOption {
+            option: *option,
+            ex_dividend_times: dividends.iter().map(|&(time, _)| time).collect(),
+            amounts: dividends.iter().map(|&(_, amount)| amount).collect(),
+        }
+    }
+
+    /// Get the underlying option's market inputs and terms.
+    pub fn option(&self) -> BSOption {
+        self.option
+    }
+
+    /// Get the ex-dividend dates before maturity, as Unix timestamps in
+    /// seconds, in order.
+    pub fn ex_dividend_times(&self) -> Vec<u32> {
+        self.ex_dividend_times.clone()
+    }
+
+    /// Get the cash amounts of the dividends, paired with
+    /// `ex_dividend_times()`.
+    pub fn amounts(&self) -> Vec<f64> {
+        self.amounts.clone()
+    }
+
+    /// Get the present value of the dividends paid before maturity.
+    pub fn dividends_present_value(&self) -> f64 {
+        let rate = self.option.r_continuous();
+
+        self.dividend_years()
+            .map(|(time, amount)| amount * (-rate * time).exp())
+            .sum()
+    }
+
+    /// Get the equivalent `BSOption` for European pricing, whose asset price
+    /// is net of the present value of the dividends.
+    pub fn escrowed_option(&self) -> BSOption {
+        let mut escrowed = self.option;
+        escrowed.set_asset_price(self.option.asset_price() - self.dividends_present_value());
+        escrowed
+    }
+
+    /// Get the European call's value.
+    pub fn call_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("DividendOption::call_value");
+
+        self.escrowed_option().call_value()
+    }
+
+    /// Get the European put's value.
+    pub fn put_value(&self) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("DividendOption::put_value");
+
+        self.escrowed_option().put_value()
+    }
+
+    /// Get the American call's value, on a 200-step binomial tree. Use
+    /// `BinomialTree` to choose the number of steps.
+    pub fn american_call_value(&self) -> f64 {
+        BinomialTree::new().dividend_call_value(self)
+    }
+
+    /// Get the American put's value, on a 200-step binomial tree. Use
+    /// `BinomialTree` to choose the number of steps.
+    pub fn american_put_value(&self) -> f64 {
+        BinomialTree::new().dividend_put_value(self)
+    }
+
+    /// Get the assignment score of a short call: the next dividend over the
+    /// extrinsic value the call has left once the asset price drops on its
+    /// ex-dividend date. Zero if no dividend is paid before maturity, or if
+    /// the call is out of the money.
+    pub fn call_assignment_score(&self) -> f64 {
+        let (time_ex_dividend, dividend) =
+            match self.ex_dividend_times.first().zip(self.amounts.first()) {
+                Some((&time, &amount)) => (time, amount),
+                None => return 0.0,
+            };
+        if self.option.asset_price() <= self.option.strike() {
+            return 0.0;
+        }
+
+        // the call after the drop, with the later dividends escrowed
+        let mut after_dividend = self.option;
+        after_dividend.set_time_curr(time_ex_dividend);
+        after_dividend.set_asset_price(self.option.asset_price() - dividend);
+        let later_dividends = DividendOption::new(
+            &after_dividend,
+            self.ex_dividend_times[1..].to_vec(),
+            self.amounts[1..].to_vec(),
+        );
+        let intrinsic = (after_dividend.asset_price() - self.option.strike()).max(0.0);
+        let extrinsic = later_dividends.call_value() - intrinsic;
+
+        if extrinsic <= 0.0 {
+            f64::INFINITY
+        } else {
+            dividend / extrinsic
+        }
+    }
+
+    /// Get the assignment score of a short put: the interest earned on the
+    /// strike until maturity over the put's extrinsic value, which dividends
+    /// raise. Zero if the put is out of the money.
+    pub fn put_assignment_score(&self) -> f64 {
+        let intrinsic = self.option.strike() - self.option.asset_price();
+        if intrinsic <= 0.0 {
+            return 0.0;
+        }
+
+        let discount_factor = (-self.option.r_continuous() * self.option.time_to_maturity()).exp();
+        let interest_on_strike = self.option.strike() * (1.0 - discount_factor);
+        let extrinsic = self.put_value() - intrinsic;
+
+        if extrinsic <= 0.0 {
+            f64::INFINITY
+        } else {
+            interest_on_strike / extrinsic
+        }
+    }
+
+    /// Get the assignment risk of a short call, from
+    /// `call_assignment_score()`.
+    pub fn call_assignment_risk(&self) -> AssignmentRisk {
+        AssignmentRisk::from_score(self.call_assignment_score())
+    }
+
+    /// Get the assignment risk of a short put, from
+    /// `put_assignment_score()`.
+    pub fn put_assignment_risk(&self) -> AssignmentRisk {
+        AssignmentRisk::from_score(self.put_assignment_score())
+    }
+
+    /// Get the value of being able to exercise the call early, e.g. right
+    /// before an ex-dividend date, on a 200-step binomial tree. Use
+    /// `BinomialTree` to choose the number of steps.
+    pub fn call_early_exercise_premium(&self) -> f64 {
+        BinomialTree::new().dividend_call_early_exercise_premium(self)
+    }
+
+    /// Get the value of being able to exercise the put early, on a 200-step
+    /// binomial tree. Use `BinomialTree` to choose the number of steps.
+    pub fn put_early_exercise_premium(&self) -> f64 {
+        BinomialTree::new().dividend_put_early_exercise_premium(self)
+    }
+}
+
+impl DividendOption {
+    /// Get the ex-dividend dates in years from now, with their amounts.
+    pub(crate) fn dividend_years(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        let (time_curr, time_maturity) = (
+            self.option.time_curr() as f64,
+            self.option.time_maturity() as f64,
+        );
+
+        self.ex_dividend_times
+            .iter()
+            .zip(&self.amounts)
+            .map(move |(&time, &amount)| {
+                let years = self.option.time_to_maturity() * (time as f64 - time_curr)
+                    / (time_maturity - time_curr);
+                (years, amount)
+            })
+    }
+}
+
+#[cfg(test)]
+mod dividends_tests {
+    use crate::option::american::BinomialTree;
+    use crate::option::dividends::DividendOption;
+    use crate::option::exercise::AssignmentRisk;
+    use crate::option::BSOption;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.0)
+    }
+
+    #[test]
+    fn normalizes_dividends() {
+        let option = create_test_option();
+        let dividend_option = DividendOption::new(
+            &option,
+            vec![1609459200, 1606780800, 1620000000, 1608076800, 1610668800],
+            vec![1.0, 2.0, 3.0, 4.0],
+        );
+
+        assert_eq!(
+            dividend_option.ex_dividend_times(),
+            vec![1608076800, 1609459200]
+        );
+        assert_eq!(dividend_option.amounts(), vec![4.0, 1.0]);
+    }
+
+    #[test]
+    fn values_european_options_on_the_escrowed_asset_price() {
+        let option = create_test_option();
+        let dividend_option = DividendOption::new(&option, vec![1608422400], vec![1.5]);
+
+        let years = (1608422400.0 - 1606780800.0) / 31_536_000.0;
+        let present_value = 1.5 * (-0.005f64.ln_1p() * years).exp();
+        approx::assert_abs_diff_eq!(
+            dividend_option.dividends_present_value(),
+            present_value,
+            epsilon = 1e-12
+        );
+
+        let mut escrowed = option;
+        escrowed.set_asset_price(100.0 - present_value);
+        approx::assert_abs_diff_eq!(
+            dividend_option.call_value(),
+            escrowed.call_value(),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            dividend_option.put_value(),
+            escrowed.put_value(),
+            epsilon = 1e-12
+        );
+
+        // without dividends, nothing changes
+        let without_dividends = DividendOption::new(&option, vec![], vec![]);
+        approx::assert_abs_diff_eq!(
+            without_dividends.call_value(),
+            option.call_value(),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            without_dividends.american_put_value(),
+            option.american_put_value(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn drops_dividends_on_the_tree() {
+        // Hull, Options, Futures, and Other Derivatives: a 5-month American
+        // put with a 2.06 dividend in 3.5 months, on a 5-step tree
+        let option = BSOption::new(
+            1_600_000_000,
+            1_613_140_000,
+            52.0,
+            50.0,
+            0.1f64.exp_m1(),
+            0.4,
+            0.0,
+        );
+        let dividend_option = DividendOption::new(&option, vec![1_609_198_000], vec![2.06]);
+
+        let tree = BinomialTree::new().with_steps(5);
+        approx::assert_abs_diff_eq!(
+            tree.dividend_put_value(&dividend_option),
+            4.44,
+            epsilon = 5e-3
+        );
+    }
+
+    #[test]
+    fn values_early_exercise_ahead_of_dividends() {
+        let option = create_test_option();
+        let tree = BinomialTree::new().with_steps(500);
+
+        // a large dividend just before maturity makes calls worth exercising
+        let dividend_option = DividendOption::new(&option, vec![1610582400], vec![8.0]);
+        let american_call = tree.dividend_call_value(&dividend_option);
+        assert!(american_call > dividend_option.call_value() + 1e-2);
+        assert!(american_call < tree.call_value(&option));
+        approx::assert_abs_diff_eq!(
+            tree.dividend_call_early_exercise_premium(&dividend_option),
+            american_call - dividend_option.call_value(),
+            epsilon = 1e-2
+        );
+        assert!(dividend_option.call_early_exercise_premium() > 1e-2);
+
+        // dividends lower the asset price and raise put values
+        assert!(tree.dividend_put_value(&dividend_option) > tree.put_value(&option));
+        assert!(tree.dividend_put_value(&dividend_option) >= dividend_option.put_value());
+    }
+
+    #[test]
+    fn flags_calls_at_risk_of_assignment_before_dividends() {
+        let mut option = create_test_option();
+        option.set_asset_price(130.0);
+        let dividend_option = DividendOption::new(&option, vec![1608422400], vec![2.0]);
+
+        // the call after the drop keeps little more than its intrinsic value
+        let mut after_dividend = option;
+        after_dividend.set_time_curr(1608422400);
+        after_dividend.set_asset_price(128.0);
+        approx::assert_abs_diff_eq!(
+            dividend_option.call_assignment_score(),
+            2.0 / (after_dividend.call_value() - 23.0),
+            epsilon = 1e-12
+        );
+        assert_eq!(dividend_option.call_assignment_risk(), AssignmentRisk::High);
+
+        // small dividends, out-of-the-money calls and no dividends are safe
+        let small_dividend = DividendOption::new(&option, vec![1608422400], vec![0.01]);
+        assert_eq!(small_dividend.call_assignment_risk(), AssignmentRisk::Low);
+        let without_dividends = DividendOption::new(&option, vec![], vec![]);
+        approx::assert_abs_diff_eq!(without_dividends.call_assignment_score(), 0.0);
+        option.set_asset_price(100.0);
+        let out_of_the_money = DividendOption::new(&option, vec![1608422400], vec![2.0]);
+        assert_eq!(out_of_the_money.call_assignment_risk(), AssignmentRisk::Low);
+    }
+
+    #[test]
+    fn flags_puts_at_risk_of_assignment() {
+        let mut option = create_test_option();
+        option.set_interest(0.08);
+        option.set_asset_price(60.0);
+
+        let without_dividends = DividendOption::new(&option, vec![], vec![]);
+        assert_eq!(
+            without_dividends.put_assignment_risk(),
+            AssignmentRisk::High
+        );
+        assert!(without_dividends.put_assignment_score() > 1.0);
+
+        // dividends lower the asset price, so holders wait for them
+        let dividend_option = DividendOption::new(&option, vec![1610582400], vec![5.0]);
+        assert!(dividend_option.put_assignment_score() < without_dividends.put_assignment_score());
+        assert_eq!(dividend_option.put_assignment_risk(), AssignmentRisk::Low);
+
+        option.set_asset_price(110.0);
+        let out_of_the_money = DividendOption::new(&option, vec![], vec![]);
+        assert_eq!(out_of_the_money.put_assignment_risk(), AssignmentRisk::Low);
+    }
+}
@@ -0,0 +1,339 @@
+//! Dual numbers for forward-mode automatic differentiation.
+//!
+//! A dual number `a + b * eps` with `eps^2 = 0` carries a value together with
+//! its derivative with respect to one input. Evaluating a function on dual
+//! numbers yields the exact derivative alongside the value, without bumping.
+//!
+//! ```rust
+//! use opcalc::option::dual::{Dual64, Scalar};
+//!
+//! // d/dx (x * e^x) at x = 1
+//! let x = Dual64::variable(1.0);
+//! let y = x * x.exp();
+//!
+//! assert!((y.eps() - 2.0 * 1f64.exp()).abs() < 1e-12);
+//! ```
+
+use crate::utils::{normal_cdf, normal_pdf};
+use std::ops::{Add, Div, Mul, Neg, Sub};
+
+/// The numeric operations the pricing core needs, implemented for `f64` and
+/// `Dual64`.
+pub trait Scalar:
+    Copy
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+{
+    /// Get a number that does not depend on the differentiated input.
+    fn constant(value: f64) -> Self;
+    /// Get the exponential function of the number.
+    fn exp(self) -> Self;
+    /// Get the natural logarithm of the number.
+    fn ln(self) -> Self;
+    /// Get the square root of the number.
+    fn sqrt(self) -> Self;
+    /// Get the standard normal cumulative distribution at the number.
+    fn normal_cdf(self) -> Self;
+}
+
+impl Scalar for f64 {
+    fn constant(value: f64) -> f64 {
+        value
+    }
+
+    fn exp(self) -> f64 {
+        f64::exp(self)
+    }
+
+    fn ln(self) -> f64 {
+        f64::ln(self)
+    }
+
+    fn sqrt(self) -> f64 {
+        f64::sqrt(self)
+    }
+
+    fn normal_cdf(self) -> f64 {
+        normal_cdf(self)
+    }
+}
+
+/// A dual number: a value and its derivative with respect to one input.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Dual64 {
+    re: f64,
+    eps: f64,
+}
+
+impl Dual64 {
+    /// Create a dual number from a value and a derivative.
+    pub fn new(re: f64, eps: f64) -> Dual64 {
+        Dual64 { re, eps }
+    }
+
+    /// Create the input to differentiate with respect to, i.e. with a
+    /// derivative of `1`.
+    pub fn variable(re: f64) -> Dual64 {
+        Dual64 { re, eps: 1.0 }
+    }
+
+    /// Get the value.
+    pub fn re(&self) -> f64 {
+        self.re
+    }
+
+    /// Get the derivative.
+    pub fn eps(&self) -> f64 {
+        self.eps
+    }
+}
+
+impl Scalar for Dual64 {
+    fn constant(value: f64) -> Dual64 {
+        Dual64::new(value, 0.0)
+    }
+
+    fn exp(self) -> Dual64 {
+        let exp = self.re.exp();
+        Dual64::new(exp, exp * self.eps)
+    }
+
+    fn ln(self) -> Dual64 {
+        Dual64::new(self.re.ln(), self.eps / self.re)
+    }
+
+    fn sqrt(self) -> Dual64 {
+        let sqrt = self.re.sqrt();
+        Dual64::new(sqrt, self.eps / (2.0 * sqrt))
+    }
+
+    fn normal_cdf(self) -> Dual64 {
+        Dual64::new(normal_cdf(self.re), normal_pdf(self.re) * self.eps)
+    }
+}
+
+impl Add for Dual64 {
+    type Output = Dual64;
+
+    fn add(self, other: Dual64) -> Dual64 {
+        Dual64::new(self.re + other.re, self.eps + other.eps)
+    }
+}
+
+impl Sub for Dual64 {
+    type Output = Dual64;
+
+    fn sub(self, other: Dual64) -> Dual64 {
+        Dual64::new(self.re - other.re, self.eps - other.eps)
+    }
+}
+
+impl Mul for Dual64 {
+    type Output = Dual64;
+
+    fn mul(self, other: Dual64) -> Dual64 {
+        Dual64::new(
+            self.re * other.re,
+            self.re * other.eps + self.eps * other.re,
+        )
+    }
+}
+
+impl Div for Dual64 {
+    type Output = Dual64;
+
+    fn div(self, other: Dual64) -> Dual64 {
+        Dual64::new(
+            self.re / other.re,
+            (self.eps * other.re - self.re * other.eps) / (other.re * other.re),
+        )
+    }
+}
+
+impl Neg for Dual64 {
+    type Output = Dual64;
+
+    fn neg(self) -> Dual64 {
+        Dual64::new(-self.re, -self.eps)
+    }
+}
+
+/// A hyper-dual number: a value, its derivatives with respect to two inputs,
+/// and the mixed second derivative with respect to both.
+///
+/// Setting both inputs to the same variable yields the second derivative
+/// with respect to that variable.
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct HyperDual64 {
+    re: f64,
+    eps1: f64,
+    eps2: f64,
+    eps1eps2: f64,
+}
+
+impl HyperDual64 {
+    /// Create a hyper-dual number from a value, two first derivatives and
+    /// the mixed second derivative.
+    pub fn new(re: f64, eps1: f64, eps2: f64, eps1eps2: f64) -> HyperDual64 {
+        HyperDual64 {
+            re,
+            eps1,
+            eps2,
+            eps1eps2,
+        }
+    }
+
+    /// Get the value.
+    pub fn re(&self) -> f64 {
+        self.re
+    }
+
+    /// Get the derivative with respect to the first input.
+    pub fn eps1(&self) -> f64 {
+        self.eps1
+    }
+
+    /// Get the derivative with respect to the second input.
+    pub fn eps2(&self) -> f64 {
+        self.eps2
+    }
+
+    /// Get the mixed second derivative with respect to both inputs.
+    pub fn eps1eps2(&self) -> f64 {
+        self.eps1eps2
+    }
+
+    /// Apply a function with value `f`, first derivative `df` and second
+    /// derivative `d2f` at `self.re`, following the chain rule.
+    fn chain(self, f: f64, df: f64, d2f: f64) -> HyperDual64 {
+        HyperDual64::new(
+            f,
+            df * self.eps1,
+            df * self.eps2,
+            df * self.eps1eps2 + d2f * self.eps1 * self.eps2,
+        )
+    }
+}
+
+impl Scalar for HyperDual64 {
+    fn constant(value: f64) -> HyperDual64 {
+        HyperDual64::new(value, 0.0, 0.0, 0.0)
+    }
+
+    fn exp(self) -> HyperDual64 {
+        let exp = self.re.exp();
+        self.chain(exp, exp, exp)
+    }
+
+    fn ln(self) -> HyperDual64 {
+        self.chain(self.re.ln(), 1.0 / self.re, -1.0 / (self.re * self.re))
+    }
+
+    fn sqrt(self) -> HyperDual64 {
+        let sqrt = self.re.sqrt();
+        self.chain(sqrt, 0.5 / sqrt, -0.25 / (sqrt * self.re))
+    }
+
+    fn normal_cdf(self) -> HyperDual64 {
+        let pdf = normal_pdf(self.re);
+        self.chain(normal_cdf(self.re), pdf, -self.re * pdf)
+    }
+}
+
+impl Add for HyperDual64 {
+    type Output = HyperDual64;
+
+    fn add(self, other: HyperDual64) -> HyperDual64 {
+        HyperDual64::new(
+            self.re + other.re,
+            self.eps1 + other.eps1,
+            self.eps2 + other.eps2,
+            self.eps1eps2 + other.eps1eps2,
+        )
+    }
+}
+
+impl Sub for HyperDual64 {
+    type Output = HyperDual64;
+
+    fn sub(self, other: HyperDual64) -> HyperDual64 {
+        self + -other
+    }
+}
+
+impl Mul for HyperDual64 {
+    type Output = HyperDual64;
+
+    fn mul(self, other: HyperDual64) -> HyperDual64 {
+        HyperDual64::new(
+            self.re * other.re,
+            self.re * other.eps1 + self.eps1 * other.re,
+            self.re * other.eps2 + self.eps2 * other.re,
+            self.re * other.eps1eps2
+                + self.eps1 * other.eps2
+                + self.eps2 * other.eps1
+                + self.eps1eps2 * other.re,
+        )
+    }
+}
+
+impl Div for HyperDual64 {
+    type Output = HyperDual64;
+
+    fn div(self, other: HyperDual64) -> HyperDual64 {
+        let reciprocal = 1.0 / other.re;
+        self * other.chain(
+            reciprocal,
+            -reciprocal * reciprocal,
+            2.0 * reciprocal * reciprocal * reciprocal,
+        )
+    }
+}
+
+impl Neg for HyperDual64 {
+    type Output = HyperDual64;
+
+    fn neg(self) -> HyperDual64 {
+        HyperDual64::new(-self.re, -self.eps1, -self.eps2, -self.eps1eps2)
+    }
+}
+
+#[cfg(test)]
+mod dual_tests {
+    use crate::option::dual::{Dual64, HyperDual64, Scalar};
+
+    #[test]
+    fn differentiates_elementary_functions() {
+        let x = Dual64::variable(2.0);
+
+        // d/dx (ln(x) * sqrt(x) / (1 - x)) at x = 2
+        let y = x.ln() * x.sqrt() / (Dual64::constant(1.0) - x);
+        let expected = (0.5f64.ln() - 2.0 + 2.0 * 2.0 * 2f64.ln()) / (2.0 * 2f64.sqrt());
+
+        approx::assert_abs_diff_eq!(y.re(), -2f64.ln() * 2f64.sqrt(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(y.eps(), expected, epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(x.normal_cdf().eps(), 0.05399096651318806, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn differentiates_twice_with_hyper_duals() {
+        // f(x, y) = x^2 * e^y / y at (3, 2)
+        let x = HyperDual64::new(3.0, 1.0, 0.0, 0.0);
+        let y = HyperDual64::new(2.0, 0.0, 1.0, 0.0);
+        let f = x * x * y.exp() / y;
+
+        // d2f/dxdy = 2x * e^y * (y - 1) / y^2
+        approx::assert_abs_diff_eq!(f.eps1eps2(), 6.0 * 2f64.exp() / 4.0, epsilon = 1e-12);
+
+        // d2/dx2 N(x) = -x * n(x)
+        let x = HyperDual64::new(0.5, 1.0, 1.0, 0.0);
+        approx::assert_abs_diff_eq!(
+            x.normal_cdf().eps1eps2(),
+            -0.5 * crate::utils::normal_pdf(0.5),
+            epsilon = 1e-12
+        );
+    }
+}
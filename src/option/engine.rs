@@ -0,0 +1,355 @@
+//! Interchangeable pricing engines for vanilla options.
+//!
+//! A `PricingEngine` values the call and put described by a `BSOption`.
+//! The closed-form Black-Scholes formulas, binomial trees, the
+//! Crank-Nicolson PDE solver and the Monte Carlo pricers all implement it,
+//! so code written against the trait can switch between European and
+//! American pricing, or between exact and simulated values, by swapping the
+//! engine. Other crates can implement it to plug in their own models.
+//!
+//! Engines only need to implement `price()`: greeks default to finite
+//! differences of it, with the option's `NumericalConfig`.
+//!
+//! A `Model` selects one of the built-in engines for a `BSOption`, whose
+//! `call_value()`, `put_value()` and `greeks()` then use it.
+
+pub use crate::option::opcalc::op_calc::OptionResults;
+
+use crate::montecarlo::european::EuropeanMonteCarlo;
+use crate::montecarlo::lsm::LongstaffSchwartz;
+use crate::option::american::BinomialTree;
+use crate::option::greeks::Greeks;
+use crate::option::opcalc::op_calc;
+use crate::option::pde::CrankNicolson;
+use crate::option::{BSOption, OptionType};
+use wasm_bindgen::prelude::*;
+
+/// A model that values vanilla calls and puts.
+///
+/// ```rust
+/// use opcalc::option::american::BinomialTree;
+/// use opcalc::option::engine::{BlackScholesEngine, PricingEngine};
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.005, 0.23, 0.0);
+///
+/// let engines: Vec<Box<dyn PricingEngine>> =
+///     vec![Box::new(BlackScholesEngine), Box::new(BinomialTree::new())];
+/// for engine in &engines {
+///     let values = engine.price(&option);
+///     assert!(values.call > 0.0 && values.put > 0.0);
+/// }
+/// ```
+pub trait PricingEngine {
+    /// Get the values of the call and the put on `option`.
+    fn price(&self, option: &BSOption) -> OptionResults;
+
+    /// Get the value and greeks of the call or put on `option`, following
+    /// the option's `GreekConventions`. Defaults to bumping the inputs of
+    /// `price()` by the option's `NumericalConfig`; noisy engines, such as
+    /// Monte Carlo, need larger bumps than the defaults.
+    fn greeks(&self, option: &BSOption, option_type: OptionType) -> Greeks {
+        Greeks::bumped(option, option_type, |option| {
+            let values = self.price(option);
+            (values.call, values.put)
+        })
+    }
+}
+
+/// The closed-form Black-Scholes formulas, as used by `BSOption` itself.
+#[derive(Copy, Clone, Debug, Default)]
+pub struct BlackScholesEngine;
+
+impl PricingEngine for BlackScholesEngine {
+    fn price(&self, option: &BSOption) -> OptionResults {
+        op_calc::calculate_option_values(option)
+    }
+
+    /// The analytic greeks, see `BSOption::greeks()`.
+    fn greeks(&self, option: &BSOption, option_type: OptionType) -> Greeks {
+        Greeks::new(option, option_type)
+    }
+}
+
+/// American values on the tree.
+impl PricingEngine for BinomialTree {
+    fn price(&self, option: &BSOption) -> OptionResults {
+        OptionResults {
+            call: self.call_value(option),
+            put: self.put_value(option),
+        }
+    }
+
+    /// Delta, gamma and theta from the tree's nodes, see
+    /// `BinomialTree::call_greeks()`.
+    fn greeks(&self, option: &BSOption, option_type: OptionType) -> Greeks {
+        self.lattice_greeks(option, option_type)
+    }
+}
+
+/// European or American values, as set on the solver.
+impl PricingEngine for CrankNicolson {
+    fn price(&self, option: &BSOption) -> OptionResults {
+        OptionResults {
+            call: self.call_value(option),
+            put: self.put_value(option),
+        }
+    }
+
+    /// Delta, gamma and theta from the grid, see
+    /// `CrankNicolson::call_greeks()`.
+    fn greeks(&self, option: &BSOption, option_type: OptionType) -> Greeks {
+        self.grid_greeks(option, option_type)
+    }
+}
+
+/// European values, simulating the option's own geometric Brownian motion.
+impl PricingEngine for EuropeanMonteCarlo {
+    fn price(&self, option: &BSOption) -> OptionResults {
+        OptionResults {
+            call: self.call_value(option, option.strike()).value(),
+            put: self.put_value(option, option.strike()).value(),
+        }
+    }
+}
+
+/// American values, simulating the option's own geometric Brownian motion.
+impl PricingEngine for LongstaffSchwartz {
+    fn price(&self, option: &BSOption) -> OptionResults {
+        OptionResults {
+            call: self.call_value(option, option.strike()).value(),
+            put: self.put_value(option, option.strike()).value(),
+        }
+    }
+}
+
+#[derive(Copy, Clone, Debug, PartialEq)]
+enum ModelKind {
+    BlackScholes,
+    Tree(BinomialTree),
+    Pde(CrankNicolson),
+    MonteCarlo { paths: usize, seed: u64 },
+}
+
+/// The built-in engine used by a `BSOption`'s `call_value()`, `put_value()`
+/// and `greeks()`. Defaults to Black-Scholes.
+///
+/// ```rust
+/// use opcalc::option::builder::BSOptionBuilder;
+/// use opcalc::option::engine::Model;
+///
+/// let option = BSOptionBuilder::new()
+///     .with_asset_price(100.0)
+///     .with_strike(105.0)
+///     .with_interest(0.05)
+///     .with_volatility(0.23)
+///     .with_current_time(1_606_780_800)
+///     .with_maturity_time(1_610_668_800)
+///     .finalize()
+///     .unwrap();
+///
+/// // the same option, valued as an American option
+/// let mut american = option;
+/// american.set_model(Model::crr(500));
+/// assert!(american.put_value() > option.put_value());
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct Model {
+    kind: ModelKind,
+}
+
+impl Default for Model {
+    fn default() -> Model {
+        Model::black_scholes()
+    }
+}
+
+#[wasm_bindgen]
+impl Model {
+    /// European values and analytic greeks, from the closed-form
+    /// Black-Scholes formulas.
+    pub fn black_scholes() -> Model {
+        Model {
+            kind: ModelKind::BlackScholes,
+        }
+    }
+
+    /// American values on a Cox-Ross-Rubinstein tree with `steps` time
+    /// steps (at least one), see `BinomialTree`.
+    pub fn crr(steps: usize) -> Model {
+        Model::binomial_tree(&BinomialTree::new().with_steps(steps))
+    }
+
+    /// American values on `tree`, e.g. to use Leisen-Reimer trees, smoothing
+    /// or Richardson extrapolation.
+    pub fn binomial_tree(tree: &BinomialTree) -> Model {
+        Model {
+            kind: ModelKind::Tree(*tree),
+        }
+    }
+
+    /// European or American values from solving the Black-Scholes PDE with
+    /// `solver`.
+    pub fn crank_nicolson(solver: &CrankNicolson) -> Model {
+        Model {
+            kind: ModelKind::Pde(*solver),
+        }
+    }
+
+    /// European values simulated over `paths` paths (at least two) from the
+    /// random `seed`, see `EuropeanMonteCarlo`. Greeks reuse the seed for
+    /// every bump, but still need larger bumps than the defaults.
+    pub fn monte_carlo(paths: usize, seed: u64) -> Model {
+        Model {
+            kind: ModelKind::MonteCarlo {
+                paths: paths.max(2),
+                seed,
+            },
+        }
+    }
+
+    /// Whether this is the Black-Scholes model.
+    pub fn is_black_scholes(&self) -> bool {
+        self.kind == ModelKind::BlackScholes
+    }
+}
+
+impl PricingEngine for Model {
+    fn price(&self, option: &BSOption) -> OptionResults {
+        match self.kind {
+            ModelKind::BlackScholes => BlackScholesEngine.price(option),
+            ModelKind::Tree(tree) => tree.price(option),
+            ModelKind::Pde(solver) => solver.price(option),
+            ModelKind::MonteCarlo { paths, seed } => EuropeanMonteCarlo::new()
+                .with_paths(paths)
+                .with_seed(seed)
+                .price(option),
+        }
+    }
+
+    fn greeks(&self, option: &BSOption, option_type: OptionType) -> Greeks {
+        match self.kind {
+            ModelKind::BlackScholes => BlackScholesEngine.greeks(option, option_type),
+            ModelKind::Tree(tree) => PricingEngine::greeks(&tree, option, option_type),
+            ModelKind::Pde(solver) => PricingEngine::greeks(&solver, option, option_type),
+            ModelKind::MonteCarlo { .. } => Greeks::bumped(option, option_type, |option| {
+                let values = self.price(option);
+                (values.call, values.put)
+            }),
+        }
+    }
+}
+
+#[cfg(test)]
+mod engine_tests {
+    use crate::montecarlo::european::EuropeanMonteCarlo;
+    use crate::option::american::BinomialTree;
+    use crate::option::engine::{BlackScholesEngine, Model, OptionResults, PricingEngine};
+    use crate::option::numerical::{DifferenceScheme, NumericalConfig};
+    use crate::option::pde::CrankNicolson;
+    use crate::option::{BSOption, OptionType};
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.02)
+    }
+
+    /// Black-Scholes values, with the default bumped greeks.
+    struct BumpedBlackScholes;
+
+    impl PricingEngine for BumpedBlackScholes {
+        fn price(&self, option: &BSOption) -> OptionResults {
+            BlackScholesEngine.price(option)
+        }
+    }
+
+    #[test]
+    fn interchanges_engines() {
+        let option = create_test_option();
+        let engines: Vec<(Box<dyn PricingEngine>, f64)> = vec![
+            (Box::new(BlackScholesEngine), 1e-12),
+            (Box::new(BinomialTree::new().with_steps(1000)), 1e-2),
+            (Box::new(CrankNicolson::new()), 1e-3),
+            (Box::new(EuropeanMonteCarlo::new().with_paths(50_000)), 0.1),
+        ];
+
+        for (engine, tolerance) in &engines {
+            let values = engine.price(&option);
+            approx::assert_abs_diff_eq!(values.call, option.call_value(), epsilon = *tolerance);
+            approx::assert_abs_diff_eq!(values.put, option.put_value(), epsilon = *tolerance);
+        }
+    }
+
+    #[test]
+    fn bumps_greeks_by_default() {
+        let mut option = create_test_option();
+        option.set_numerical_config(
+            NumericalConfig::new()
+                .with_price_bump(0.01)
+                .with_volatility_bump(0.001)
+                .with_difference_scheme(DifferenceScheme::Central),
+        );
+
+        for &option_type in &[OptionType::Call, OptionType::Put] {
+            let analytic = BlackScholesEngine.greeks(&option, option_type);
+            let bumped = BumpedBlackScholes.greeks(&option, option_type);
+
+            approx::assert_abs_diff_eq!(bumped.value(), analytic.value(), epsilon = 1e-12);
+            approx::assert_abs_diff_eq!(bumped.delta(), analytic.delta(), epsilon = 1e-6);
+            approx::assert_abs_diff_eq!(bumped.gamma(), analytic.gamma(), epsilon = 1e-5);
+            approx::assert_abs_diff_eq!(bumped.vega(), analytic.vega(), epsilon = 1e-5);
+            approx::assert_abs_diff_eq!(bumped.theta(), analytic.theta(), epsilon = 1e-4);
+            approx::assert_abs_diff_eq!(bumped.rho(), analytic.rho(), epsilon = 1e-5);
+            approx::assert_abs_diff_eq!(bumped.epsilon(), analytic.epsilon(), epsilon = 1e-5);
+            approx::assert_abs_diff_eq!(bumped.vanna(), analytic.vanna(), epsilon = 1e-5);
+            approx::assert_abs_diff_eq!(bumped.volga(), analytic.volga(), epsilon = 1e-4);
+            approx::assert_abs_diff_eq!(bumped.veta(), analytic.veta(), epsilon = 1e-4);
+        }
+    }
+
+    #[test]
+    fn dispatches_to_the_selected_model() {
+        let european = create_test_option();
+        approx::assert_abs_diff_eq!(
+            european.call_value(),
+            BlackScholesEngine.price(&european).call,
+            epsilon = 1e-12
+        );
+
+        let mut american = european;
+        american.set_model(Model::crr(500));
+        let tree = BinomialTree::new().with_steps(500);
+        approx::assert_abs_diff_eq!(
+            american.put_value(),
+            tree.put_value(&european),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            american.greeks(OptionType::Put).value(),
+            tree.put_value(&european),
+            epsilon = 1e-12
+        );
+        assert!(american.put_value() > european.put_value());
+
+        let mut simulated = european;
+        simulated.set_model(Model::monte_carlo(50_000, 7));
+        let monte_carlo = EuropeanMonteCarlo::new().with_paths(50_000).with_seed(7);
+        approx::assert_abs_diff_eq!(
+            simulated.call_value(),
+            monte_carlo.call_value(&european, 105.0).value(),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(simulated.call_value(), european.call_value(), epsilon = 0.1);
+    }
+
+    #[test]
+    fn simulates_shifted_options() {
+        let mut option = BSOption::new(1606780800, 1638316800, 0.5, 0.5, 0.005, 0.2, 0.0);
+        option.set_shift(2.0);
+        let mut simulated = option;
+        simulated.set_model(Model::monte_carlo(200_000, 7));
+
+        approx::assert_abs_diff_eq!(simulated.call_value(), option.call_value(), epsilon = 5e-3);
+        approx::assert_abs_diff_eq!(simulated.put_value(), option.put_value(), epsilon = 5e-3);
+    }
+}
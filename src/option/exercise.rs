@@ -0,0 +1,204 @@
+//! Early exercise advisories around discrete dividends, and the matching
+//! assignment risk of short positions.
+
+use crate::option::BSOption;
+use wasm_bindgen::prelude::*;
+
+/// A recommendation on whether to exercise an American option early ahead
+/// of an ex-dividend date, along with the numbers supporting it.
+///
+/// Exercise is recommended when the exercise value exceeds the value of
+/// holding on to the option (the continuation value). The continuation
+/// value is approximated by the European Black-Scholes value, which is a
+/// lower bound for the American one, so a positive `advantage()` close to
+/// zero should be read as "borderline".
+///
+/// See `BSOption::call_dividend_exercise_advice()` and
+/// `BSOption::put_dividend_exercise_advice()`.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct ExerciseAdvice {
+    exercise_value: f64,
+    continuation_value: f64,
+    dividend: f64,
+    interest_on_strike: f64,
+}
+
+#[wasm_bindgen]
+impl ExerciseAdvice {
+    /// Whether exercising early is optimal.
+    pub fn should_exercise(&self) -> bool {
+        self.advantage() > 0.0
+    }
+
+    /// Get the amount by which exercising beats holding on to the option.
+    /// Negative values indicate that holding on is better.
+    pub fn advantage(&self) -> f64 {
+        self.exercise_value - self.continuation_value
+    }
+
+    /// Get the value received by exercising.
+    pub fn exercise_value(&self) -> f64 {
+        self.exercise_value
+    }
+
+    /// Get the value of holding on to the option instead of exercising.
+    pub fn continuation_value(&self) -> f64 {
+        self.continuation_value
+    }
+
+    /// Get the upcoming dividend amount.
+    pub fn dividend(&self) -> f64 {
+        self.dividend
+    }
+
+    /// Get the interest earned on the strike over the relevant period: until
+    /// maturity for calls (the cost of paying the strike early), and until
+    /// the ex-dividend date for puts (the gain of receiving it early).
+    pub fn interest_on_strike(&self) -> f64 {
+        self.interest_on_strike
+    }
+}
+
+/// How likely a short American option is to be assigned early, from its
+/// assignment score: how much the holder gains by exercising, relative to
+/// what they give up. A score of one or more means exercising pays.
+///
+/// See `DividendOption::call_assignment_risk()` and
+/// `DividendOption::put_assignment_risk()`.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum AssignmentRisk {
+    /// The score is below one half. Exercising early would cost the holder.
+    Low,
+    /// The score is between one half and one. A small move in the asset
+    /// price or volatility could make exercising pay.
+    Moderate,
+    /// The score is one or more. Exercising early pays, so assignment is
+    /// likely.
+    High,
+}
+
+impl AssignmentRisk {
+    pub(crate) fn from_score(score: f64) -> AssignmentRisk {
+        if score >= 1.0 {
+            AssignmentRisk::High
+        } else if score >= 0.5 {
+            AssignmentRisk::Moderate
+        } else {
+            AssignmentRisk::Low
+        }
+    }
+}
+
+impl ExerciseAdvice {
+    /// Compares exercising a call right before the ex-dividend date with
+    /// holding it through the dividend's drop in the asset price.
+    pub(crate) fn for_call(
+        option: &BSOption,
+        dividend: f64,
+        time_ex_dividend: u32,
+    ) -> Option<ExerciseAdvice> {
+        if !Self::is_before_maturity(option, time_ex_dividend) {
+            return None;
+        }
+
+        let mut after_dividend = *option;
+        after_dividend.set_time_curr(time_ex_dividend);
+        after_dividend.set_asset_price(option.asset_price - dividend);
+
+        Some(ExerciseAdvice {
+            exercise_value: option.asset_price - option.strike,
+            continuation_value: after_dividend.call_value(),
+            dividend,
+            interest_on_strike: option.strike
+                * (1.0 - (-after_dividend.r_continuous() * after_dividend.time_to_maturity).exp()),
+        })
+    }
+
+    /// Compares exercising a put now with holding it, where the upcoming
+    /// dividend lowers the asset price and so favors holding.
+    pub(crate) fn for_put(
+        option: &BSOption,
+        dividend: f64,
+        time_ex_dividend: u32,
+    ) -> Option<ExerciseAdvice> {
+        if !Self::is_before_maturity(option, time_ex_dividend) {
+            return None;
+        }
+
+        let mut until_dividend = *option;
+        until_dividend.set_time_maturity(time_ex_dividend);
+        let discount_factor = (-option.r_continuous() * until_dividend.time_to_maturity).exp();
+
+        // escrow the dividend: the asset price net of the dividend's present value
+        let mut holding = *option;
+        holding.set_asset_price(option.asset_price - dividend * discount_factor);
+
+        Some(ExerciseAdvice {
+            exercise_value: option.strike - option.asset_price,
+            continuation_value: holding.put_value(),
+            dividend,
+            interest_on_strike: option.strike * (1.0 - discount_factor),
+        })
+    }
+
+    fn is_before_maturity(option: &BSOption, time_ex_dividend: u32) -> bool {
+        option.time_curr <= time_ex_dividend && time_ex_dividend < option.time_maturity
+    }
+}
+
+#[cfg(test)]
+mod exercise_tests {
+    use crate::option::BSOption;
+
+    // 2020/12/01 00:00:00, ex-dividend on 2020/12/02 00:00:00
+    const TIME_CURR: u32 = 1606780800;
+    const TIME_EX_DIVIDEND: u32 = 1606867200;
+    const TIME_MATURITY: u32 = 1610668800;
+
+    #[test]
+    fn advises_exercising_deep_calls_before_large_dividends() {
+        let option = BSOption::new(TIME_CURR, TIME_MATURITY, 150.0, 100.0, 0.005, 0.2, 0.0);
+
+        let advice = option
+            .call_dividend_exercise_advice(5.0, TIME_EX_DIVIDEND)
+            .unwrap();
+        assert!(advice.should_exercise());
+        approx::assert_abs_diff_eq!(advice.exercise_value(), 50.0);
+        assert!(advice.dividend() > advice.interest_on_strike());
+
+        let advice = option
+            .call_dividend_exercise_advice(0.01, TIME_EX_DIVIDEND)
+            .unwrap();
+        assert!(!advice.should_exercise());
+    }
+
+    #[test]
+    fn advises_holding_puts_through_dividends() {
+        let option = BSOption::new(TIME_CURR, TIME_MATURITY, 50.0, 100.0, 0.05, 0.2, 0.0);
+
+        let advice = option
+            .put_dividend_exercise_advice(0.0, TIME_EX_DIVIDEND)
+            .unwrap();
+        assert!(advice.should_exercise());
+
+        let advice = option
+            .put_dividend_exercise_advice(2.0, TIME_EX_DIVIDEND)
+            .unwrap();
+        assert!(!advice.should_exercise());
+        assert!(advice.dividend() > advice.interest_on_strike());
+    }
+
+    #[test]
+    fn ignores_dividends_outside_of_the_options_life() {
+        let option = BSOption::new(TIME_CURR, TIME_MATURITY, 100.0, 100.0, 0.005, 0.2, 0.0);
+
+        assert!(option
+            .call_dividend_exercise_advice(1.0, TIME_MATURITY + 1)
+            .is_none());
+        assert!(option
+            .put_dividend_exercise_advice(1.0, TIME_CURR - 1)
+            .is_none());
+    }
+}
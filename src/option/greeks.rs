@@ -0,0 +1,437 @@
+//! All of an option's greeks, calculated in a single pass.
+
+use crate::option::american::LatticeGreeks;
+use crate::option::numerical::DifferenceScheme;
+use crate::option::opcalc::op_calc;
+use crate::option::{BSOption, OptionType};
+use crate::utils::normal_pdf;
+use wasm_bindgen::prelude::*;
+
+/// The value and greeks of a call or put under the option's model.
+///
+/// Under Black-Scholes, all greeks are analytic, whatever the option's
+/// `GreekMethod`, sharing `d1`, `d2` and the discount factors between them.
+/// Under other models, they are finite differences of the model's values,
+/// except for the delta, gamma and theta that trees and grids read off their
+/// nodes. The individual getters on `BSOption`, e.g. `call_delta()`, always
+/// use Black-Scholes, so they only match these under the default model.
+///
+/// Either way, the greeks use the option's `GreekConventions`. By default,
+/// vega, rho, epsilon, vanna and volga are per 1% change, while theta and
+/// veta are per calendar day.
+///
+/// See `BSOption::greeks()`.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct Greeks {
+    value: f64,
+    delta: f64,
+    gamma: f64,
+    vega: f64,
+    theta: f64,
+    rho: f64,
+    epsilon: f64,
+    vanna: f64,
+    volga: f64,
+    veta: f64,
+    lambda: f64,
+}
+
+#[wasm_bindgen]
+impl Greeks {
+    /// Get the option's value.
+    pub fn value(&self) -> f64 {
+        self.value
+    }
+
+    /// Get the option's delta.
+    pub fn delta(&self) -> f64 {
+        self.delta
+    }
+
+    /// Get the option's gamma.
+    pub fn gamma(&self) -> f64 {
+        self.gamma
+    }
+
+    /// Get the option's vega.
+    pub fn vega(&self) -> f64 {
+        self.vega
+    }
+
+    /// Get the option's continuous-time theta, per day. This matches
+    /// `BSOption::call_theta_per_day()` rather than the 1-day value change of
+    /// `BSOption::call_theta()`.
+    pub fn theta(&self) -> f64 {
+        self.theta
+    }
+
+    /// Get the option's rho, i.e. its sensitivity to the continuously
+    /// compounded interest rate.
+    pub fn rho(&self) -> f64 {
+        self.rho
+    }
+
+    /// Get the option's epsilon, i.e. its sensitivity to the continuously
+    /// compounded payout rate.
+    pub fn epsilon(&self) -> f64 {
+        self.epsilon
+    }
+
+    /// Get the option's vanna, i.e. the change in delta for a change in
+    /// volatility.
+    pub fn vanna(&self) -> f64 {
+        self.vanna
+    }
+
+    /// Get the option's volga, i.e. the change in vega for a change in
+    /// volatility.
+    pub fn volga(&self) -> f64 {
+        self.volga
+    }
+
+    /// Get the option's veta, i.e. the change in vega as one day passes.
+    pub fn veta(&self) -> f64 {
+        self.veta
+    }
+
+    /// Get the option's lambda (elasticity).
+    pub fn lambda(&self) -> f64 {
+        self.lambda
+    }
+}
+
+impl Greeks {
+    /// Scale every greek by `factor`, e.g. a signed position size. Lambda is
+    /// a ratio and stays unchanged.
+    pub(crate) fn scaled(&self, factor: f64) -> Greeks {
+        Greeks {
+            value: self.value * factor,
+            delta: self.delta * factor,
+            gamma: self.gamma * factor,
+            vega: self.vega * factor,
+            theta: self.theta * factor,
+            rho: self.rho * factor,
+            epsilon: self.epsilon * factor,
+            vanna: self.vanna * factor,
+            volga: self.volga * factor,
+            veta: self.veta * factor,
+            lambda: self.lambda,
+        }
+    }
+
+    /// Approximate the greeks of the call or put valued by `price` with
+    /// finite differences, using the option's `NumericalConfig`. Rates are
+    /// bumped by one basis point.
+    pub(crate) fn bumped<F>(option: &BSOption, option_type: OptionType, price: F) -> Greeks
+    where
+        F: Fn(&BSOption) -> (f64, f64),
+    {
+        const RATE_BUMP: f64 = 1e-4;
+        const TIMESTAMP_ONE_YEAR: f64 = 31_536_000.0;
+
+        let config = option.numerical_config;
+        let conventions = option.greek_conventions;
+        let pick = |(call, put): (f64, f64)| match option_type {
+            OptionType::Call => call,
+            OptionType::Put => put,
+        };
+        let reprice = |update: &dyn Fn(&mut BSOption)| {
+            let mut option_prime = *option;
+            update(&mut option_prime);
+            price(&option_prime)
+        };
+
+        // first derivatives, around `option` with `update` applied
+        let delta_of = |update: &dyn Fn(&mut BSOption)| {
+            let mut base = *option;
+            update(&mut base);
+            config.differentiate(
+                |asset_price| {
+                    let mut option_prime = base;
+                    option_prime.set_asset_price(asset_price);
+                    price(&option_prime)
+                },
+                base.asset_price,
+                config.price_bump(),
+            )
+        };
+        let vega_of = |update: &dyn Fn(&mut BSOption)| {
+            let mut base = *option;
+            update(&mut base);
+            config.differentiate(
+                |volatility| {
+                    let mut option_prime = base;
+                    option_prime.set_volatility(volatility);
+                    price(&option_prime)
+                },
+                base.volatility,
+                config.volatility_bump(),
+            )
+        };
+        let unchanged = |_: &mut BSOption| {};
+
+        let value = pick(price(option));
+        let delta = pick(delta_of(&unchanged));
+        let gamma = pick(config.differentiate(
+            |asset_price| {
+                delta_of(&|option_prime: &mut BSOption| option_prime.set_asset_price(asset_price))
+            },
+            option.asset_price,
+            config.price_bump(),
+        ));
+        let vega = pick(vega_of(&unchanged));
+        let vanna = pick(config.differentiate(
+            |volatility| {
+                delta_of(&|option_prime: &mut BSOption| option_prime.set_volatility(volatility))
+            },
+            option.volatility,
+            config.volatility_bump(),
+        ));
+        let volga = pick(config.differentiate(
+            |volatility| {
+                vega_of(&|option_prime: &mut BSOption| option_prime.set_volatility(volatility))
+            },
+            option.volatility,
+            config.volatility_bump(),
+        ));
+
+        // time derivatives, scaled to one day passing
+        let seconds_per_day = TIMESTAMP_ONE_YEAR / conventions.days_per_year();
+        let set_time = |time_curr: f64| {
+            move |option_prime: &mut BSOption| option_prime.set_time_curr(time_curr as u32)
+        };
+        let time_curr = f64::from(option.time_curr);
+        let time_bump = f64::from(config.time_bump());
+        let theta =
+            pick(config.differentiate(|time| reprice(&set_time(time)), time_curr, time_bump));
+        let veta =
+            pick(config.differentiate(|time| vega_of(&set_time(time)), time_curr, time_bump));
+
+        // rate derivatives, with respect to the continuously compounded rates
+        let rho = pick(config.differentiate(
+            |rate| reprice(&|option_prime: &mut BSOption| option_prime.set_interest(rate.exp_m1())),
+            option.r_continuous(),
+            RATE_BUMP,
+        ));
+        let epsilon = pick(config.differentiate(
+            |rate| {
+                reprice(&|option_prime: &mut BSOption| option_prime.set_payout_rate(rate.exp_m1()))
+            },
+            option.div_continuous(),
+            RATE_BUMP,
+        ));
+
+        let volatility_unit = conventions.volatility_unit();
+        Greeks {
+            value,
+            delta,
+            gamma: conventions.scale_gamma(gamma, option.asset_price),
+            vega: vega * volatility_unit,
+            theta: theta * seconds_per_day,
+            rho: rho * conventions.rate_unit(),
+            epsilon: epsilon * conventions.rate_unit(),
+            vanna: vanna * volatility_unit,
+            volga: volga * volatility_unit.powi(2),
+            veta: veta * volatility_unit * seconds_per_day,
+            lambda: delta * option.asset_price / value,
+        }
+    }
+
+    /// Combine the delta, gamma and theta read off a tree's nodes by
+    /// `lattice` with finite differences of re-run trees for the other
+    /// greeks, using the option's `NumericalConfig`. Rates are bumped by one
+    /// basis point. Needs five trees with forward differences and seven with
+    /// central ones, against over thirty for `bumped()`.
+    pub(crate) fn from_lattice<F>(option: &BSOption, lattice: F) -> Greeks
+    where
+        F: Fn(&BSOption) -> LatticeGreeks,
+    {
+        const RATE_BUMP: f64 = 1e-4;
+
+        let config = option.numerical_config;
+        let conventions = option.greek_conventions;
+        let relattice = |update: &dyn Fn(&mut BSOption)| {
+            let mut option_prime = *option;
+            update(&mut option_prime);
+            lattice(&option_prime)
+        };
+        let base = lattice(option);
+
+        // volatility derivatives, from the values and node greeks of the
+        // bumped trees
+        let bump = config.volatility_bump();
+        let at_volatility = |volatility: f64| {
+            relattice(&|option_prime: &mut BSOption| option_prime.set_volatility(volatility))
+        };
+        let up = at_volatility(option.volatility + bump);
+        let (down, width, volga) = match config.difference_scheme() {
+            DifferenceScheme::Forward => {
+                let up_twice = at_volatility(option.volatility + 2.0 * bump);
+                let volga = (up_twice.value - 2.0 * up.value + base.value) / bump.powi(2);
+                (base, bump, volga)
+            }
+            DifferenceScheme::Central => {
+                let down = at_volatility(option.volatility - bump);
+                let volga = (up.value - 2.0 * base.value + down.value) / bump.powi(2);
+                (down, 2.0 * bump, volga)
+            }
+        };
+        let vega = (up.value - down.value) / width;
+        let vanna = (up.delta - down.delta) / width;
+        let veta = (up.theta - down.theta) / width;
+
+        // rate derivatives, with respect to the continuously compounded rates
+        let rate_derivative = |set_rate: fn(&mut BSOption, f64), rate: f64| {
+            let value_at = |rate: f64| {
+                relattice(&|option_prime: &mut BSOption| set_rate(option_prime, rate.exp_m1()))
+                    .value
+            };
+            let (value_down, width) = match config.difference_scheme() {
+                DifferenceScheme::Forward => (base.value, RATE_BUMP),
+                DifferenceScheme::Central => (value_at(rate - RATE_BUMP), 2.0 * RATE_BUMP),
+            };
+            (value_at(rate + RATE_BUMP) - value_down) / width
+        };
+        let rho = rate_derivative(BSOption::set_interest, option.r_continuous());
+        let epsilon = rate_derivative(BSOption::set_payout_rate, option.div_continuous());
+
+        let volatility_unit = conventions.volatility_unit();
+        let days_per_year = conventions.days_per_year();
+        Greeks {
+            value: base.value,
+            delta: base.delta,
+            gamma: conventions.scale_gamma(base.gamma, option.asset_price),
+            vega: vega * volatility_unit,
+            theta: base.theta / days_per_year,
+            rho: rho * conventions.rate_unit(),
+            epsilon: epsilon * conventions.rate_unit(),
+            vanna: vanna * volatility_unit,
+            volga: volga * volatility_unit.powi(2),
+            veta: veta * volatility_unit / days_per_year,
+            lambda: base.delta * option.asset_price / base.value,
+        }
+    }
+
+    /// The intrinsic value and the limits of the greeks of an option at its
+    /// maturity, where only the delta remains.
+    fn expired(option: &BSOption, option_type: OptionType) -> Greeks {
+        let deltas = op_calc::expired_deltas(option);
+        let (value, delta) = match option_type {
+            OptionType::Call => (option.call_intrinsic(), deltas.call),
+            OptionType::Put => (option.put_intrinsic(), deltas.put),
+        };
+
+        Greeks {
+            value,
+            delta,
+            gamma: 0.0,
+            vega: 0.0,
+            theta: 0.0,
+            rho: 0.0,
+            epsilon: 0.0,
+            vanna: 0.0,
+            volga: 0.0,
+            veta: 0.0,
+            lambda: delta * option.asset_price / value,
+        }
+    }
+
+    pub(crate) fn new(option: &BSOption, option_type: OptionType) -> Greeks {
+        if op_calc::is_expired(option) {
+            return Greeks::expired(option, option_type);
+        }
+
+        let components = option.pricing_components();
+        let (d1, d2) = (components.d1(), components.d2());
+        let r = option.r_continuous();
+        let q = option.div_continuous();
+        let time = option.time_to_maturity;
+        let sqrt_time = time.sqrt();
+        let volatility = option.volatility;
+
+        let discounted_asset_price = option.shifted_asset_price() * components.carry_factor();
+        let discounted_strike = option.shifted_strike() * components.discount_factor();
+        let pdf_d1 = normal_pdf(d1);
+        let conventions = option.greek_conventions;
+        let volatility_unit = conventions.volatility_unit();
+        let rate_unit = conventions.rate_unit();
+        let days_per_year = conventions.days_per_year();
+
+        // quantities shared by calls and puts
+        let gamma = conventions.scale_gamma(
+            components.carry_factor() * pdf_d1
+                / (option.shifted_asset_price() * volatility * sqrt_time),
+            option.asset_price,
+        );
+        let vega = discounted_asset_price * pdf_d1 * sqrt_time * volatility_unit;
+        let vanna = -components.carry_factor() * pdf_d1 * d2 / volatility * volatility_unit;
+        let volga = vega * d1 * d2 / volatility * volatility_unit;
+        let veta = vega
+            * (q + (r - q) * d1 / (volatility * sqrt_time) - (1.0 + d1 * d2) / (2.0 * time))
+            / days_per_year;
+        let decay = -discounted_asset_price * pdf_d1 * volatility / (2.0 * sqrt_time);
+
+        // quantities that differ by sign and tail: N(d) for calls, -N(-d) for puts
+        let (sign, nd1, nd2) = match option_type {
+            OptionType::Call => (1.0, components.nd1(), components.nd2()),
+            OptionType::Put => (-1.0, 1.0 - components.nd1(), 1.0 - components.nd2()),
+        };
+
+        let value = sign * (discounted_asset_price * nd1 - discounted_strike * nd2);
+        let delta = sign * components.carry_factor() * nd1;
+        let theta = (decay
+            - sign * (r * discounted_strike * nd2 - q * discounted_asset_price * nd1))
+            / days_per_year;
+        let rho = sign * discounted_strike * time * nd2 * rate_unit;
+        let epsilon = -sign * discounted_asset_price * time * nd1 * rate_unit;
+
+        Greeks {
+            value,
+            delta,
+            gamma,
+            vega,
+            theta,
+            rho,
+            epsilon,
+            vanna,
+            volga,
+            veta,
+            lambda: delta * option.asset_price / value,
+        }
+    }
+}
+
+#[cfg(test)]
+mod greeks_tests {
+    use crate::option::{BSOption, OptionType};
+
+    #[test]
+    fn matches_individual_greeks() {
+        let option = BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.02);
+
+        let call = option.greeks(OptionType::Call);
+        approx::assert_abs_diff_eq!(call.value(), option.call_value(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(call.delta(), option.call_delta(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(call.gamma(), option.call_gamma(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(call.vega(), option.call_vega(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(call.theta(), option.call_theta_per_day(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(call.rho(), option.call_rho(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(call.epsilon(), option.call_epsilon(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(call.vanna(), option.call_vanna(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(call.volga(), option.volga(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(call.veta(), option.veta(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(call.lambda(), option.call_lambda(), epsilon = 1e-10);
+
+        let put = option.greeks(OptionType::Put);
+        approx::assert_abs_diff_eq!(put.value(), option.put_value(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(put.delta(), option.put_delta(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(put.gamma(), option.put_gamma(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(put.vega(), option.put_vega(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(put.theta(), option.put_theta_per_day(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(put.rho(), option.put_rho(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(put.epsilon(), option.put_epsilon(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(put.vanna(), option.put_vanna(), epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(put.lambda(), option.put_lambda(), epsilon = 1e-10);
+    }
+}
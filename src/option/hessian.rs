@@ -0,0 +1,137 @@
+//! Second derivatives of an option's value.
+
+use wasm_bindgen::prelude::*;
+
+/// The symmetric 3x3 matrix of second derivatives of a call's or put's value
+/// with respect to the asset price (index `0`), the volatility (index `1`)
+/// and the passage of time in years (index `2`).
+///
+/// Entries are unscaled, e.g. the vanna entry is per unit of volatility
+/// rather than per 1% like `BSOption::call_vanna()`.
+///
+/// See `BSOption::hessian()`.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct Hessian {
+    entries: [[f64; 3]; 3],
+}
+
+#[wasm_bindgen]
+impl Hessian {
+    /// Get the second derivative with respect to the inputs at `row` and
+    /// `column`, or `None` if either is not `0`, `1` or `2`.
+    pub fn entry(&self, row: usize, column: usize) -> Option<f64> {
+        self.entries.get(row)?.get(column).copied()
+    }
+
+    /// Get all entries, row by row.
+    pub fn entries(&self) -> Vec<f64> {
+        self.entries.iter().flatten().copied().collect()
+    }
+
+    /// Get the second-order part of the value change for the given moves,
+    /// i.e. `0.5 * x' * H * x` with `x = (asset_move, volatility_move,
+    /// time_passed)`. Add the first-order greeks' contribution for a full
+    /// quadratic P&L estimate.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `asset_move`: the change in the asset price.
+    /// - `volatility_move`: the change in volatility, e.g. `0.01` for 1%.
+    /// - `time_passed`: the time passed, in years.
+    pub fn quadratic_pnl(&self, asset_move: f64, volatility_move: f64, time_passed: f64) -> f64 {
+        let moves = [asset_move, volatility_move, time_passed];
+
+        let mut pnl = 0.0;
+        for (row, row_move) in moves.iter().enumerate() {
+            for (column, column_move) in moves.iter().enumerate() {
+                pnl += 0.5 * self.entries[row][column] * row_move * column_move;
+            }
+        }
+
+        pnl
+    }
+}
+
+impl Hessian {
+    pub(crate) fn new(entries: [[f64; 3]; 3]) -> Hessian {
+        Hessian { entries }
+    }
+}
+
+#[cfg(test)]
+mod hessian_tests {
+    use crate::option::{BSOption, OptionType};
+
+    const TIME_CURR: u32 = 1606867200;
+    const ONE_DAY: f64 = 1.0 / 365.0;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(TIME_CURR, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.01)
+    }
+
+    /// The call's value after the asset price and volatility moved, and
+    /// `days` passed, which may be negative.
+    fn call_value(asset_move: f64, volatility_move: f64, days: i32) -> f64 {
+        let option = create_test_option();
+        option
+            .value_at(
+                (TIME_CURR as i64 + i64::from(days) * 86_400) as u32,
+                Some(option.asset_price() + asset_move),
+                Some(option.volatility() + volatility_move),
+            )
+            .call_value()
+    }
+
+    #[test]
+    fn matches_bumped_second_derivatives() {
+        let hessian = create_test_option().hessian(OptionType::Call);
+        let (price_bump, volatility_bump) = (0.01, 1e-4);
+
+        let gamma = (call_value(price_bump, 0.0, 0) - 2.0 * call_value(0.0, 0.0, 0)
+            + call_value(-price_bump, 0.0, 0))
+            / price_bump.powi(2);
+        approx::assert_abs_diff_eq!(hessian.entry(0, 0).unwrap(), gamma, epsilon = 1e-5);
+
+        let vanna = (call_value(price_bump, volatility_bump, 0)
+            - call_value(price_bump, -volatility_bump, 0)
+            - call_value(-price_bump, volatility_bump, 0)
+            + call_value(-price_bump, -volatility_bump, 0))
+            / (4.0 * price_bump * volatility_bump);
+        approx::assert_abs_diff_eq!(hessian.entry(0, 1).unwrap(), vanna, epsilon = 1e-4);
+        approx::assert_abs_diff_eq!(hessian.entry(1, 0).unwrap(), vanna, epsilon = 1e-4);
+
+        // central differences over a day either way
+        let charm = (call_value(price_bump, 0.0, 1)
+            - call_value(-price_bump, 0.0, 1)
+            - call_value(price_bump, 0.0, -1)
+            + call_value(-price_bump, 0.0, -1))
+            / (4.0 * price_bump * ONE_DAY);
+        approx::assert_abs_diff_eq!(hessian.entry(0, 2).unwrap(), charm, epsilon = 1e-3);
+        let theta_decay = (call_value(0.0, 0.0, 1) - 2.0 * call_value(0.0, 0.0, 0)
+            + call_value(0.0, 0.0, -1))
+            / ONE_DAY.powi(2);
+        approx::assert_abs_diff_eq!(hessian.entry(2, 2).unwrap(), theta_decay, epsilon = 1e-3);
+
+        assert!(hessian.entry(3, 0).is_none());
+        assert!(hessian.entry(0, 3).is_none());
+    }
+
+    #[test]
+    fn estimates_the_second_order_pnl() {
+        let hessian = create_test_option().hessian(OptionType::Call);
+        let (asset_move, volatility_move) = (1.0, 0.01);
+
+        // repricing the move and its opposite cancels the first-order part
+        let repriced = (call_value(asset_move, volatility_move, 1)
+            + call_value(-asset_move, -volatility_move, -1))
+            / 2.0
+            - call_value(0.0, 0.0, 0);
+
+        approx::assert_abs_diff_eq!(
+            hessian.quadratic_pnl(asset_move, volatility_move, ONE_DAY),
+            repriced,
+            epsilon = 2e-4
+        );
+    }
+}
@@ -0,0 +1,573 @@
+//! Implied volatilities, in closed-form approximations or solved for, the
+//! forward implied by put-call parity, and strikes solved for from a price.
+//!
+//! The Black-Scholes value only depends on the asset price and the strike
+//! discounted to now, so a whole chain of one expiry shares the discount
+//! factors. Puts are converted to calls with put-call parity first.
+
+use crate::option::{BSOption, OptionType};
+use crate::utils::{normal_cdf, normal_pdf};
+use std::f64::consts::PI;
+use wasm_bindgen::prelude::*;
+
+/// The bounds of the volatilities searched for by `implied_vols()`.
+const MIN_VOLATILITY: f64 = 1e-4;
+const MAX_VOLATILITY: f64 = 5.0;
+/// The volatility interval at which `implied_vols()` stops bisecting the
+/// values of models other than Black-Scholes.
+const VOLATILITY_TOLERANCE: f64 = 1e-8;
+/// The strike interval, relative to the strike, at which
+/// `strike_for_price()` stops.
+const STRIKE_TOLERANCE: f64 = 1e-10;
+const MAX_BRACKET_STEPS: usize = 64;
+
+/// The settings of the solver inverting prices for implied volatilities.
+///
+/// See `BSOption::set_implied_vol_config()`.
+///
+/// ```rust
+/// use opcalc::option::implied::ImpliedVolConfig;
+///
+/// let config = ImpliedVolConfig::new()
+///     .with_price_tolerance(1e-8)
+///     .with_max_iterations(50);
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct ImpliedVolConfig {
+    price_tolerance: f64,
+    max_iterations: usize,
+}
+
+impl Default for ImpliedVolConfig {
+    fn default() -> ImpliedVolConfig {
+        ImpliedVolConfig {
+            price_tolerance: 1e-10,
+            max_iterations: 100,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl ImpliedVolConfig {
+    /// Create an `ImpliedVolConfig` with a price tolerance of `1e-10` and at
+    /// most 100 iterations.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> ImpliedVolConfig {
+        Default::default()
+    }
+
+    /// Set the price error at which the solver stops. Prices further than
+    /// this out of reach of the volatilities searched give `NaN`.
+    pub fn with_price_tolerance(self, price_tolerance: f64) -> ImpliedVolConfig {
+        ImpliedVolConfig {
+            price_tolerance,
+            ..self
+        }
+    }
+
+    /// Set the most iterations the solver takes per price.
+    pub fn with_max_iterations(self, max_iterations: usize) -> ImpliedVolConfig {
+        ImpliedVolConfig {
+            max_iterations,
+            ..self
+        }
+    }
+
+    /// Get the price error at which the solver stops.
+    pub fn price_tolerance(&self) -> f64 {
+        self.price_tolerance
+    }
+
+    /// Get the most iterations the solver takes per price.
+    pub fn max_iterations(&self) -> usize {
+        self.max_iterations
+    }
+}
+
+/// The volatility approximately implied by `price` for a call or put on
+/// `option`, following Corrado and Miller (1996).
+pub(crate) fn vol_guess(option: &BSOption, option_type: OptionType, price: f64) -> f64 {
+    let time = option.time_to_maturity;
+    let asset_price = option.shifted_asset_price() * (-option.div_continuous() * time).exp();
+    let strike = option.shifted_strike() * (-option.r_continuous() * time).exp();
+
+    let call_price = match option_type {
+        OptionType::Call => price,
+        OptionType::Put => price + asset_price - strike,
+    };
+
+    total_vol_guess(asset_price, strike, call_price) / time.sqrt()
+}
+
+/// Get the forward price implied by the prices of a call and a put with the
+/// same strike and maturity, from put-call parity
+/// `C - P = (F - K) * e^(-r * T)`. No model is involved, which makes it the
+/// natural anchor for a smile in moneyness or delta space.
+///
+/// # Arguments
+///
+/// - `call_price`, `put_price`: the options' market prices.
+///
+/// - `strike`: the options' strike price.
+///
+/// - `interest`: the interest rate until maturity, in decimal form and
+///      compounded annually like `BSOption::interest()`.
+///
+/// - `time_to_maturity`: the time to maturity as a fraction of 365 days.
+///
+/// ```rust
+/// use opcalc::option::implied::implied_forward;
+///
+/// approx::assert_abs_diff_eq!(implied_forward(5.0, 5.0, 100.0, 0.02, 0.5), 100.0);
+/// ```
+#[wasm_bindgen]
+pub fn implied_forward(
+    call_price: f64,
+    put_price: f64,
+    strike: f64,
+    interest: f64,
+    time_to_maturity: f64,
+) -> f64 {
+    let discount_factor = (-interest.ln_1p() * time_to_maturity).exp();
+
+    strike + (call_price - put_price) / discount_factor
+}
+
+/// The strike at which the call or put on `option` is worth `target_price`
+/// under the option's model, by bisection: call values decrease with the
+/// strike, and put values increase with it. `None` if no strike reaches the
+/// price, i.e. if it is not positive, or for calls, not below the asset price
+/// net of payouts.
+pub(crate) fn strike_for_price(
+    option: &BSOption,
+    option_type: OptionType,
+    target_price: f64,
+) -> Option<f64> {
+    let net_asset_price =
+        option.shifted_asset_price() * (-option.div_continuous() * option.time_to_maturity).exp();
+    if target_price <= 0.0 || (option_type == OptionType::Call && target_price >= net_asset_price) {
+        return None;
+    }
+
+    let mut quoted = *option;
+    // whether the value at `strike` is below the target, i.e. the strike is
+    // too high for calls and too low for puts
+    let mut below_target = |strike: f64| {
+        quoted.set_strike(strike - option.shift);
+        match option_type {
+            OptionType::Call => quoted.call_value() < target_price,
+            OptionType::Put => quoted.put_value() < target_price,
+        }
+    };
+    let too_high = |below_target: bool| match option_type {
+        OptionType::Call => below_target,
+        OptionType::Put => !below_target,
+    };
+
+    // bracket the strike between a shifted strike of zero and a multiple of
+    // the asset price
+    let mut low = 0.0;
+    let mut high = option.shifted_asset_price();
+    let mut steps = 0;
+    while !too_high(below_target(high)) {
+        low = high;
+        high *= 2.0;
+        steps += 1;
+        if steps > MAX_BRACKET_STEPS {
+            return None;
+        }
+    }
+
+    while high - low > STRIKE_TOLERANCE * high {
+        let mid = (low + high) / 2.0;
+        if too_high(below_target(mid)) {
+            high = mid;
+        } else {
+            low = mid;
+        }
+    }
+
+    Some((low + high) / 2.0 - option.shift)
+}
+
+/// The asset price net of the payouts until maturity, `S * e^(-q * T)`,
+/// implied by the prices of a call and a put at the option's strike, from
+/// put-call parity `C - P = S * e^(-q * T) - K * e^(-r * T)`.
+pub(crate) fn parity_asset_price(option: &BSOption, call_price: f64, put_price: f64) -> f64 {
+    let discount_factor = (-option.r_continuous() * option.time_to_maturity).exp();
+
+    call_price - put_price + option.shifted_strike() * discount_factor
+}
+
+/// The volatilities implied by the `prices` of calls (`is_call` non-zero) or
+/// puts at the `strikes`, with the rest of the inputs, including the model,
+/// taken from `option`. Prices out of reach of the volatilities in
+/// `[1e-4, 5]` give `NaN`, and chains whose inputs have different lengths
+/// give no volatilities at all.
+pub(crate) fn implied_vols(
+    option: &BSOption,
+    strikes: &[f64],
+    prices: &[f64],
+    is_call: &[u8],
+) -> Vec<f64> {
+    if prices.len() != strikes.len() || is_call.len() != strikes.len() {
+        return Vec::new();
+    }
+
+    let config = option.implied_vol_config;
+    let time = option.time_to_maturity;
+    let asset_price = option.shifted_asset_price() * (-option.div_continuous() * time).exp();
+    let discount_factor = (-option.r_continuous() * time).exp();
+
+    strikes
+        .iter()
+        .zip(prices)
+        .zip(is_call)
+        .map(|((&strike, &price), &is_call)| {
+            if !option.model.is_black_scholes() {
+                let option_type = if is_call != 0 {
+                    OptionType::Call
+                } else {
+                    OptionType::Put
+                };
+                return model_implied_vol(option, strike, option_type, price, config);
+            }
+
+            let strike = (strike + option.shift) * discount_factor;
+            let call_price = if is_call != 0 {
+                price
+            } else {
+                price + asset_price - strike
+            };
+
+            total_implied_vol(asset_price, strike, call_price, time.sqrt(), config) / time.sqrt()
+        })
+        .collect()
+}
+
+/// Solve for the volatility at which the option's model values the call or
+/// put at `strike` at `price`, by bisection, as values increase with the
+/// volatility. Unlike Black-Scholes, e.g. binomial trees have no closed-form
+/// vega, and account for early exercise.
+fn model_implied_vol(
+    option: &BSOption,
+    strike: f64,
+    option_type: OptionType,
+    price: f64,
+    config: ImpliedVolConfig,
+) -> f64 {
+    let mut quoted = *option;
+    quoted.set_strike(strike);
+    let mut value_at = |volatility: f64| {
+        quoted.set_volatility(volatility);
+        match option_type {
+            OptionType::Call => quoted.call_value(),
+            OptionType::Put => quoted.put_value(),
+        }
+    };
+
+    let mut low = MIN_VOLATILITY;
+    let mut high = MAX_VOLATILITY;
+    let tolerance = config.price_tolerance;
+    if price < value_at(low) - tolerance || price > value_at(high) + tolerance {
+        return f64::NAN;
+    }
+
+    for _ in 0..config.max_iterations {
+        if high - low <= VOLATILITY_TOLERANCE {
+            break;
+        }
+        let mid = (low + high) / 2.0;
+        if value_at(mid) < price {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    (low + high) / 2.0
+}
+
+/// The total volatility `vol * sqrt(T)` approximately implied by a call's
+/// price, from the discounted asset price and strike.
+///
+/// At the money forward, Corrado-Miller reduces to Brenner and
+/// Subrahmanyam's (1988) `vol * sqrt(T) = sqrt(2 * pi) * C / S`. This is also
+/// used when Corrado-Miller has no real solution, which happens for prices
+/// far from the money.
+fn total_vol_guess(asset_price: f64, strike: f64, call_price: f64) -> f64 {
+    let half_moneyness = (asset_price - strike) / 2.0;
+    let time_value = call_price - half_moneyness;
+    let discriminant = time_value.powi(2) - (asset_price - strike).powi(2) / PI;
+    let vol_sqrt_time = if discriminant >= 0.0 {
+        (2.0 * PI).sqrt() / (asset_price + strike) * (time_value + discriminant.sqrt())
+    } else {
+        (2.0 * PI).sqrt() * time_value / asset_price
+    };
+
+    vol_sqrt_time.max(0.0)
+}
+
+/// Solve for the total volatility implied by a call's price with Newton's
+/// method, starting from Corrado-Miller. Steps leaving the bracket around
+/// the solution, e.g. where vega vanishes far from the money, bisect it
+/// instead.
+fn total_implied_vol(
+    asset_price: f64,
+    strike: f64,
+    call_price: f64,
+    sqrt_time: f64,
+    config: ImpliedVolConfig,
+) -> f64 {
+    // in the money, the price is mostly intrinsic value, so solve for the
+    // out-of-the-money put's price instead
+    let price = if strike < asset_price {
+        call_price - asset_price + strike
+    } else {
+        call_price
+    };
+
+    let mut low = MIN_VOLATILITY * sqrt_time;
+    let mut high = MAX_VOLATILITY * sqrt_time;
+    let tolerance = config.price_tolerance;
+    if price < out_of_the_money_value(asset_price, strike, low).0 - tolerance
+        || price > out_of_the_money_value(asset_price, strike, high).0 + tolerance
+    {
+        return f64::NAN;
+    }
+
+    let mut vol_sqrt_time = total_vol_guess(asset_price, strike, call_price)
+        .max(low)
+        .min(high);
+    for _ in 0..config.max_iterations {
+        let (value, vega) = out_of_the_money_value(asset_price, strike, vol_sqrt_time);
+        let error = value - price;
+        if error.abs() < tolerance {
+            break;
+        }
+
+        if error > 0.0 {
+            high = vol_sqrt_time;
+        } else {
+            low = vol_sqrt_time;
+        }
+        let newton = vol_sqrt_time - error / vega;
+        vol_sqrt_time = if newton > low && newton < high {
+            newton
+        } else {
+            (low + high) / 2.0
+        };
+    }
+
+    vol_sqrt_time
+}
+
+/// The value of the out-of-the-money call or put and its derivative by the
+/// total volatility, from the discounted asset price and strike.
+fn out_of_the_money_value(asset_price: f64, strike: f64, vol_sqrt_time: f64) -> (f64, f64) {
+    let d1 = (asset_price / strike).ln() / vol_sqrt_time + vol_sqrt_time / 2.0;
+    let d2 = d1 - vol_sqrt_time;
+    let value = if strike < asset_price {
+        strike * normal_cdf(-d2) - asset_price * normal_cdf(-d1)
+    } else {
+        asset_price * normal_cdf(d1) - strike * normal_cdf(d2)
+    };
+
+    (value, asset_price * normal_pdf(d1))
+}
+
+#[cfg(test)]
+mod implied_tests {
+    use super::{implied_forward, implied_vols, strike_for_price, vol_guess, ImpliedVolConfig};
+    use crate::option::dividends::DividendOption;
+    use crate::option::engine::Model;
+    use crate::option::{BSOption, OptionType};
+
+    fn create_test_option(strike: f64) -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, strike, 0.005, 0.23, 0.0)
+    }
+
+    #[test]
+    fn guesses_close_to_the_money() {
+        for &strike in &[95.0, 100.0, 105.0] {
+            let option = create_test_option(strike);
+            approx::assert_abs_diff_eq!(
+                vol_guess(&option, OptionType::Call, option.call_value()),
+                0.23,
+                epsilon = 5e-3
+            );
+            approx::assert_abs_diff_eq!(
+                vol_guess(&option, OptionType::Put, option.put_value()),
+                0.23,
+                epsilon = 5e-3
+            );
+        }
+    }
+
+    #[test]
+    fn never_guesses_negative_volatilities() {
+        let option = create_test_option(60.0);
+        let guess = vol_guess(&option, OptionType::Call, option.call_intrinsic());
+
+        assert!(guess >= 0.0);
+        assert!(guess.is_finite());
+    }
+
+    #[test]
+    fn solves_for_a_whole_chain() {
+        let option = create_test_option(100.0);
+        let strikes = [80.0, 90.0, 100.0, 110.0, 125.0];
+        let mut prices = Vec::new();
+        let mut is_call = Vec::new();
+        for (i, &strike) in strikes.iter().enumerate() {
+            let mut quoted = create_test_option(strike);
+            quoted.set_volatility(0.2 + 0.02 * i as f64);
+            if i % 2 == 0 {
+                prices.push(quoted.call_value());
+                is_call.push(1);
+            } else {
+                prices.push(quoted.put_value());
+                is_call.push(0);
+            }
+        }
+
+        let vols = implied_vols(&option, &strikes, &prices, &is_call);
+        for (i, &vol) in vols.iter().enumerate() {
+            approx::assert_abs_diff_eq!(vol, 0.2 + 0.02 * i as f64, epsilon = 1e-6);
+        }
+    }
+
+    #[test]
+    fn rejects_prices_out_of_reach() {
+        let option = create_test_option(100.0);
+        let vols = implied_vols(&option, &[100.0, 100.0], &[-1.0, 150.0], &[1, 1]);
+
+        assert!(vols.iter().all(|vol| vol.is_nan()));
+    }
+
+    #[test]
+    fn brackets_where_vega_vanishes() {
+        // far from the money, and close to intrinsic value, Newton steps from
+        // the initial guess overshoot the bounds; there, the price tolerance
+        // pins the volatility down less tightly
+        let option = create_test_option(100.0);
+        for &(strike, volatility) in [(80.0, 0.12), (150.0, 0.2), (60.0, 0.35), (250.0, 1.5)].iter()
+        {
+            let mut quoted = create_test_option(strike);
+            quoted.set_volatility(volatility);
+
+            let call = implied_vols(&option, &[strike], &[quoted.call_value()], &[1])[0];
+            let put = implied_vols(&option, &[strike], &[quoted.put_value()], &[0])[0];
+            approx::assert_abs_diff_eq!(call, volatility, epsilon = 1e-5);
+            approx::assert_abs_diff_eq!(put, volatility, epsilon = 1e-5);
+        }
+    }
+
+    #[test]
+    fn rejects_chains_of_different_lengths() {
+        let option = create_test_option(100.0);
+
+        assert!(implied_vols(&option, &[95.0, 100.0], &[7.0], &[1, 1]).is_empty());
+        assert!(implied_vols(&option, &[95.0, 100.0], &[7.0, 4.0], &[1]).is_empty());
+    }
+
+    #[test]
+    fn follows_the_solver_settings() {
+        let mut option = create_test_option(100.0);
+        let quoted = create_test_option(110.0);
+        let price = quoted.call_value();
+
+        // without iterations, the solver stops at its initial guess
+        option.set_implied_vol_config(ImpliedVolConfig::new().with_max_iterations(0));
+        approx::assert_abs_diff_eq!(
+            implied_vols(&option, &[110.0], &[price], &[1])[0],
+            vol_guess(&quoted, OptionType::Call, price),
+            epsilon = 1e-12
+        );
+
+        option.set_implied_vol_config(ImpliedVolConfig::new());
+        approx::assert_abs_diff_eq!(
+            implied_vols(&option, &[110.0], &[price], &[1])[0],
+            0.23,
+            epsilon = 1e-8
+        );
+    }
+
+    #[test]
+    fn backs_out_payouts_from_put_call_parity() {
+        let mut option = create_test_option(105.0);
+        option.set_payout_rate(0.03);
+        approx::assert_abs_diff_eq!(
+            option
+                .implied_payout_rate(option.call_value(), option.put_value())
+                .unwrap(),
+            0.03,
+            epsilon = 1e-10
+        );
+
+        // no payout rate explains a negative net asset price, or expired options
+        assert!(option.implied_payout_rate(0.0, 200.0).is_none());
+        let mut expired = option;
+        expired.set_time_maturity(expired.time_curr());
+        assert!(expired.implied_payout_rate(1.0, 5.0).is_none());
+
+        option.set_payout_rate(0.0);
+        let dividend_option = DividendOption::new(&option, vec![1608422400], vec![2.0]);
+        approx::assert_abs_diff_eq!(
+            option.implied_dividends_present_value(
+                dividend_option.call_value(),
+                dividend_option.put_value()
+            ),
+            dividend_option.dividends_present_value(),
+            epsilon = 1e-10
+        );
+    }
+
+    #[test]
+    fn implies_the_forward() {
+        let mut option = create_test_option(105.0);
+        option.set_payout_rate(0.03);
+        let time = option.time_to_maturity();
+        let forward = 100.0 * ((0.005f64.ln_1p() - 0.03f64.ln_1p()) * time).exp();
+
+        approx::assert_abs_diff_eq!(
+            implied_forward(option.call_value(), option.put_value(), 105.0, 0.005, time),
+            forward,
+            epsilon = 1e-10
+        );
+    }
+
+    #[test]
+    fn solves_for_the_strike() {
+        let option = create_test_option(100.0);
+        for &(option_type, strike) in &[(OptionType::Call, 108.0), (OptionType::Put, 93.5)] {
+            let mut quoted = create_test_option(strike);
+            let price = match option_type {
+                OptionType::Call => quoted.call_value(),
+                OptionType::Put => quoted.put_value(),
+            };
+            quoted.set_strike(strike_for_price(&option, option_type, price).unwrap());
+
+            approx::assert_abs_diff_eq!(quoted.strike(), strike, epsilon = 1e-6);
+        }
+
+        assert!(strike_for_price(&option, OptionType::Call, 100.0).is_none());
+        assert!(strike_for_price(&option, OptionType::Put, 0.0).is_none());
+    }
+
+    #[test]
+    fn inverts_american_values() {
+        let mut option = BSOption::new(1606780800, 1637366400, 100.0, 110.0, 0.08, 0.3, 0.0);
+        option.set_model(Model::crr(200));
+        let price = option.put_value();
+
+        let vols = implied_vols(&option, &[110.0], &[price], &[0]);
+        approx::assert_abs_diff_eq!(vols[0], 0.3, epsilon = 1e-6);
+
+        // a European inversion overstates the volatility of American puts
+        option.set_model(Model::black_scholes());
+        let vols = implied_vols(&option, &[110.0], &[price], &[0]);
+        assert!(vols[0] > 0.31);
+    }
+}
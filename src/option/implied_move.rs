@@ -0,0 +1,114 @@
+//! The move of the underlying's price by expiry implied by a straddle's or
+//! a strangle's price.
+
+use crate::option::BSOption;
+use wasm_bindgen::prelude::*;
+
+/// The move of the underlying's price by an option's maturity implied by
+/// the price of a straddle (a call and a put at the same strike) or a
+/// strangle (a put below and a call above the asset price).
+///
+/// See `BSOption::straddle_implied_move()` and
+/// `BSOption::strangle_implied_move()`.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct ImpliedMove {
+    asset_price: f64,
+    price: f64,
+    lower_breakeven: f64,
+    upper_breakeven: f64,
+}
+
+#[wasm_bindgen]
+impl ImpliedMove {
+    /// Get the quick approximation of the move, `price / S`, as a fraction
+    /// of the asset price.
+    ///
+    /// An at-the-money straddle is worth about `0.8 * vol * sqrt(T) * S`,
+    /// which is also the expected absolute move `E|S_T - S|` of a normally
+    /// distributed price, so the straddle's price is the market's expected
+    /// move.
+    pub fn approximate_move(&self) -> f64 {
+        self.price / self.asset_price
+    }
+
+    /// Get the asset price below which the position pays off at maturity:
+    /// the put's strike less the price, compounded to maturity. `0` if the
+    /// price exceeds the put's strike.
+    pub fn lower_breakeven(&self) -> f64 {
+        self.lower_breakeven
+    }
+
+    /// Get the asset price above which the position pays off at maturity:
+    /// the call's strike plus the price, compounded to maturity.
+    pub fn upper_breakeven(&self) -> f64 {
+        self.upper_breakeven
+    }
+
+    /// Get the fall to the lower breakeven, as a fraction of the asset price.
+    pub fn down_move(&self) -> f64 {
+        1.0 - self.lower_breakeven / self.asset_price
+    }
+
+    /// Get the rise to the upper breakeven, as a fraction of the asset price.
+    pub fn up_move(&self) -> f64 {
+        self.upper_breakeven / self.asset_price - 1.0
+    }
+}
+
+impl ImpliedMove {
+    pub(crate) fn new(
+        option: &BSOption,
+        price: f64,
+        put_strike: f64,
+        call_strike: f64,
+    ) -> ImpliedMove {
+        // the price is paid now, and the payoff received at maturity
+        let price_at_maturity = price * (option.r_continuous() * option.time_to_maturity).exp();
+
+        ImpliedMove {
+            asset_price: option.asset_price,
+            price,
+            lower_breakeven: (put_strike - price_at_maturity).max(0.0),
+            upper_breakeven: call_strike + price_at_maturity,
+        }
+    }
+}
+
+#[cfg(test)]
+mod implied_move_tests {
+    use crate::option::BSOption;
+    use std::f64::consts::PI;
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 100.0, 0.005, 0.23, 0.0)
+    }
+
+    #[test]
+    fn approximates_the_expected_absolute_move() {
+        let option = create_test_option();
+        let implied_move = option.straddle_implied_move(option.call_value() + option.put_value());
+        let expected_move = 0.23 * option.time_to_maturity().sqrt() * (2.0 / PI).sqrt();
+
+        approx::assert_abs_diff_eq!(
+            implied_move.approximate_move(),
+            expected_move,
+            epsilon = 1e-3
+        );
+    }
+
+    #[test]
+    fn moves_to_the_breakevens() {
+        let option = create_test_option();
+        let growth = (0.005f64.ln_1p() * option.time_to_maturity()).exp();
+
+        let straddle = option.straddle_implied_move(5.0);
+        approx::assert_abs_diff_eq!(straddle.lower_breakeven(), 100.0 - 5.0 * growth);
+        approx::assert_abs_diff_eq!(straddle.upper_breakeven(), 100.0 + 5.0 * growth);
+        approx::assert_abs_diff_eq!(straddle.down_move(), straddle.up_move(), epsilon = 1e-12);
+
+        let strangle = option.strangle_implied_move(2.0, 95.0, 105.0);
+        approx::assert_abs_diff_eq!(strangle.down_move(), 0.05 + 0.02 * growth, epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(strangle.up_move(), 0.05 + 0.02 * growth, epsilon = 1e-12);
+    }
+}
@@ -1,14 +1,48 @@
 //! Option calculation based on Black-Scholes.
 
+pub mod american;
+pub mod attribution;
+pub mod bermudan;
 pub mod builder;
-mod opcalc;
+pub mod components;
+pub mod cone;
+pub mod conventions;
+pub mod dividends;
+pub mod dual;
+pub mod engine;
+pub mod exercise;
+pub mod greeks;
+pub mod hessian;
+pub mod implied;
+pub mod implied_move;
+pub mod numerical;
+pub(crate) mod opcalc;
+pub mod pde;
+pub mod position;
 
+use self::american::BinomialTree;
+use self::attribution::{PnlAttribution, PnlExplain};
+use self::components::PricingComponents;
+use self::cone::ProbabilityCone;
+use self::conventions::GreekConventions;
+use self::engine::{Model, PricingEngine};
+use self::exercise::ExerciseAdvice;
+use self::greeks::Greeks;
+use self::hessian::Hessian;
+use self::implied::ImpliedVolConfig;
+use self::implied_move::ImpliedMove;
+use self::numerical::NumericalConfig;
 use self::opcalc::op_calc;
+use self::position::{PositionGreeks, PositionSide};
+use crate::montecarlo::PathModel;
+use crate::transform::{CharacteristicFunction, Complex64};
 use crate::utils;
 use statrs::distribution::{Normal, Univariate};
 use wasm_bindgen::prelude::*;
 
 /// An enumeration of the different supported option types.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum OptionType {
     /// A call option.
     Call,
@@ -16,6 +50,24 @@ pub enum OptionType {
     Put,
 }
 
+/// An enumeration of the supported ways to calculate greeks.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum GreekMethod {
+    /// Closed-form Black-Scholes expressions. This is the default.
+    Analytic,
+    /// Bump-and-reprice finite differences. Slower and less precise, but
+    /// useful to cross-check the analytic greeks.
+    FiniteDifference,
+    /// Finite differences at two bump sizes, combined with Richardson
+    /// extrapolation to cancel the leading error term. Twice as slow as
+    /// `FiniteDifference`, but much more precise for the same bump sizes.
+    RichardsonFD,
+    /// Automatic differentiation of the pricing formulas with dual numbers.
+    /// Exact like `Analytic`, without needing a closed form for each greek.
+    AutomaticDifferentiation,
+}
+
 /// Specifies the two timestamps required for option calculation:
 /// option maturity as well as the current time.
 #[wasm_bindgen]
@@ -42,6 +94,12 @@ pub struct BSOption {
     interest: f64,
     volatility: f64,
     payout_rate: f64,
+    shift: f64,
+    greek_method: GreekMethod,
+    numerical_config: NumericalConfig,
+    greek_conventions: GreekConventions,
+    implied_vol_config: ImpliedVolConfig,
+    model: Model,
 }
 
 #[wasm_bindgen]
@@ -129,12 +187,18 @@ impl BSOption {
             interest,
             volatility,
             payout_rate,
+            shift: 0.0,
+            greek_method: GreekMethod::Analytic,
+            numerical_config: NumericalConfig::new(),
+            greek_conventions: GreekConventions::new(),
+            implied_vol_config: ImpliedVolConfig::new(),
+            model: Model::black_scholes(),
         }
     }
 
-    /// Get the option's call value.
+    /// Get the option's call value, under the option's `Model`.
     pub fn call_value(&self) -> f64 {
-        op_calc::calculate_option_values(self).call
+        self.model.price(self).call
     }
 
     /// Get the option's call delta value.
@@ -142,24 +206,68 @@ impl BSOption {
         op_calc::calculate_deltas(self).call
     }
 
-    /// Get the option's call gamma value.
+    /// Get the option's call lambda (elasticity) value, i.e. the percentage
+    /// change in the call's value per 1% change in the asset price.
+    pub fn call_lambda(&self) -> f64 {
+        op_calc::calculate_lambdas(self).call
+    }
+
+    /// Get the option's call gamma value, per unit of the asset price by
+    /// default (see `GreekConventions`).
     pub fn call_gamma(&self) -> f64 {
         op_calc::calculate_gammas(self).call
     }
 
-    /// Get the option's call vega value.
+    /// Get the option's call vega value, per 1% change in volatility by default
+    /// (see `GreekConventions`).
     pub fn call_vega(&self) -> f64 {
         op_calc::calculate_vegas(self).call
     }
 
+    /// Get the option's call rho value, per 1% change in the continuously
+    /// compounded interest rate by default (see `GreekConventions`).
+    pub fn call_rho(&self) -> f64 {
+        op_calc::calculate_rhos(self).call
+    }
+
+    /// Get the option's call vanna value, i.e. the change in delta per 1%
+    /// change in volatility by default (see `GreekConventions`).
+    pub fn call_vanna(&self) -> f64 {
+        op_calc::calculate_vannas(self).call
+    }
+
+    /// Get the option's call epsilon value, i.e. its sensitivity to the
+    /// payout rate, per 1% change in the continuously compounded payout rate
+    /// by default (see `GreekConventions`).
+    pub fn call_epsilon(&self) -> f64 {
+        op_calc::calculate_epsilons(self).call
+    }
+
     /// Get the option's call theta value.
+    ///
+    /// This is the change in the call's value over one calendar day, or over
+    /// the day length set by `GreekConventions`. See
+    /// `call_theta_annual()` and `call_theta_per_day()` for the
+    /// continuous-time theta.
     pub fn call_theta(&self) -> f64 {
         op_calc::calculate_thetas(self).call
     }
 
-    /// Get the option's put value.
+    /// Get the option's continuous-time call theta, i.e. the rate of change
+    /// of the call's value per year as time passes.
+    pub fn call_theta_annual(&self) -> f64 {
+        op_calc::calculate_annual_thetas(self).call
+    }
+
+    /// Get the option's continuous-time call theta, per day
+    /// (`call_theta_annual() / 365` by default, see `GreekConventions`).
+    pub fn call_theta_per_day(&self) -> f64 {
+        self.call_theta_annual() / self.greek_conventions.days_per_year()
+    }
+
+    /// Get the option's put value, under the option's `Model`.
     pub fn put_value(&self) -> f64 {
-        op_calc::calculate_option_values(self).put
+        self.model.price(self).put
     }
 
     /// Get the option's put delta value.
@@ -167,21 +275,393 @@ impl BSOption {
         op_calc::calculate_deltas(self).put
     }
 
-    /// Get the option's put gamma value.
+    /// Get the option's put lambda (elasticity) value, i.e. the percentage
+    /// change in the put's value per 1% change in the asset price.
+    pub fn put_lambda(&self) -> f64 {
+        op_calc::calculate_lambdas(self).put
+    }
+
+    /// Get the option's put gamma value, per unit of the asset price by
+    /// default (see `GreekConventions`).
     pub fn put_gamma(&self) -> f64 {
         op_calc::calculate_gammas(self).put
     }
 
-    /// Get the option's put vega value.
+    /// Get the option's volga (or vomma) value, i.e. the change in vega per
+    /// 1% change in volatility by default (see `GreekConventions`). It is
+    /// identical for calls and puts.
+    pub fn volga(&self) -> f64 {
+        op_calc::calculate_volgas(self).call
+    }
+
+    /// Get the option's veta value, i.e. the change in vega as one calendar
+    /// day passes by default (see `GreekConventions`). It is identical for
+    /// calls and puts.
+    pub fn veta(&self) -> f64 {
+        op_calc::calculate_vetas(self).call
+    }
+
+    /// Get the option's put vega value, per 1% change in volatility by default
+    /// (see `GreekConventions`).
     pub fn put_vega(&self) -> f64 {
         op_calc::calculate_vegas(self).put
     }
 
+    /// Get the option's put rho value, per 1% change in the continuously
+    /// compounded interest rate by default (see `GreekConventions`).
+    pub fn put_rho(&self) -> f64 {
+        op_calc::calculate_rhos(self).put
+    }
+
+    /// Get the option's put vanna value, i.e. the change in delta per 1%
+    /// change in volatility by default (see `GreekConventions`).
+    pub fn put_vanna(&self) -> f64 {
+        op_calc::calculate_vannas(self).put
+    }
+
+    /// Get the option's put epsilon value, i.e. its sensitivity to the
+    /// payout rate, per 1% change in the continuously compounded payout rate
+    /// by default (see `GreekConventions`).
+    pub fn put_epsilon(&self) -> f64 {
+        op_calc::calculate_epsilons(self).put
+    }
+
     /// Get the option's put theta value.
+    ///
+    /// This is the change in the put's value over one calendar day, or over
+    /// the day length set by `GreekConventions`. See
+    /// `put_theta_annual()` and `put_theta_per_day()` for the continuous-time
+    /// theta.
     pub fn put_theta(&self) -> f64 {
         op_calc::calculate_thetas(self).put
     }
 
+    /// Get the option's continuous-time put theta, i.e. the rate of change
+    /// of the put's value per year as time passes.
+    pub fn put_theta_annual(&self) -> f64 {
+        op_calc::calculate_annual_thetas(self).put
+    }
+
+    /// Get the option's continuous-time put theta, per day
+    /// (`put_theta_annual() / 365` by default, see `GreekConventions`).
+    pub fn put_theta_per_day(&self) -> f64 {
+        self.put_theta_annual() / self.greek_conventions.days_per_year()
+    }
+
+    /// Get the option's value as an American call, on a 200-step binomial
+    /// tree. Use `BinomialTree` to choose the number of steps.
+    pub fn american_call_value(&self) -> f64 {
+        BinomialTree::new().call_value(self)
+    }
+
+    /// Get the option's value as an American put, on a 200-step binomial
+    /// tree. Use `BinomialTree` to choose the number of steps.
+    pub fn american_put_value(&self) -> f64 {
+        BinomialTree::new().put_value(self)
+    }
+
+    /// Get the value of being able to exercise the call early: its American
+    /// value less its European value, both on a 200-step binomial tree so
+    /// that their discretization errors cancel out. Use `BinomialTree` to
+    /// choose the number of steps.
+    pub fn call_early_exercise_premium(&self) -> f64 {
+        BinomialTree::new().call_early_exercise_premium(self)
+    }
+
+    /// Get the value of being able to exercise the put early. See
+    /// `call_early_exercise_premium()`.
+    pub fn put_early_exercise_premium(&self) -> f64 {
+        BinomialTree::new().put_early_exercise_premium(self)
+    }
+
+    /// Get the option's value as a perpetual American call, which never
+    /// expires. The option's maturity is ignored.
+    pub fn perpetual_call_value(&self) -> f64 {
+        american::perpetual_value(self, OptionType::Call)
+    }
+
+    /// Get the option's value as a perpetual American put, which never
+    /// expires. The option's maturity is ignored.
+    pub fn perpetual_put_value(&self) -> f64 {
+        american::perpetual_value(self, OptionType::Put)
+    }
+
+    /// Get the value and all greeks of the call or put in a single pass,
+    /// sharing `d1`, `d2` and the discount factors between them.
+    ///
+    /// Under models other than Black-Scholes (see `set_model()`), the greeks
+    /// are finite differences of the model's values, using the option's
+    /// `NumericalConfig`, except for the delta, gamma and theta that the
+    /// binomial tree and the Crank-Nicolson grid read off their nodes. The
+    /// individual greek getters, e.g. `call_delta()`, always use
+    /// Black-Scholes, including for the values they reprice.
+    pub fn greeks(&self, option_type: OptionType) -> Greeks {
+        crate::utils::set_panic_hook();
+        trace_scope!("BSOption::greeks");
+
+        self.model.greeks(self, option_type)
+    }
+
+    /// Get the value and greeks of a position in the call or put, signed by
+    /// `side` and scaled by its size, so that positions can be aggregated by
+    /// adding them up.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `option_type`: `OptionType.Call` or `OptionType.Put`.
+    /// - `side`: `PositionSide.Long` or `PositionSide.Short`.
+    /// - `quantity`: the number of contracts held.
+    /// - `multiplier`: the number of units of the asset per contract, e.g.
+    ///      `100.0` for standard equity options.
+    pub fn position_greeks(
+        &self,
+        option_type: OptionType,
+        side: PositionSide,
+        quantity: f64,
+        multiplier: f64,
+    ) -> PositionGreeks {
+        PositionGreeks::new(self.greeks(option_type), side, quantity, multiplier)
+    }
+
+    /// Get the matrix of second derivatives of the call's or put's value with
+    /// respect to the asset price, the volatility and the passage of time,
+    /// e.g. for quadratic P&L estimation.
+    pub fn hessian(&self, option_type: OptionType) -> Hessian {
+        Hessian::new(op_calc::calculate_hessian(self, option_type))
+    }
+
+    /// Get the intermediate quantities used to value the option: `d1`, `d2`,
+    /// `N(d1)`, `N(d2)`, and the carry and discount factors.
+    ///
+    /// Useful to validate the option's values, e.g. against a spreadsheet.
+    pub fn pricing_components(&self) -> PricingComponents {
+        PricingComponents::new(self)
+    }
+
+    /// Get `d1 = (ln(S / K) + (r - q + vol^2 / 2) * T) / (vol * sqrt(T))`,
+    /// with `r` and `q` the continuously compounded interest and payout
+    /// rates. If the option has a shift, `S` and `K` include it.
+    pub fn d1(&self) -> f64 {
+        let s_k_ratio = self.shifted_asset_price() / self.shifted_strike();
+        let vol_factor = self.volatility.powi(2) / 2.0;
+        let rates = self.r_continuous() - self.div_continuous() + vol_factor;
+
+        let num = s_k_ratio.ln() + rates * self.time_to_maturity;
+        let den = self.volatility * self.time_to_maturity.powf(0.5);
+
+        num / den
+    }
+
+    /// Get `d2 = d1 - vol * sqrt(T)`.
+    pub fn d2(&self) -> f64 {
+        self.d1() - self.volatility * self.time_to_maturity.powf(0.5)
+    }
+
+    /// Get `N(d1)`, the standard normal cumulative distribution at `d1`.
+    pub fn nd1(&self) -> f64 {
+        BSOption::normdist(self.d1())
+    }
+
+    /// Get `N(d2)`, the standard normal cumulative distribution at `d2`.
+    pub fn nd2(&self) -> f64 {
+        BSOption::normdist(self.d2())
+    }
+
+    /// Get the call's intrinsic value, `max(S - K, 0)`, i.e. what exercising
+    /// it now would be worth, without discounting.
+    pub fn call_intrinsic(&self) -> f64 {
+        (self.asset_price - self.strike).max(0.0)
+    }
+
+    /// Get the put's intrinsic value, `max(K - S, 0)`, i.e. what exercising
+    /// it now would be worth, without discounting.
+    pub fn put_intrinsic(&self) -> f64 {
+        (self.strike - self.asset_price).max(0.0)
+    }
+
+    /// Get the call's extrinsic (time) value, `call_value() - call_intrinsic()`.
+    ///
+    /// This can be negative for European options deep in the money, e.g.
+    /// when payouts exceed the interest earned until maturity.
+    pub fn call_extrinsic(&self) -> f64 {
+        self.call_value() - self.call_intrinsic()
+    }
+
+    /// Get the put's extrinsic (time) value, `put_value() - put_intrinsic()`.
+    ///
+    /// This can be negative for European options deep in the money, as the
+    /// strike is only received at maturity.
+    pub fn put_extrinsic(&self) -> f64 {
+        self.put_value() - self.put_intrinsic()
+    }
+
+    /// Get a quick approximation of the volatility implied by the call's
+    /// `price`, with Corrado and Miller's closed form (Brenner and
+    /// Subrahmanyam's at the money). Useful to seed an iterative solver, or
+    /// on its own where a rough figure will do. The option's own volatility
+    /// is ignored.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `price`: the call's market price.
+    pub fn call_implied_vol_guess(&self, price: f64) -> f64 {
+        implied::vol_guess(self, OptionType::Call, price)
+    }
+
+    /// Get a quick approximation of the volatility implied by the put's
+    /// `price`. See `call_implied_vol_guess()`.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `price`: the put's market price.
+    pub fn put_implied_vol_guess(&self, price: f64) -> f64 {
+        implied::vol_guess(self, OptionType::Put, price)
+    }
+
+    /// Get the volatilities implied by the market prices of a chain of calls
+    /// and puts at this option's expiry, in a single call. The asset price,
+    /// rates and shift are taken from this option, and its strike and
+    /// volatility are ignored.
+    ///
+    /// Prices are inverted under the option's model, see `set_model()`, so
+    /// that e.g. `Model.crr(steps)` gives the volatilities implied by
+    /// American prices. The Black-Scholes closed form is inverted with
+    /// Newton's method, and other models by bisection, which is much slower.
+    ///
+    /// Prices that no volatility in `[0.0001, 5]` reaches, e.g. below the
+    /// intrinsic value, give `NaN`. The solver's tolerance and iteration
+    /// limit are set with `set_implied_vol_config()`.
+    ///
+    /// Returns an empty array if `strikes`, `prices` and `is_call` have
+    /// different lengths.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `strikes`: the options' strikes.
+    /// - `prices`: the options' market prices, in the same order.
+    /// - `is_call`: non-zero for calls and zero for puts, in the same order.
+    pub fn chain_implied_volatilities(
+        &self,
+        strikes: &[f64],
+        prices: &[f64],
+        is_call: &[u8],
+    ) -> Vec<f64> {
+        crate::utils::set_panic_hook();
+        trace_scope!("BSOption::chain_implied_volatilities");
+
+        implied::implied_vols(self, strikes, prices, is_call)
+    }
+
+    /// Get the strike at which the call or put is worth `target_price`, e.g.
+    /// to answer "what strike costs 1.50?". Values come from the option's
+    /// model, see `set_model()`, and the option's own strike is ignored.
+    ///
+    /// Returns `None` if no strike reaches the price: prices must be positive,
+    /// and for calls, below the asset price net of payouts.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `target_price`: the price the option should be worth.
+    /// - `option_type`: whether to solve for a call's or a put's strike.
+    pub fn solve_strike_for_price(
+        &self,
+        target_price: f64,
+        option_type: OptionType,
+    ) -> Option<f64> {
+        crate::utils::set_panic_hook();
+        trace_scope!("BSOption::solve_strike_for_price");
+
+        implied::strike_for_price(self, option_type, target_price)
+    }
+
+    /// Get the payout rate implied by the market prices of a call and a put
+    /// at the option's strike and maturity, from put-call parity, in the same
+    /// annual form as `payout_rate()`. The option's own payout rate is
+    /// ignored.
+    ///
+    /// Returns `None` if the option has expired, or if the prices imply an
+    /// asset price net of payouts that is not positive, since no payout rate
+    /// explains them.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `call_price`: the call's market price.
+    /// - `put_price`: the put's market price.
+    pub fn implied_payout_rate(&self, call_price: f64, put_price: f64) -> Option<f64> {
+        let net_asset_price = implied::parity_asset_price(self, call_price, put_price);
+        if self.time_to_maturity <= 0.0 || net_asset_price <= 0.0 {
+            return None;
+        }
+        let continuous =
+            (self.shifted_asset_price() / net_asset_price).ln() / self.time_to_maturity;
+
+        Some(continuous.exp_m1())
+    }
+
+    /// Get the present value of the discrete dividends until maturity
+    /// implied by the market prices of a call and a put at the option's
+    /// strike and maturity, from put-call parity. The option's own payout
+    /// rate is ignored, i.e. the dividends are assumed to be the only
+    /// payouts. See `DividendOption`.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `call_price`: the call's market price.
+    /// - `put_price`: the put's market price.
+    pub fn implied_dividends_present_value(&self, call_price: f64, put_price: f64) -> f64 {
+        self.shifted_asset_price() - implied::parity_asset_price(self, call_price, put_price)
+    }
+
+    /// Get the option's log-moneyness `ln(F / K)`, with `F = S * e^((r - q) * T)`
+    /// the forward price. Positive when the call is in the money with respect
+    /// to the forward. If the option has a shift, `S` and `K` include it.
+    pub fn log_moneyness(&self) -> f64 {
+        let forward = self.shifted_asset_price()
+            * ((self.r_continuous() - self.div_continuous()) * self.time_to_maturity).exp();
+
+        (forward / self.shifted_strike()).ln()
+    }
+
+    /// Get the option's log-moneyness in standard deviations,
+    /// `ln(F / K) / (vol * sqrt(T))`, i.e. the midpoint of `d1` and `d2`.
+    pub fn standardized_moneyness(&self) -> f64 {
+        self.log_moneyness() / (self.volatility * self.time_to_maturity.sqrt())
+    }
+
+    /// Get the risk-neutral probability that the call finishes in the money,
+    /// `N(d2)`.
+    pub fn call_itm_probability(&self) -> f64 {
+        self.nd2()
+    }
+
+    /// Get the risk-neutral probability that the put finishes in the money,
+    /// `N(-d2)`.
+    pub fn put_itm_probability(&self) -> f64 {
+        1.0 - self.nd2()
+    }
+
+    /// Get the real-world probability that the call finishes in the money.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `drift`: the asset's expected annual total return, in decimal form,
+    ///      including payouts. Passing the option's interest rate gives
+    ///      `call_itm_probability()`.
+    pub fn call_itm_probability_with_drift(&self, drift: f64) -> f64 {
+        BSOption::normdist(self.d2_with_drift(drift))
+    }
+
+    /// Get the real-world probability that the put finishes in the money.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `drift`: the asset's expected annual total return, in decimal form,
+    ///      including payouts. Passing the option's interest rate gives
+    ///      `put_itm_probability()`.
+    pub fn put_itm_probability_with_drift(&self, drift: f64) -> f64 {
+        1.0 - BSOption::normdist(self.d2_with_drift(drift))
+    }
+
     /// Get the option's time at which calculation is based.
     /// The time's unit is second-based timestamp.
     pub fn time_curr(&self) -> u32 {
@@ -226,6 +706,274 @@ impl BSOption {
         self.payout_rate
     }
 
+    /// Get the option's displaced-diffusion shift. See `set_shift()`.
+    pub fn shift(&self) -> f64 {
+        self.shift
+    }
+
+    /// Get the method used to calculate the option's greeks.
+    pub fn greek_method(&self) -> GreekMethod {
+        self.greek_method
+    }
+
+    /// Get the bump sizes and difference scheme used by finite-difference
+    /// greeks.
+    pub fn numerical_config(&self) -> NumericalConfig {
+        self.numerical_config
+    }
+
+    /// Get the units in which the option's greeks are reported.
+    pub fn greek_conventions(&self) -> GreekConventions {
+        self.greek_conventions
+    }
+
+    /// Get the settings of the solver used by
+    /// `chain_implied_volatilities()`.
+    pub fn implied_vol_config(&self) -> ImpliedVolConfig {
+        self.implied_vol_config
+    }
+
+    /// Get the model used to value the option. See `set_model()`.
+    pub fn model(&self) -> Model {
+        self.model
+    }
+
+    /// Get a copy of this option as of a future evaluation time, with
+    /// optional overrides for the asset price and volatility.
+    ///
+    /// All values and greeks of the returned option are calculated at the
+    /// new inputs, e.g. `option.value_at(time, Some(105.0), None).call_value()`.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `time`: a timestamp, in seconds, at which to evaluate the option.
+    ///      Times after the option's maturity are clamped to it, where the
+    ///      option is worth its intrinsic value, its call delta is `1`, `0` or
+    ///      `1/2` at the money, and its other greeks are zero.
+    ///
+    /// - `asset_price`: optional. The asset price at `time`. Defaults to the
+    ///      option's current asset price.
+    ///
+    /// - `volatility`: optional. The volatility at `time`. Defaults to the
+    ///      option's current volatility.
+    ///
+    /// # Examples
+    ///
+    /// ```js
+    /// // value the call a week from now, should the asset rally to 110
+    /// const value = option.value_at(option.time_curr() + 7 * 86400, 110).call_value();
+    /// ```
+    pub fn value_at(
+        &self,
+        time: u32,
+        asset_price: Option<f64>,
+        volatility: Option<f64>,
+    ) -> BSOption {
+        let mut option = *self;
+        option.set_time_curr(time.min(self.time_maturity));
+
+        if let Some(asset_price) = asset_price {
+            option.set_asset_price(asset_price);
+        }
+        if let Some(volatility) = volatility {
+            option.set_volatility(volatility);
+        }
+
+        option
+    }
+
+    /// Attribute the change in the call's value, from this option to `end`,
+    /// to its greeks.
+    ///
+    /// `end` should describe the same contract (strike and maturity) under
+    /// later market conditions.
+    pub fn call_pnl_attribution(&self, end: &BSOption) -> PnlAttribution {
+        PnlAttribution::for_call(self, end)
+    }
+
+    /// Attribute the change in the put's value, from this option to `end`,
+    /// to its greeks.
+    ///
+    /// `end` should describe the same contract (strike and maturity) under
+    /// later market conditions.
+    pub fn put_pnl_attribution(&self, end: &BSOption) -> PnlAttribution {
+        PnlAttribution::for_put(self, end)
+    }
+
+    /// Explain the change in the call's value, from this option to `to`, by
+    /// repricing under each input's move in turn.
+    pub fn call_explain(&self, to: &BSOption) -> PnlExplain {
+        PnlExplain::new(self, to, BSOption::call_value)
+    }
+
+    /// Explain the change in the put's value, from this option to `to`, by
+    /// repricing under each input's move in turn.
+    pub fn put_explain(&self, to: &BSOption) -> PnlExplain {
+        PnlExplain::new(self, to, BSOption::put_value)
+    }
+
+    /// Advise whether to exercise the call (as an American option) right
+    /// before an upcoming discrete dividend.
+    ///
+    /// Returns `None` if the ex-dividend date is not within the option's
+    /// remaining life.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `dividend`: the dividend amount per share.
+    ///
+    /// - `time_ex_dividend`: a timestamp, in seconds, of the ex-dividend date.
+    pub fn call_dividend_exercise_advice(
+        &self,
+        dividend: f64,
+        time_ex_dividend: u32,
+    ) -> Option<ExerciseAdvice> {
+        ExerciseAdvice::for_call(self, dividend, time_ex_dividend)
+    }
+
+    /// Advise whether to exercise the put (as an American option) now,
+    /// rather than holding it through an upcoming discrete dividend.
+    ///
+    /// Returns `None` if the ex-dividend date is not within the option's
+    /// remaining life.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `dividend`: the dividend amount per share.
+    ///
+    /// - `time_ex_dividend`: a timestamp, in seconds, of the ex-dividend date.
+    pub fn put_dividend_exercise_advice(
+        &self,
+        dividend: f64,
+        time_ex_dividend: u32,
+    ) -> Option<ExerciseAdvice> {
+        ExerciseAdvice::for_put(self, dividend, time_ex_dividend)
+    }
+
+    /// Update the option's displaced-diffusion shift.
+    ///
+    /// With a nonzero shift `s`, the option is priced under a shifted
+    /// lognormal model, where `asset_price + s` (rather than `asset_price`)
+    /// follows a geometric Brownian motion. This allows pricing underlyings
+    /// that are close to, or below, zero. The volatility is then a shifted
+    /// lognormal volatility; see `volatility::conversion` to convert it from
+    /// or to lognormal and normal volatilities. Defaults to `0.0`, i.e. plain
+    /// Black-Scholes.
+    ///
+    /// *Note*: the transform pricers (see `transform`) do not support shifts
+    /// and price the unshifted model.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `new_shift`: the option's new shift, in the asset price's units.
+    pub fn set_shift(&mut self, new_shift: f64) {
+        self.shift = new_shift;
+    }
+
+    /// Update the method used to calculate the option's greeks.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `new_greek_method`: `GreekMethod.Analytic` (the default),
+    ///      `GreekMethod.FiniteDifference`, `GreekMethod.RichardsonFD` or
+    ///      `GreekMethod.AutomaticDifferentiation`.
+    pub fn set_greek_method(&mut self, new_greek_method: GreekMethod) {
+        self.greek_method = new_greek_method;
+    }
+
+    /// Update the bump sizes and difference scheme used by finite-difference
+    /// greeks, i.e. gamma and vega with `GreekMethod.FiniteDifference`, and
+    /// the 1-day theta.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `new_numerical_config`: the new finite-difference settings.
+    pub fn set_numerical_config(&mut self, new_numerical_config: NumericalConfig) {
+        self.numerical_config = new_numerical_config;
+    }
+
+    /// Update the units in which the option's greeks are reported, e.g. to
+    /// quote rho per basis point or theta per trading day.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `new_greek_conventions`: the new scaling conventions.
+    pub fn set_greek_conventions(&mut self, new_greek_conventions: GreekConventions) {
+        self.greek_conventions = new_greek_conventions;
+    }
+
+    /// Update the price tolerance and iteration limit of the solver used by
+    /// `chain_implied_volatilities()`.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `new_implied_vol_config`: the new solver settings.
+    pub fn set_implied_vol_config(&mut self, new_implied_vol_config: ImpliedVolConfig) {
+        self.implied_vol_config = new_implied_vol_config;
+    }
+
+    /// Update the model used by `call_value()`, `put_value()` and
+    /// `greeks()`, e.g. to value the option as an American option.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `new_model`: `Model.black_scholes()` (the default),
+    ///      `Model.crr(steps)`, `Model.binomial_tree(tree)`,
+    ///      `Model.crank_nicolson(solver)` or `Model.monte_carlo(paths, seed)`.
+    pub fn set_model(&mut self, new_model: Model) {
+        self.model = new_model;
+    }
+
+    /// Get the expected range of the asset price for each day until the
+    /// option's maturity.
+    ///
+    /// Prices are assumed to be lognormally distributed with the option's
+    /// volatility.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `confidence_levels`: the probabilities, in decimal form, that the
+    ///      asset price ends up within each band (e.g. `0.95` for '95%').
+    ///
+    /// - `drift`: the asset's expected annual return, in decimal form. For
+    ///      risk-neutral bands, pass the interest rate net of the payout
+    ///      rate, `(1 + interest) / (1 + payout_rate) - 1`, i.e. a drift of
+    ///      `r - q` continuously compounded.
+    pub fn probability_cone(&self, confidence_levels: &[f64], drift: f64) -> ProbabilityCone {
+        ProbabilityCone::new(self, confidence_levels, drift)
+    }
+
+    /// Get the move of the asset price by maturity implied by the price of a
+    /// straddle at the option's strike, as a quick approximation and as the
+    /// moves to the straddle's breakevens.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `straddle_price`: the market price of a call and a put at the
+    ///      option's strike, usually at the money.
+    pub fn straddle_implied_move(&self, straddle_price: f64) -> ImpliedMove {
+        ImpliedMove::new(self, straddle_price, self.strike, self.strike)
+    }
+
+    /// Get the move of the asset price by maturity implied by the price of a
+    /// strangle. See `straddle_implied_move()`. The option's own strike is
+    /// ignored.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `strangle_price`: the market price of the put and the call.
+    ///
+    /// - `put_strike`, `call_strike`: the strikes of the put and the call,
+    ///      usually below and above the asset price.
+    pub fn strangle_implied_move(
+        &self,
+        strangle_price: f64,
+        put_strike: f64,
+        call_strike: f64,
+    ) -> ImpliedMove {
+        ImpliedMove::new(self, strangle_price, put_strike, call_strike)
+    }
+
     /// Update the time at which the option's calculation is based.
     ///
     /// **Arguments:**
@@ -281,6 +1029,15 @@ impl BSOption {
         self.volatility = new_volatility;
     }
 
+    /// Update the option's interest rate.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `new_interest`: the option's new annual interest rate.
+    pub fn set_interest(&mut self, new_interest: f64) {
+        self.interest = new_interest;
+    }
+
     /// Update the option's payout rate.
     ///
     /// **Arguments:**
@@ -290,19 +1047,20 @@ impl BSOption {
         self.payout_rate = new_payout_rate;
     }
 
-    fn d1(&self) -> f64 {
-        let s_k_ratio = self.asset_price / self.strike;
-        let vol_factor = self.volatility.powi(2) / 2.0;
-        let rates = self.r_continuous() - self.div_continuous() + vol_factor;
+    /// `d2` with the asset growing at `drift` instead of the interest rate.
+    fn d2_with_drift(&self, drift: f64) -> f64 {
+        let vol_sqrt_time = self.volatility * self.time_to_maturity.sqrt();
+        let drift_difference = drift.ln_1p() - self.r_continuous();
 
-        let num = s_k_ratio.ln() + rates * self.time_to_maturity;
-        let den = self.volatility * self.time_to_maturity.powf(0.5);
+        self.d2() + drift_difference * self.time_to_maturity / vol_sqrt_time
+    }
 
-        num / den
+    fn shifted_asset_price(&self) -> f64 {
+        self.asset_price + self.shift
     }
 
-    fn d2(&self) -> f64 {
-        self.d1() - self.volatility * self.time_to_maturity.powf(0.5)
+    fn shifted_strike(&self) -> f64 {
+        self.strike + self.shift
     }
 
     fn r_continuous(&self) -> f64 {
@@ -324,3 +1082,65 @@ impl BSOption {
         ((time_def.time_maturity - time_def.time_curr) as f64) / TIMESTAMP_ONE_YEAR
     }
 }
+
+impl CharacteristicFunction for BSOption {
+    /// Under Black-Scholes, `ln(S_T)` is normally distributed with mean
+    /// `ln(S) + (r - q - vol^2 / 2) * T` and variance `vol^2 * T`.
+    fn log_price_cf(&self, u: Complex64) -> Complex64 {
+        let variance = self.volatility.powi(2) * self.time_to_maturity;
+        let mean = self.asset_price.ln()
+            + (self.r_continuous() - self.div_continuous()) * self.time_to_maturity
+            - variance / 2.0;
+
+        let i = Complex64::new(0.0, 1.0);
+        (i * u * mean - u * u * variance / 2.0).exp()
+    }
+
+    fn discount_factor(&self) -> f64 {
+        (-self.r_continuous() * self.time_to_maturity).exp()
+    }
+
+    fn log_price_cumulants(&self) -> [f64; 3] {
+        let variance = self.volatility.powi(2) * self.time_to_maturity;
+        let mean = self.asset_price.ln()
+            + (self.r_continuous() - self.div_continuous()) * self.time_to_maturity
+            - variance / 2.0;
+
+        [mean, variance, 0.0]
+    }
+}
+
+/// The state is the shifted asset price `S + shift`, so that simulated prices
+/// follow the same shifted lognormal distribution as the closed form.
+impl PathModel for BSOption {
+    type State = f64;
+
+    fn initial_state(&self) -> f64 {
+        self.shifted_asset_price()
+    }
+
+    fn price(&self, state: &f64) -> f64 {
+        state - self.shift
+    }
+
+    /// The shifted asset price follows a geometric Brownian motion, which is
+    /// simulated exactly:
+    /// `S(t + dt) = S(t) * e^((r - q - vol^2 / 2) * dt + vol * sqrt(dt) * z)`.
+    fn step(&self, state: &f64, dt: f64, normals: &[f64]) -> f64 {
+        let drift =
+            (self.r_continuous() - self.div_continuous() - self.volatility.powi(2) / 2.0) * dt;
+        state * (drift + self.volatility * dt.sqrt() * normals[0]).exp()
+    }
+
+    fn discount_rate(&self) -> f64 {
+        self.r_continuous()
+    }
+
+    fn horizon(&self) -> f64 {
+        self.time_to_maturity
+    }
+
+    fn control_model(&self) -> Option<BSOption> {
+        Some(*self)
+    }
+}
@@ -0,0 +1,155 @@
+//! Settings for finite-difference greeks.
+
+use wasm_bindgen::prelude::*;
+
+/// An enumeration of the supported finite-difference schemes.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum DifferenceScheme {
+    /// Reprice after bumping the input up only. This is the default, and
+    /// needs one extra valuation per greek.
+    Forward,
+    /// Reprice after bumping the input both up and down. Needs two extra
+    /// valuations per greek, but its error shrinks with the square of the
+    /// bump size instead of linearly.
+    Central,
+}
+
+/// The bump sizes and difference scheme used by finite-difference greeks,
+/// i.e. the numerical gamma and vega (with `GreekMethod.FiniteDifference` or
+/// `GreekMethod.RichardsonFD`) and the 1-day theta.
+///
+/// See `BSOption::set_numerical_config()`.
+///
+/// ```rust
+/// use opcalc::option::numerical::{DifferenceScheme, NumericalConfig};
+///
+/// let config = NumericalConfig::new()
+///     .with_price_bump(0.01)
+///     .with_difference_scheme(DifferenceScheme::Central);
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct NumericalConfig {
+    price_bump: f64,
+    volatility_bump: f64,
+    time_bump: u32,
+    difference_scheme: DifferenceScheme,
+}
+
+impl Default for NumericalConfig {
+    fn default() -> NumericalConfig {
+        NumericalConfig {
+            price_bump: 0.001,
+            volatility_bump: 0.0001,
+            time_bump: 86_400,
+            difference_scheme: DifferenceScheme::Forward,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl NumericalConfig {
+    /// Create a `NumericalConfig` with a price bump of `0.001`, a volatility
+    /// bump of `0.0001`, a time bump of one day, and forward differences.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> NumericalConfig {
+        Default::default()
+    }
+
+    /// Set the bump applied to the asset price, in the asset price's units.
+    pub fn with_price_bump(self, price_bump: f64) -> NumericalConfig {
+        NumericalConfig { price_bump, ..self }
+    }
+
+    /// Set the bump applied to the volatility, e.g. `0.0001` for 0.01%.
+    pub fn with_volatility_bump(self, volatility_bump: f64) -> NumericalConfig {
+        NumericalConfig {
+            volatility_bump,
+            ..self
+        }
+    }
+
+    /// Set the bump applied to the current time, in seconds. Theta is still
+    /// reported per day.
+    pub fn with_time_bump(self, time_bump: u32) -> NumericalConfig {
+        NumericalConfig { time_bump, ..self }
+    }
+
+    /// Set the finite-difference scheme.
+    pub fn with_difference_scheme(self, difference_scheme: DifferenceScheme) -> NumericalConfig {
+        NumericalConfig {
+            difference_scheme,
+            ..self
+        }
+    }
+
+    /// Get the bump applied to the asset price.
+    pub fn price_bump(&self) -> f64 {
+        self.price_bump
+    }
+
+    /// Get the bump applied to the volatility.
+    pub fn volatility_bump(&self) -> f64 {
+        self.volatility_bump
+    }
+
+    /// Get the bump applied to the current time, in seconds.
+    pub fn time_bump(&self) -> u32 {
+        self.time_bump
+    }
+
+    /// Get the finite-difference scheme.
+    pub fn difference_scheme(&self) -> DifferenceScheme {
+        self.difference_scheme
+    }
+}
+
+impl NumericalConfig {
+    /// Approximate the derivatives of the call and put results returned by
+    /// `f` at `x`, bumping `x` by `bump` with the configured scheme.
+    pub(crate) fn differentiate<F: Fn(f64) -> (f64, f64)>(
+        &self,
+        f: F,
+        x: f64,
+        bump: f64,
+    ) -> (f64, f64) {
+        let (call_up, put_up) = f(x + bump);
+        let (call_down, put_down, width) = match self.difference_scheme {
+            DifferenceScheme::Forward => {
+                let (call, put) = f(x);
+                (call, put, bump)
+            }
+            DifferenceScheme::Central => {
+                let (call, put) = f(x - bump);
+                (call, put, 2.0 * bump)
+            }
+        };
+
+        ((call_up - call_down) / width, (put_up - put_down) / width)
+    }
+
+    /// Like `differentiate()`, but combines the estimates for `bump` and
+    /// `bump / 2` with Richardson extrapolation to cancel the leading error
+    /// term.
+    pub(crate) fn extrapolate<F: Fn(f64) -> (f64, f64)>(
+        &self,
+        f: F,
+        x: f64,
+        bump: f64,
+    ) -> (f64, f64) {
+        let (call_coarse, put_coarse) = self.differentiate(&f, x, bump);
+        let (call_fine, put_fine) = self.differentiate(&f, x, bump / 2.0);
+
+        // forward differences have an error of order h, central ones of order h^2
+        let weight = match self.difference_scheme {
+            DifferenceScheme::Forward => 2.0,
+            DifferenceScheme::Central => 4.0,
+        };
+
+        (
+            (weight * call_fine - call_coarse) / (weight - 1.0),
+            (weight * put_fine - put_coarse) / (weight - 1.0),
+        )
+    }
+}
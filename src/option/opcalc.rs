@@ -1,42 +1,279 @@
 //! Implements Black-Scholes related calculation logic.
 
 pub mod op_calc {
-    use crate::option::BSOption;
+    use crate::option::dual::{Dual64, HyperDual64, Scalar};
+    use crate::option::{BSOption, GreekMethod, OptionType};
+    use crate::utils::normal_pdf;
 
+    /// A result calculated for both the call and the put.
+    #[derive(Copy, Clone, Debug)]
     pub struct OptionResults {
+        /// The call's result.
         pub call: f64,
+        /// The put's result.
         pub put: f64,
     }
 
-    pub fn calculate_option_values(&option: &BSOption) -> OptionResults {
+    /// The inputs of the Black-Scholes formulas, generic over the number type
+    /// so that they can be differentiated automatically with `Dual64`.
+    #[derive(Copy, Clone)]
+    pub struct PricingInputs<T> {
+        pub asset_price: T,
+        pub strike: T,
+        pub interest: T,
+        pub payout_rate: T,
+        pub volatility: T,
+        pub time_to_maturity: T,
+    }
+
+    impl<T: Scalar> PricingInputs<T> {
+        /// Get the option's inputs, with the shift applied to the asset price
+        /// and strike, and continuously compounded rates.
+        pub fn new(option: &BSOption) -> PricingInputs<T> {
+            PricingInputs {
+                asset_price: T::constant(option.shifted_asset_price()),
+                strike: T::constant(option.shifted_strike()),
+                interest: T::constant(option.r_continuous()),
+                payout_rate: T::constant(option.div_continuous()),
+                volatility: T::constant(option.volatility),
+                time_to_maturity: T::constant(option.time_to_maturity),
+            }
+        }
+
+        fn carry_factor(&self) -> T {
+            (-self.payout_rate * self.time_to_maturity).exp()
+        }
+
+        fn discount_factor(&self) -> T {
+            (-self.interest * self.time_to_maturity).exp()
+        }
+
+        fn d1_d2(&self) -> (T, T) {
+            let vol_sqrt_time = self.volatility * self.time_to_maturity.sqrt();
+            let rates = self.interest - self.payout_rate
+                + self.volatility * self.volatility / T::constant(2.0);
+            let d1 = ((self.asset_price / self.strike).ln() + rates * self.time_to_maturity)
+                / vol_sqrt_time;
+
+            (d1, d1 - vol_sqrt_time)
+        }
+
+        /// Get the call and put values.
+        pub fn values(&self) -> (T, T) {
+            let (d1, d2) = self.d1_d2();
+            let discounted_asset_price = self.asset_price * self.carry_factor();
+            let discounted_strike = self.strike * self.discount_factor();
+
+            let call =
+                discounted_asset_price * d1.normal_cdf() - discounted_strike * d2.normal_cdf();
+            let put = call - discounted_asset_price + discounted_strike;
+
+            (call, put)
+        }
+
+        /// Get the call and put deltas.
+        pub fn deltas(&self) -> (T, T) {
+            let (d1, _) = self.d1_d2();
+            let carry_factor = self.carry_factor();
+            let call = carry_factor * d1.normal_cdf();
+
+            (call, call - carry_factor)
+        }
+    }
+
+    /// Evaluate `f` with the input chosen by `select` set up for
+    /// differentiation, and return the derivatives of the call and put results.
+    fn dual_derivatives<S, F>(option: &BSOption, select: S, f: F) -> OptionResults
+    where
+        S: Fn(&mut PricingInputs<Dual64>) -> &mut Dual64,
+        F: Fn(&PricingInputs<Dual64>) -> (Dual64, Dual64),
+    {
+        let mut inputs = PricingInputs::new(option);
+        let input = select(&mut inputs);
+        *input = Dual64::variable(input.re());
+
+        let (call, put) = f(&inputs);
+        OptionResults {
+            call: call.eps(),
+            put: put.eps(),
+        }
+    }
+
+    /// Calculates the matrix of second derivatives of the call's or put's
+    /// value with respect to the asset price, the volatility and the passage
+    /// of time (in years), in that order.
+    pub fn calculate_hessian(&option: &BSOption, option_type: OptionType) -> [[f64; 3]; 3] {
         crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_hessian");
+
+        fn input(inputs: &mut PricingInputs<HyperDual64>, index: usize) -> &mut HyperDual64 {
+            match index {
+                0 => &mut inputs.asset_price,
+                1 => &mut inputs.volatility,
+                _ => &mut inputs.time_to_maturity,
+            }
+        }
+
+        let second_derivative = |row: usize, column: usize| {
+            let mut inputs = PricingInputs::<HyperDual64>::new(&option);
+            let value = input(&mut inputs, row).re();
+            *input(&mut inputs, row) = HyperDual64::new(value, 1.0, 0.0, 0.0);
+            let tagged = *input(&mut inputs, column);
+            *input(&mut inputs, column) = HyperDual64::new(tagged.re(), tagged.eps1(), 1.0, 0.0);
+
+            let (call, put) = inputs.values();
+            let derivative = match option_type {
+                OptionType::Call => call.eps1eps2(),
+                OptionType::Put => put.eps1eps2(),
+            };
+
+            //  time passing shortens the time to maturity, flipping the sign
+            //  once for each time derivative
+            if (row == 2) != (column == 2) {
+                -derivative
+            } else {
+                derivative
+            }
+        };
 
-        // calculate call value
-        let asset_price_factor = (-option.div_continuous() * option.time_to_maturity).exp();
-        let discounted_asset_price = option.asset_price * asset_price_factor;
-        //  call_pt1 = S_t * N(d1)
-        let call_pt1 = discounted_asset_price * BSOption::normdist(option.d1());
+        let mut hessian = [[0.0; 3]; 3];
+        for (row, entries) in hessian.iter_mut().enumerate() {
+            for (column, entry) in entries.iter_mut().enumerate() {
+                *entry = second_derivative(row, column);
+            }
+        }
 
-        let strike_factor = (-option.r_continuous() * option.time_to_maturity).exp();
-        //  call_pt2 = K * e^(-r*t) * N(d2)
-        let call_pt2 = option.strike * strike_factor * BSOption::normdist(option.d2());
+        hessian
+    }
 
-        let call_value = call_pt1 - call_pt2;
+    pub fn calculate_dual_deltas(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_dual_deltas");
+
+        dual_derivatives(
+            &option,
+            |inputs| &mut inputs.asset_price,
+            |inputs| inputs.values(),
+        )
+    }
 
-        // calculate put value, which can be derived from call's value
-        let put_pt1 = option.asset_price * (-option.div_continuous() * option.r_continuous()).exp();
-        let put_pt2 = option.strike * (-option.r_continuous() * option.time_to_maturity).exp();
+    pub fn calculate_dual_gammas(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_dual_gammas");
 
-        let put_value = call_value - put_pt1 + put_pt2;
+        let gammas = dual_derivatives(
+            &option,
+            |inputs| &mut inputs.asset_price,
+            |inputs| inputs.deltas(),
+        );
 
+        let conventions = option.greek_conventions;
         OptionResults {
-            call: call_value,
-            put: put_value,
+            call: conventions.scale_gamma(gammas.call, option.asset_price),
+            put: conventions.scale_gamma(gammas.put, option.asset_price),
         }
     }
 
-    pub fn calculate_deltas(&option: &BSOption) -> OptionResults {
+    pub fn calculate_dual_vegas(&option: &BSOption) -> OptionResults {
         crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_dual_vegas");
+
+        let vegas = dual_derivatives(
+            &option,
+            |inputs| &mut inputs.volatility,
+            |inputs| inputs.values(),
+        );
+
+        let unit = option.greek_conventions.volatility_unit();
+        OptionResults {
+            call: vegas.call * unit,
+            put: vegas.put * unit,
+        }
+    }
+
+    /// Calculates the continuous-time thetas per year by automatic
+    /// differentiation, like `calculate_analytic_thetas()`.
+    pub fn calculate_dual_thetas(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_dual_thetas");
+
+        let derivatives = dual_derivatives(
+            &option,
+            |inputs| &mut inputs.time_to_maturity,
+            |inputs| inputs.values(),
+        );
+
+        //  time passing shortens the time to maturity
+        OptionResults {
+            call: -derivatives.call,
+            put: -derivatives.put,
+        }
+    }
+
+    pub fn calculate_dual_rhos(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_dual_rhos");
+
+        let rhos = dual_derivatives(
+            &option,
+            |inputs| &mut inputs.interest,
+            |inputs| inputs.values(),
+        );
+
+        let unit = option.greek_conventions.rate_unit();
+        OptionResults {
+            call: rhos.call * unit,
+            put: rhos.put * unit,
+        }
+    }
+
+    pub fn calculate_dual_epsilons(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_dual_epsilons");
+
+        let epsilons = dual_derivatives(
+            &option,
+            |inputs| &mut inputs.payout_rate,
+            |inputs| inputs.values(),
+        );
+
+        let unit = option.greek_conventions.rate_unit();
+        OptionResults {
+            call: epsilons.call * unit,
+            put: epsilons.put * unit,
+        }
+    }
+
+    pub fn calculate_option_values(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_option_values");
+
+        if is_expired(&option) {
+            return OptionResults {
+                call: option.call_intrinsic(),
+                put: option.put_intrinsic(),
+            };
+        }
+
+        let (call, put) = PricingInputs::<f64>::new(&option).values();
+
+        OptionResults { call, put }
+    }
+
+    pub fn calculate_deltas(option: &BSOption) -> OptionResults {
+        if is_expired(option) {
+            return expired_deltas(option);
+        }
+
+        match option.greek_method {
+            GreekMethod::AutomaticDifferentiation => calculate_dual_deltas(option),
+            _ => calculate_analytic_deltas(option),
+        }
+    }
+
+    pub fn calculate_analytic_deltas(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_analytic_deltas");
 
         let delta_factor = -option.div_continuous() * option.time_to_maturity;
         let call_delta = delta_factor.exp() * BSOption::normdist(option.d1());
@@ -48,63 +285,390 @@ pub mod op_calc {
         }
     }
 
-    pub fn calculate_gammas(&option: &BSOption) -> OptionResults {
+    pub fn calculate_lambdas(&option: &BSOption) -> OptionResults {
         crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_lambdas");
 
-        // minimum price movement unit
-        const PRICE_DELTA: f64 = 0.001;
+        let values = calculate_option_values(&option);
+        let deltas = calculate_deltas(&option);
 
-        let mut option_prime = option;
-        option_prime.set_asset_price(option.asset_price() + PRICE_DELTA);
+        //  lambda = delta * S_t / V
+        OptionResults {
+            call: deltas.call * option.asset_price / values.call,
+            put: deltas.put * option.asset_price / values.put,
+        }
+    }
+
+    pub fn calculate_gammas(option: &BSOption) -> OptionResults {
+        if is_expired(option) {
+            return expired_sensitivities();
+        }
+
+        match option.greek_method {
+            GreekMethod::Analytic => calculate_analytic_gammas(option),
+            GreekMethod::FiniteDifference | GreekMethod::RichardsonFD => {
+                calculate_numerical_gammas(option)
+            }
+            GreekMethod::AutomaticDifferentiation => calculate_dual_gammas(option),
+        }
+    }
+
+    pub fn calculate_analytic_gammas(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_analytic_gammas");
+
+        let components = option.pricing_components();
 
-        let call_gamma = (option_prime.call_delta() - option.call_delta()) / PRICE_DELTA;
-        let put_gamma = (option_prime.put_delta() - option.put_delta()) / PRICE_DELTA;
+        //  gamma = e^(-q*t) * n(d1) / (S_t * vol * sqrt(t)), identical for calls and puts
+        let gamma = components.carry_factor() * normal_pdf(components.d1())
+            / (option.shifted_asset_price() * option.volatility * option.time_to_maturity.sqrt());
+        let gamma = option
+            .greek_conventions
+            .scale_gamma(gamma, option.asset_price);
 
         OptionResults {
-            call: call_gamma,
-            put: put_gamma,
+            call: gamma,
+            put: gamma,
         }
     }
 
-    pub fn calculate_vegas(&option: &BSOption) -> OptionResults {
+    pub fn calculate_numerical_gammas(&option: &BSOption) -> OptionResults {
         crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_numerical_gammas");
 
-        const VOLATILITY_DELTA: f64 = 0.0001;
+        let config = option.numerical_config;
+        let (call_gamma, put_gamma) = bumped_derivatives(
+            &option,
+            |asset_price| {
+                let mut option_prime = option;
+                option_prime.set_asset_price(asset_price);
+                (option_prime.call_delta(), option_prime.put_delta())
+            },
+            option.asset_price,
+            config.price_bump(),
+        );
 
-        let mut option_prime = option;
-        option_prime.set_volatility(option.volatility() + VOLATILITY_DELTA);
+        let conventions = option.greek_conventions;
+        OptionResults {
+            call: conventions.scale_gamma(call_gamma, option.asset_price),
+            put: conventions.scale_gamma(put_gamma, option.asset_price),
+        }
+    }
+
+    pub fn calculate_vegas(option: &BSOption) -> OptionResults {
+        if is_expired(option) {
+            return expired_sensitivities();
+        }
+
+        match option.greek_method {
+            GreekMethod::Analytic => calculate_analytic_vegas(option),
+            GreekMethod::FiniteDifference | GreekMethod::RichardsonFD => {
+                calculate_numerical_vegas(option)
+            }
+            GreekMethod::AutomaticDifferentiation => calculate_dual_vegas(option),
+        }
+    }
+
+    pub fn calculate_analytic_vegas(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_analytic_vegas");
 
-        let call_vega = (option_prime.call_value() - option.call_value()) / 0.01;
-        let put_vega = (option_prime.call_value() - option.call_value()) / 0.01;
+        let components = option.pricing_components();
+
+        //  vega = S_t * e^(-q*t) * n(d1) * sqrt(t), identical for calls and puts
+        let vega = option.shifted_asset_price()
+            * components.carry_factor()
+            * normal_pdf(components.d1())
+            * option.time_to_maturity.sqrt()
+            * option.greek_conventions.volatility_unit();
 
         OptionResults {
-            call: call_vega,
-            put: put_vega,
+            call: vega,
+            put: vega,
         }
     }
 
-    pub fn calculate_thetas(&option: &BSOption) -> OptionResults {
+    pub fn calculate_numerical_vegas(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_numerical_vegas");
+
+        let config = option.numerical_config;
+        let (call_vega, put_vega) = bumped_derivatives(
+            &option,
+            |volatility| {
+                let mut option_prime = option;
+                option_prime.set_volatility(volatility);
+                let values = calculate_option_values(&option_prime);
+                (values.call, values.put)
+            },
+            option.volatility,
+            config.volatility_bump(),
+        );
+
+        let unit = option.greek_conventions.volatility_unit();
+        OptionResults {
+            call: call_vega * unit,
+            put: put_vega * unit,
+        }
+    }
+
+    pub fn calculate_rhos(option: &BSOption) -> OptionResults {
+        if is_expired(option) {
+            return expired_sensitivities();
+        }
+
+        match option.greek_method {
+            GreekMethod::AutomaticDifferentiation => calculate_dual_rhos(option),
+            _ => calculate_analytic_rhos(option),
+        }
+    }
+
+    pub fn calculate_analytic_rhos(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_analytic_rhos");
+
+        let components = option.pricing_components();
+        let discounted_strike = option.shifted_strike() * components.discount_factor();
+        let unit = option.greek_conventions.rate_unit();
+
+        //  call rho = K * t * e^(-r*t) * N(d2), put rho = -K * t * e^(-r*t) * N(-d2)
+        let call_rho = discounted_strike * option.time_to_maturity * components.nd2() * unit;
+        let put_rho =
+            -discounted_strike * option.time_to_maturity * (1.0 - components.nd2()) * unit;
+
+        OptionResults {
+            call: call_rho,
+            put: put_rho,
+        }
+    }
+
+    pub fn calculate_epsilons(option: &BSOption) -> OptionResults {
+        if is_expired(option) {
+            return expired_sensitivities();
+        }
+
+        match option.greek_method {
+            GreekMethod::AutomaticDifferentiation => calculate_dual_epsilons(option),
+            _ => calculate_analytic_epsilons(option),
+        }
+    }
+
+    pub fn calculate_analytic_epsilons(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_analytic_epsilons");
+
+        let components = option.pricing_components();
+        let discounted_asset_price = option.shifted_asset_price() * components.carry_factor();
+        let unit = option.greek_conventions.rate_unit();
+
+        //  call epsilon = -S_t * t * e^(-q*t) * N(d1), put epsilon = S_t * t * e^(-q*t) * N(-d1)
+        let call_epsilon =
+            -discounted_asset_price * option.time_to_maturity * components.nd1() * unit;
+        let put_epsilon =
+            discounted_asset_price * option.time_to_maturity * (1.0 - components.nd1()) * unit;
+
+        OptionResults {
+            call: call_epsilon,
+            put: put_epsilon,
+        }
+    }
+
+    pub fn calculate_vannas(&option: &BSOption) -> OptionResults {
         crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_vannas");
 
-        const TIMESTAMP_ONE_DAY: u32 = 86_400;
+        if is_expired(&option) {
+            return expired_sensitivities();
+        }
 
-        let mut option_prime = option;
-        option_prime.set_time_curr(option.time_curr() + TIMESTAMP_ONE_DAY);
+        let components = option.pricing_components();
 
-        let call_theta = option_prime.call_value() - option.call_value();
-        let put_theta = option_prime.put_value() - option.put_value();
+        //  vanna = -e^(-q*t) * n(d1) * d2 / vol, identical for calls and puts
+        let vanna = -components.carry_factor() * normal_pdf(components.d1()) * components.d2()
+            / option.volatility
+            * option.greek_conventions.volatility_unit();
+
+        OptionResults {
+            call: vanna,
+            put: vanna,
+        }
+    }
+
+    pub fn calculate_volgas(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_volgas");
+
+        if is_expired(&option) {
+            return expired_sensitivities();
+        }
+
+        let components = option.pricing_components();
+        let vega = calculate_analytic_vegas(&option).call;
+
+        //  volga = vega * d1 * d2 / vol, identical for calls and puts
+        let volga = vega * components.d1() * components.d2() / option.volatility
+            * option.greek_conventions.volatility_unit();
+
+        OptionResults {
+            call: volga,
+            put: volga,
+        }
+    }
+
+    pub fn calculate_vetas(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_vetas");
+
+        if is_expired(&option) {
+            return expired_sensitivities();
+        }
+
+        let components = option.pricing_components();
+        let vega = calculate_analytic_vegas(&option).call;
+        let r = option.r_continuous();
+        let q = option.div_continuous();
+        let time = option.time_to_maturity;
+
+        //  veta = vega * (q + (r - q) * d1 / (vol * sqrt(t)) - (1 + d1 * d2) / (2 * t)),
+        //  identical for calls and puts, scaled to one day passing
+        let veta = vega
+            * (q + (r - q) * components.d1() / (option.volatility * time.sqrt())
+                - (1.0 + components.d1() * components.d2()) / (2.0 * time))
+            / option.greek_conventions.days_per_year();
+
+        OptionResults {
+            call: veta,
+            put: veta,
+        }
+    }
+
+    /// Calculates the continuous-time thetas, i.e. the value's rate of change
+    /// per year as time passes.
+    pub fn calculate_annual_thetas(option: &BSOption) -> OptionResults {
+        if is_expired(option) {
+            return expired_sensitivities();
+        }
+
+        match option.greek_method {
+            GreekMethod::AutomaticDifferentiation => calculate_dual_thetas(option),
+            _ => calculate_analytic_thetas(option),
+        }
+    }
+
+    pub fn calculate_analytic_thetas(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_analytic_thetas");
+
+        let components = option.pricing_components();
+        let r = option.r_continuous();
+        let q = option.div_continuous();
+        let discounted_asset_price = option.shifted_asset_price() * components.carry_factor();
+        let discounted_strike = option.shifted_strike() * components.discount_factor();
+
+        //  decay = -S_t * e^(-q*t) * n(d1) * vol / (2 * sqrt(t)), shared by calls and puts
+        let decay = -discounted_asset_price * normal_pdf(components.d1()) * option.volatility
+            / (2.0 * option.time_to_maturity.sqrt());
+
+        //  call theta = decay - r * K * e^(-r*t) * N(d2) + q * S_t * e^(-q*t) * N(d1)
+        let call_theta = decay - r * discounted_strike * components.nd2()
+            + q * discounted_asset_price * components.nd1();
+        //  put theta = decay + r * K * e^(-r*t) * N(-d2) - q * S_t * e^(-q*t) * N(-d1)
+        let put_theta = decay + r * discounted_strike * (1.0 - components.nd2())
+            - q * discounted_asset_price * (1.0 - components.nd1());
 
         OptionResults {
             call: call_theta,
             put: put_theta,
         }
     }
+
+    /// Whether the option is at its maturity, where the closed forms divide
+    /// by the zero time to maturity.
+    pub(crate) fn is_expired(option: &BSOption) -> bool {
+        option.time_to_maturity <= 0.0
+    }
+
+    /// The limits of the deltas as the option reaches its maturity: `1` for
+    /// the call in the money, `-1` for the put, and `1/2` and `-1/2` at the
+    /// money, where `N(d1)` tends to `1/2`.
+    pub(crate) fn expired_deltas(option: &BSOption) -> OptionResults {
+        let moneyness = option.shifted_asset_price() - option.shifted_strike();
+        let call = if moneyness > 0.0 {
+            1.0
+        } else if moneyness < 0.0 {
+            0.0
+        } else {
+            0.5
+        };
+
+        OptionResults {
+            call,
+            put: call - 1.0,
+        }
+    }
+
+    /// The limits of the other greeks at maturity, when nothing but the asset
+    /// price affects the option's value any more.
+    fn expired_sensitivities() -> OptionResults {
+        OptionResults {
+            call: 0.0,
+            put: 0.0,
+        }
+    }
+
+    /// Approximate the derivatives of the call and put results of `f` with the
+    /// option's finite-difference settings, extrapolating them when the
+    /// option uses `GreekMethod::RichardsonFD`.
+    fn bumped_derivatives<F: Fn(f64) -> (f64, f64)>(
+        option: &BSOption,
+        f: F,
+        x: f64,
+        bump: f64,
+    ) -> (f64, f64) {
+        match option.greek_method {
+            GreekMethod::RichardsonFD => option.numerical_config.extrapolate(f, x, bump),
+            _ => option.numerical_config.differentiate(f, x, bump),
+        }
+    }
+
+    pub fn calculate_thetas(&option: &BSOption) -> OptionResults {
+        crate::utils::set_panic_hook();
+        trace_scope!("op_calc::calculate_thetas");
+
+        if is_expired(&option) {
+            return expired_sensitivities();
+        }
+
+        const TIMESTAMP_ONE_YEAR: f64 = 31_536_000.0;
+
+        let config = option.numerical_config;
+        let (call_theta, put_theta) = config.differentiate(
+            |time_curr| {
+                let mut option_prime = option;
+                option_prime.set_time_curr(time_curr as u32);
+                let values = calculate_option_values(&option_prime);
+                (values.call, values.put)
+            },
+            f64::from(option.time_curr),
+            f64::from(config.time_bump()),
+        );
+
+        //  scaled to one day passing
+        let seconds_per_day = TIMESTAMP_ONE_YEAR / option.greek_conventions.days_per_year();
+        OptionResults {
+            call: call_theta * seconds_per_day,
+            put: put_theta * seconds_per_day,
+        }
+    }
 }
 
 #[cfg(test)]
 mod opcalc_tests {
+    use crate::option::conventions::{GammaUnit, GreekConventions};
+    use crate::option::engine::Model;
+    use crate::option::numerical::{DifferenceScheme, NumericalConfig};
     use crate::option::opcalc::op_calc;
-    use crate::option::BSOption;
+    use crate::option::{BSOption, GreekMethod, OptionType};
 
     fn create_test_option() -> BSOption {
         let time_curr = 1606780800; // 2020/12/01 00:00:00
@@ -135,6 +699,22 @@ mod opcalc_tests {
         approx::assert_abs_diff_eq!(option_vals.put, 6.338100538847982, epsilon = f64::EPSILON);
     }
 
+    #[test]
+    fn calculates_put_values_with_payouts() {
+        let mut option = create_test_option();
+        option.set_payout_rate(0.02);
+
+        let option_vals = op_calc::calculate_option_values(&option);
+        let components = option.pricing_components();
+
+        // put-call parity: C - P = S * e^(-q*t) - K * e^(-r*t)
+        approx::assert_abs_diff_eq!(
+            option_vals.call - option_vals.put,
+            100.0 * components.carry_factor() - 105.0 * components.discount_factor(),
+            epsilon = 1e-12
+        );
+    }
+
     #[test]
     fn calculates_option_deltas() {
         let deltas = op_calc::calculate_deltas(&create_test_option());
@@ -147,7 +727,19 @@ mod opcalc_tests {
     fn calculates_option_gammas() {
         let gammas = op_calc::calculate_gammas(&create_test_option());
 
-        // TODO: investigate whether gamma should be absolutely equal for calls and puts.
+        approx::assert_abs_diff_eq!(gammas.call, 0.042321064715460606, epsilon = f64::EPSILON);
+        approx::assert_abs_diff_eq!(gammas.call, gammas.put, epsilon = f64::EPSILON);
+    }
+
+    #[test]
+    fn calculates_numerical_option_gammas() {
+        let mut option = create_test_option();
+        option.set_greek_method(GreekMethod::FiniteDifference);
+        let gammas = op_calc::calculate_gammas(&option);
+
+        let analytic_gammas = op_calc::calculate_analytic_gammas(&option);
+        approx::assert_abs_diff_eq!(gammas.call, analytic_gammas.call, epsilon = 1e-4);
+
         approx::assert_abs_diff_eq!(gammas.call, 0.04232231027889721, epsilon = f64::EPSILON);
         approx::assert_abs_diff_eq!(gammas.put, 0.042322310279008235, epsilon = f64::EPSILON);
     }
@@ -156,8 +748,21 @@ mod opcalc_tests {
     fn calculates_option_vegas() {
         let vegas = op_calc::calculate_vegas(&create_test_option());
 
+        approx::assert_abs_diff_eq!(vegas.call, 0.12000630679589515, epsilon = f64::EPSILON);
+        approx::assert_abs_diff_eq!(vegas.call, vegas.put, epsilon = f64::EPSILON);
+    }
+
+    #[test]
+    fn calculates_numerical_option_vegas() {
+        let mut option = create_test_option();
+        option.set_greek_method(GreekMethod::FiniteDifference);
+        let vegas = op_calc::calculate_vegas(&option);
+
+        let analytic_vegas = op_calc::calculate_analytic_vegas(&option);
+        approx::assert_abs_diff_eq!(vegas.call, analytic_vegas.call, epsilon = 1e-4);
+
         approx::assert_abs_diff_eq!(vegas.call, 0.12001554434952766, epsilon = f64::EPSILON);
-        approx::assert_abs_diff_eq!(vegas.put, 0.12001554434952766, epsilon = f64::EPSILON);
+        approx::assert_abs_diff_eq!(vegas.put, 0.12001554434952766, epsilon = 1e-10);
     }
 
     #[test]
@@ -167,4 +772,468 @@ mod opcalc_tests {
         approx::assert_abs_diff_eq!(thetas.call, -0.03115177341956965, epsilon = f64::EPSILON);
         approx::assert_abs_diff_eq!(thetas.put, -0.029717873380988635, epsilon = f64::EPSILON);
     }
+
+    #[test]
+    fn calculates_option_rhos() {
+        let option = create_test_option();
+        let rhos = op_calc::calculate_rhos(&option);
+
+        // bump the continuously compounded rate by 1% in both directions
+        let rate = option.r_continuous();
+        let mut up = option;
+        up.interest = (rate + 0.0001).exp() - 1.0;
+        let mut down = option;
+        down.interest = (rate - 0.0001).exp() - 1.0;
+
+        let call_rho = (up.call_value() - down.call_value()) / 0.0002 * 0.01;
+        let put_rho = (up.put_value() - down.put_value()) / 0.0002 * 0.01;
+        approx::assert_relative_eq!(rhos.call, call_rho, max_relative = 1e-6);
+        approx::assert_relative_eq!(rhos.put, put_rho, max_relative = 1e-6);
+        assert!(rhos.call > 0.0 && rhos.put < 0.0);
+    }
+
+    #[test]
+    fn calculates_option_epsilons() {
+        let mut option = create_test_option();
+        option.set_payout_rate(0.02);
+        let epsilons = op_calc::calculate_epsilons(&option);
+
+        // bump the continuously compounded payout rate by 1% in both directions
+        let rate = option.div_continuous();
+        let mut up = option;
+        up.set_payout_rate((rate + 0.0001).exp() - 1.0);
+        let mut down = option;
+        down.set_payout_rate((rate - 0.0001).exp() - 1.0);
+
+        let call_epsilon = (up.call_value() - down.call_value()) / 0.0002 * 0.01;
+        let put_epsilon = (up.put_value() - down.put_value()) / 0.0002 * 0.01;
+        approx::assert_relative_eq!(epsilons.call, call_epsilon, max_relative = 1e-6);
+        approx::assert_relative_eq!(epsilons.put, put_epsilon, max_relative = 1e-6);
+        assert!(epsilons.call < 0.0 && epsilons.put > 0.0);
+    }
+
+    #[test]
+    fn calculates_option_vannas() {
+        let mut option = create_test_option();
+        option.set_payout_rate(0.02);
+        let vannas = op_calc::calculate_vannas(&option);
+
+        // vanna is the change in delta for a 1% change in volatility
+        let mut up = option;
+        up.set_volatility(option.volatility() + 0.0001);
+        let mut down = option;
+        down.set_volatility(option.volatility() - 0.0001);
+
+        let call_vanna = (up.call_delta() - down.call_delta()) / 0.0002 * 0.01;
+        let put_vanna = (up.put_delta() - down.put_delta()) / 0.0002 * 0.01;
+        approx::assert_relative_eq!(vannas.call, call_vanna, max_relative = 1e-6);
+        approx::assert_relative_eq!(vannas.put, put_vanna, max_relative = 1e-6);
+    }
+
+    #[test]
+    fn calculates_option_volgas() {
+        let option = create_test_option();
+        let volgas = op_calc::calculate_volgas(&option);
+
+        // volga is the change in vega for a 1% change in volatility
+        let mut up = option;
+        up.set_volatility(option.volatility() + 0.0001);
+        let mut down = option;
+        down.set_volatility(option.volatility() - 0.0001);
+
+        let volga = (up.call_vega() - down.call_vega()) / 0.0002 * 0.01;
+        approx::assert_relative_eq!(volgas.call, volga, max_relative = 1e-6);
+        approx::assert_abs_diff_eq!(volgas.call, volgas.put, epsilon = f64::EPSILON);
+        approx::assert_abs_diff_eq!(option.volga(), volgas.call, epsilon = f64::EPSILON);
+    }
+
+    #[test]
+    fn calculates_option_vetas() {
+        let mut option = create_test_option();
+        option.set_payout_rate(0.02);
+        let vetas = op_calc::calculate_vetas(&option);
+
+        // veta is the change in vega as one day passes
+        let mut earlier = option;
+        earlier.set_time_curr(option.time_curr() - 1);
+        let mut later = option;
+        later.set_time_curr(option.time_curr() + 1);
+
+        let veta = (later.call_vega() - earlier.call_vega()) / 2.0 * 86_400.0;
+        approx::assert_relative_eq!(vetas.call, veta, max_relative = 1e-6);
+        approx::assert_abs_diff_eq!(option.veta(), vetas.put, epsilon = f64::EPSILON);
+    }
+
+    #[test]
+    fn calculates_option_lambdas() {
+        let option = create_test_option();
+        let lambdas = op_calc::calculate_lambdas(&option);
+
+        let call_lambda = option.call_delta() * 100.0 / option.call_value();
+        let put_lambda = option.put_delta() * 100.0 / option.put_value();
+        approx::assert_abs_diff_eq!(lambdas.call, call_lambda, epsilon = f64::EPSILON);
+        approx::assert_abs_diff_eq!(lambdas.put, put_lambda, epsilon = f64::EPSILON);
+        assert!(lambdas.call > 1.0 && lambdas.put < -1.0);
+    }
+
+    #[test]
+    fn calculates_central_difference_greeks() {
+        let mut option = create_test_option();
+        option.set_greek_method(GreekMethod::FiniteDifference);
+        option.set_numerical_config(
+            NumericalConfig::new()
+                .with_price_bump(0.01)
+                .with_volatility_bump(0.001)
+                .with_time_bump(3_600)
+                .with_difference_scheme(DifferenceScheme::Central),
+        );
+
+        let analytic_gammas = op_calc::calculate_analytic_gammas(&option);
+        let gammas = op_calc::calculate_gammas(&option);
+        approx::assert_relative_eq!(gammas.call, analytic_gammas.call, max_relative = 1e-5);
+        approx::assert_relative_eq!(gammas.put, analytic_gammas.put, max_relative = 1e-5);
+
+        let analytic_vegas = op_calc::calculate_analytic_vegas(&option);
+        let vegas = op_calc::calculate_vegas(&option);
+        approx::assert_relative_eq!(vegas.call, analytic_vegas.call, max_relative = 1e-5);
+        approx::assert_relative_eq!(vegas.put, analytic_vegas.put, max_relative = 1e-5);
+
+        let thetas = op_calc::calculate_thetas(&option);
+        approx::assert_relative_eq!(
+            thetas.call,
+            option.call_theta_per_day(),
+            max_relative = 1e-5
+        );
+        approx::assert_relative_eq!(thetas.put, option.put_theta_per_day(), max_relative = 1e-5);
+    }
+
+    #[test]
+    fn calculates_richardson_extrapolated_greeks() {
+        let mut option = create_test_option();
+        option.set_numerical_config(NumericalConfig::new().with_price_bump(0.5));
+        let analytic_gammas = op_calc::calculate_analytic_gammas(&option);
+
+        option.set_greek_method(GreekMethod::FiniteDifference);
+        let gammas = op_calc::calculate_gammas(&option);
+        option.set_greek_method(GreekMethod::RichardsonFD);
+        let extrapolated_gammas = op_calc::calculate_gammas(&option);
+
+        // extrapolating removes most of the error of the coarse bump
+        let error = (gammas.call - analytic_gammas.call).abs();
+        let extrapolated_error = (extrapolated_gammas.call - analytic_gammas.call).abs();
+        assert!(extrapolated_error < error / 10.0);
+
+        let analytic_vegas = op_calc::calculate_analytic_vegas(&option);
+        let vegas = op_calc::calculate_vegas(&option);
+        approx::assert_relative_eq!(vegas.call, analytic_vegas.call, max_relative = 1e-6);
+        approx::assert_relative_eq!(vegas.put, analytic_vegas.put, max_relative = 1e-6);
+    }
+
+    #[test]
+    fn calculates_greeks_by_automatic_differentiation() {
+        let mut option = create_test_option();
+        option.set_payout_rate(0.02);
+        option.set_greek_method(GreekMethod::AutomaticDifferentiation);
+
+        let pairs = [
+            (
+                op_calc::calculate_deltas(&option),
+                op_calc::calculate_analytic_deltas(&option),
+            ),
+            (
+                op_calc::calculate_gammas(&option),
+                op_calc::calculate_analytic_gammas(&option),
+            ),
+            (
+                op_calc::calculate_vegas(&option),
+                op_calc::calculate_analytic_vegas(&option),
+            ),
+            (
+                op_calc::calculate_annual_thetas(&option),
+                op_calc::calculate_analytic_thetas(&option),
+            ),
+            (
+                op_calc::calculate_rhos(&option),
+                op_calc::calculate_analytic_rhos(&option),
+            ),
+            (
+                op_calc::calculate_epsilons(&option),
+                op_calc::calculate_analytic_epsilons(&option),
+            ),
+        ];
+
+        for (dual, analytic) in pairs.iter() {
+            approx::assert_abs_diff_eq!(dual.call, analytic.call, epsilon = 1e-12);
+            approx::assert_abs_diff_eq!(dual.put, analytic.put, epsilon = 1e-12);
+        }
+    }
+
+    #[test]
+    fn calculates_option_hessians() {
+        let mut option = create_test_option();
+        option.set_payout_rate(0.02);
+
+        for &option_type in [OptionType::Call, OptionType::Put].iter() {
+            let hessian = op_calc::calculate_hessian(&option, option_type);
+
+            approx::assert_abs_diff_eq!(hessian[0][0], option.call_gamma(), epsilon = 1e-12);
+            approx::assert_abs_diff_eq!(hessian[0][1], option.call_vanna() / 0.01, epsilon = 1e-12);
+            approx::assert_abs_diff_eq!(hessian[1][1], option.volga() / 0.0001, epsilon = 1e-10);
+            approx::assert_abs_diff_eq!(
+                hessian[1][2],
+                option.veta() * 365.0 / 0.01,
+                epsilon = 1e-10
+            );
+
+            // time derivatives against central differences of the analytic greeks
+            let mut earlier = option;
+            earlier.set_time_curr(option.time_curr() - 1);
+            let mut later = option;
+            later.set_time_curr(option.time_curr() + 1);
+            let seconds_per_year = 31_536_000.0;
+
+            let (charm, theta_rate) = match option_type {
+                OptionType::Call => (
+                    later.call_delta() - earlier.call_delta(),
+                    later.call_theta_annual() - earlier.call_theta_annual(),
+                ),
+                OptionType::Put => (
+                    later.put_delta() - earlier.put_delta(),
+                    later.put_theta_annual() - earlier.put_theta_annual(),
+                ),
+            };
+            approx::assert_relative_eq!(
+                hessian[0][2],
+                charm / 2.0 * seconds_per_year,
+                max_relative = 1e-5
+            );
+            approx::assert_relative_eq!(
+                hessian[2][2],
+                theta_rate / 2.0 * seconds_per_year,
+                max_relative = 1e-5
+            );
+            approx::assert_abs_diff_eq!(hessian[2][0], hessian[0][2], epsilon = f64::EPSILON);
+        }
+    }
+
+    #[test]
+    fn scales_greeks_by_conventions() {
+        let option = create_test_option();
+        let mut scaled = option;
+        scaled.set_greek_conventions(
+            GreekConventions::new()
+                .with_volatility_unit(1.0)
+                .with_rate_unit(0.0001)
+                .with_days_per_year(252.0)
+                .with_gamma_unit(GammaUnit::Percent),
+        );
+
+        approx::assert_relative_eq!(scaled.call_vega(), option.call_vega() * 100.0);
+        approx::assert_relative_eq!(scaled.put_rho(), option.put_rho() / 100.0);
+        approx::assert_relative_eq!(scaled.call_gamma(), option.call_gamma() * 100.0 * 0.01);
+        approx::assert_relative_eq!(
+            scaled.call_theta_per_day(),
+            option.call_theta_per_day() * 365.0 / 252.0
+        );
+        approx::assert_relative_eq!(
+            scaled.call_theta(),
+            option.call_theta() * 365.0 / 252.0,
+            max_relative = 1e-12
+        );
+        approx::assert_relative_eq!(
+            scaled.greeks(OptionType::Put).rho(),
+            scaled.put_rho(),
+            max_relative = 1e-12
+        );
+    }
+
+    #[test]
+    fn splits_intrinsic_and_extrinsic_values() {
+        let option = create_test_option();
+
+        approx::assert_abs_diff_eq!(option.call_intrinsic(), 0.0, epsilon = f64::EPSILON);
+        approx::assert_abs_diff_eq!(option.put_intrinsic(), 5.0, epsilon = f64::EPSILON);
+        approx::assert_abs_diff_eq!(
+            option.call_extrinsic(),
+            option.call_value(),
+            epsilon = f64::EPSILON
+        );
+        approx::assert_abs_diff_eq!(
+            option.put_extrinsic(),
+            option.put_value() - 5.0,
+            epsilon = f64::EPSILON
+        );
+
+        // a deep in-the-money European put is worth less than exercising it
+        let mut deep = option;
+        deep.set_asset_price(20.0);
+        deep.interest = 0.1;
+        assert!(deep.put_extrinsic() < 0.0);
+    }
+
+    #[test]
+    fn calculates_moneyness() {
+        let mut option = create_test_option();
+        option.set_payout_rate(0.02);
+
+        let forward =
+            100.0 * ((0.005f64.ln_1p() - 0.02f64.ln_1p()) * option.time_to_maturity()).exp();
+        approx::assert_abs_diff_eq!(
+            option.log_moneyness(),
+            (forward / 105.0).ln(),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            option.standardized_moneyness(),
+            (option.d1() + option.d2()) / 2.0,
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn calculates_itm_probabilities() {
+        let option = create_test_option();
+
+        approx::assert_abs_diff_eq!(
+            option.call_itm_probability() + option.put_itm_probability(),
+            1.0,
+            epsilon = f64::EPSILON
+        );
+        approx::assert_abs_diff_eq!(
+            option.call_itm_probability_with_drift(0.005),
+            option.call_itm_probability(),
+            epsilon = 1e-12
+        );
+
+        // a higher expected return makes the call more likely to finish in the money
+        assert!(option.call_itm_probability_with_drift(0.1) > option.call_itm_probability());
+        assert!(option.put_itm_probability_with_drift(0.1) < option.put_itm_probability());
+    }
+
+    #[test]
+    fn calculates_analytic_option_thetas() {
+        let mut option = create_test_option();
+        option.set_payout_rate(0.02);
+        let thetas = op_calc::calculate_analytic_thetas(&option);
+
+        // compare against a central difference over one second
+        let mut earlier = option;
+        earlier.set_time_curr(option.time_curr() - 1);
+        let mut later = option;
+        later.set_time_curr(option.time_curr() + 1);
+        let seconds_per_year = 31_536_000.0;
+
+        let call_theta = (later.call_value() - earlier.call_value()) / 2.0 * seconds_per_year;
+        let put_theta = (later.put_value() - earlier.put_value()) / 2.0 * seconds_per_year;
+        approx::assert_relative_eq!(thetas.call, call_theta, max_relative = 1e-6);
+        approx::assert_relative_eq!(thetas.put, put_theta, max_relative = 1e-6);
+
+        // the per-day theta is close to the one-day value change
+        let one_day_thetas = op_calc::calculate_thetas(&option);
+        approx::assert_abs_diff_eq!(
+            option.call_theta_per_day(),
+            one_day_thetas.call,
+            epsilon = 1e-3
+        );
+        approx::assert_abs_diff_eq!(
+            option.put_theta_per_day(),
+            one_day_thetas.put,
+            epsilon = 1e-3
+        );
+    }
+
+    #[test]
+    fn calculates_values_at_a_future_time() {
+        let option = create_test_option();
+        let future = option.value_at(option.time_curr() + 86_400, Some(101.0), None);
+
+        let mut expected = create_test_option();
+        expected.set_time_curr(option.time_curr() + 86_400);
+        expected.set_asset_price(101.0);
+
+        let future_vals = op_calc::calculate_option_values(&future);
+        let expected_vals = op_calc::calculate_option_values(&expected);
+        approx::assert_abs_diff_eq!(future_vals.call, expected_vals.call, epsilon = f64::EPSILON);
+        approx::assert_abs_diff_eq!(future_vals.put, expected_vals.put, epsilon = f64::EPSILON);
+        approx::assert_abs_diff_eq!(future.volatility(), option.volatility());
+    }
+
+    #[test]
+    fn takes_the_limits_of_at_the_money_options_at_maturity() {
+        let option = create_test_option();
+        let expired = option.value_at(option.time_maturity(), Some(105.0), None);
+
+        approx::assert_abs_diff_eq!(expired.call_value(), 0.0);
+        approx::assert_abs_diff_eq!(expired.put_value(), 0.0);
+        approx::assert_abs_diff_eq!(expired.call_delta(), 0.5);
+        approx::assert_abs_diff_eq!(expired.put_delta(), -0.5);
+        approx::assert_abs_diff_eq!(expired.call_gamma(), 0.0);
+        approx::assert_abs_diff_eq!(expired.call_vega(), 0.0);
+        approx::assert_abs_diff_eq!(expired.call_theta(), 0.0);
+        approx::assert_abs_diff_eq!(expired.call_rho(), 0.0);
+        approx::assert_abs_diff_eq!(expired.veta(), 0.0);
+
+        let greeks = expired.greeks(OptionType::Call);
+        approx::assert_abs_diff_eq!(greeks.value(), 0.0);
+        approx::assert_abs_diff_eq!(greeks.delta(), 0.5);
+        approx::assert_abs_diff_eq!(greeks.gamma(), 0.0);
+    }
+
+    #[test]
+    fn values_options_past_maturity_at_their_intrinsic_value() {
+        let option = create_test_option();
+        let expired = option.value_at(option.time_maturity() + 86_400, Some(110.0), None);
+
+        assert_eq!(expired.time_curr(), option.time_maturity());
+        approx::assert_abs_diff_eq!(expired.time_to_maturity(), 0.0);
+        approx::assert_abs_diff_eq!(expired.call_value(), 5.0, epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(expired.put_value(), 0.0, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn calculates_shifted_option_values() {
+        let mut shifted_option = create_test_option();
+        shifted_option.set_shift(20.0);
+
+        let mut equivalent_option = create_test_option();
+        equivalent_option.set_asset_price(120.0);
+        equivalent_option.set_strike(125.0);
+
+        let shifted_vals = op_calc::calculate_option_values(&shifted_option);
+        let equivalent_vals = op_calc::calculate_option_values(&equivalent_option);
+        approx::assert_abs_diff_eq!(shifted_vals.call, equivalent_vals.call, epsilon = 1e-12);
+        approx::assert_abs_diff_eq!(shifted_vals.put, equivalent_vals.put, epsilon = 1e-12);
+
+        let shifted_deltas = op_calc::calculate_deltas(&shifted_option);
+        let equivalent_deltas = op_calc::calculate_deltas(&equivalent_option);
+        approx::assert_abs_diff_eq!(shifted_deltas.call, equivalent_deltas.call, epsilon = 1e-12);
+    }
+
+    #[test]
+    fn keeps_the_greek_getters_on_black_scholes_under_other_models() {
+        for &method in [GreekMethod::Analytic, GreekMethod::FiniteDifference].iter() {
+            let mut black_scholes = create_test_option();
+            black_scholes.set_greek_method(method);
+            let mut simulated = black_scholes;
+            simulated.set_model(Model::monte_carlo(1000, 7));
+
+            let getters: [fn(&BSOption) -> f64; 8] = [
+                BSOption::call_delta,
+                BSOption::call_gamma,
+                BSOption::call_vega,
+                BSOption::call_theta,
+                BSOption::call_lambda,
+                BSOption::put_vega,
+                BSOption::put_theta,
+                BSOption::put_lambda,
+            ];
+            for getter in getters.iter() {
+                approx::assert_abs_diff_eq!(
+                    getter(&simulated),
+                    getter(&black_scholes),
+                    epsilon = 1e-12
+                );
+            }
+        }
+    }
 }
@@ -0,0 +1,546 @@
+//! Option pricing by solving the Black-Scholes PDE with finite differences.
+//!
+//! The PDE is solved backwards from maturity on a uniform grid of the log
+//! asset price, with Crank-Nicolson time steps: the average of the explicit
+//! and implicit schemes, which is accurate to second order in both the price
+//! and time steps. The first step is replaced by two implicit half steps
+//! (Rannacher, 1984), which damp the oscillations the kink of the payoff
+//! would otherwise cause.
+//!
+//! Unlike trees, the grid handles several features at once:
+//!
+//! - American exercise, by projecting the values onto the exercise value
+//!   after each time step.
+//! - Discrete dividends, by shifting the values along the price axis at the
+//!   step of each ex-dividend date, where the asset price drops.
+//! - Knock-out barriers, by placing the grid's edges on them. The option is
+//!   worth nothing there.
+
+use crate::option::american::LatticeGreeks;
+use crate::option::dividends::DividendOption;
+use crate::option::greeks::Greeks;
+use crate::option::{BSOption, OptionType};
+use wasm_bindgen::prelude::*;
+
+/// The number of standard deviations of the log asset price at maturity
+/// covered by the grid beyond the asset price and the strike.
+const GRID_WIDTH: f64 = 5.0;
+
+/// A Crank-Nicolson finite-difference pricer, for European or American
+/// options, optionally with knock-out barriers.
+///
+/// ```rust
+/// use opcalc::option::pde::CrankNicolson;
+/// use opcalc::option::BSOption;
+///
+/// let option = BSOption::new(1_606_780_800, 1_610_668_800, 100.0, 105.0, 0.08, 0.23, 0.0);
+///
+/// let pde = CrankNicolson::new().with_american(true);
+/// assert!(pde.put_value(&option) > option.put_value());
+///
+/// // an American up-and-out put, knocked out at 110
+/// let knock_out = pde.with_upper_barrier(110.0);
+/// assert!(knock_out.put_value(&option) < pde.put_value(&option));
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct CrankNicolson {
+    price_steps: usize,
+    time_steps: usize,
+    american: bool,
+    lower_barrier: f64,
+    upper_barrier: f64,
+}
+
+impl Default for CrankNicolson {
+    fn default() -> CrankNicolson {
+        CrankNicolson {
+            price_steps: 200,
+            time_steps: 200,
+            american: false,
+            lower_barrier: 0.0,
+            upper_barrier: f64::INFINITY,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl CrankNicolson {
+    /// Create a `CrankNicolson` pricer for European options, on a grid of 200
+    /// price steps and 200 time steps, without barriers.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> CrankNicolson {
+        Default::default()
+    }
+
+    /// Set the number of steps of the log asset price grid. At least three
+    /// steps are always used.
+    pub fn with_price_steps(self, price_steps: usize) -> CrankNicolson {
+        CrankNicolson {
+            price_steps: price_steps.max(3),
+            ..self
+        }
+    }
+
+    /// Set the number of time steps. At least one step is always used.
+    pub fn with_time_steps(self, time_steps: usize) -> CrankNicolson {
+        CrankNicolson {
+            time_steps: time_steps.max(1),
+            ..self
+        }
+    }
+
+    /// Set whether the options can be exercised early.
+    pub fn with_american(self, american: bool) -> CrankNicolson {
+        CrankNicolson { american, ..self }
+    }
+
+    /// Set a barrier below the asset price, at which the options are
+    /// knocked out without a rebate. `0` removes the barrier.
+    pub fn with_lower_barrier(self, lower_barrier: f64) -> CrankNicolson {
+        CrankNicolson {
+            lower_barrier,
+            ..self
+        }
+    }
+
+    /// Set a barrier above the asset price, at which the options are
+    /// knocked out without a rebate. Infinity removes the barrier.
+    pub fn with_upper_barrier(self, upper_barrier: f64) -> CrankNicolson {
+        CrankNicolson {
+            upper_barrier,
+            ..self
+        }
+    }
+
+    /// Get the number of steps of the log asset price grid.
+    pub fn price_steps(&self) -> usize {
+        self.price_steps
+    }
+
+    /// Get the number of time steps.
+    pub fn time_steps(&self) -> usize {
+        self.time_steps
+    }
+
+    /// Get whether the options can be exercised early.
+    pub fn american(&self) -> bool {
+        self.american
+    }
+
+    /// Get the lower knock-out barrier, `0` if there is none.
+    pub fn lower_barrier(&self) -> f64 {
+        self.lower_barrier
+    }
+
+    /// Get the upper knock-out barrier, infinity if there is none.
+    pub fn upper_barrier(&self) -> f64 {
+        self.upper_barrier
+    }
+
+    /// Get the value of a call on `option`.
+    pub fn call_value(&self, option: &BSOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("CrankNicolson::call_value");
+
+        self.solve(option, OptionType::Call, &[]).value
+    }
+
+    /// Get the value of a put on `option`.
+    pub fn put_value(&self, option: &BSOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("CrankNicolson::put_value");
+
+        self.solve(option, OptionType::Put, &[]).value
+    }
+
+    /// Get the value of a call on an asset paying discrete dividends. Unlike
+    /// the escrowed dividend model of `DividendOption`, the whole asset price
+    /// follows a geometric Brownian motion between ex-dividend dates. Each
+    /// ex-dividend date is moved to the nearest time step.
+    pub fn dividend_call_value(&self, option: &DividendOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("CrankNicolson::dividend_call_value");
+
+        let dividends: Vec<(f64, f64)> = option.dividend_years().collect();
+        self.solve(&option.option(), OptionType::Call, &dividends)
+            .value
+    }
+
+    /// Get the value of a put on an asset paying discrete dividends. See
+    /// `dividend_call_value()`.
+    pub fn dividend_put_value(&self, option: &DividendOption) -> f64 {
+        crate::utils::set_panic_hook();
+        trace_scope!("CrankNicolson::dividend_put_value");
+
+        let dividends: Vec<(f64, f64)> = option.dividend_years().collect();
+        self.solve(&option.option(), OptionType::Put, &dividends)
+            .value
+    }
+
+    /// Get the value and greeks of a call on `option`. Delta, gamma and theta
+    /// come from the grid, and the other greeks from solving it again with
+    /// the option's `NumericalConfig`.
+    pub fn call_greeks(&self, option: &BSOption) -> Greeks {
+        crate::utils::set_panic_hook();
+        trace_scope!("CrankNicolson::call_greeks");
+
+        self.grid_greeks(option, OptionType::Call)
+    }
+
+    /// Get the value and greeks of a put on `option`. See `call_greeks()`.
+    pub fn put_greeks(&self, option: &BSOption) -> Greeks {
+        crate::utils::set_panic_hook();
+        trace_scope!("CrankNicolson::put_greeks");
+
+        self.grid_greeks(option, OptionType::Put)
+    }
+}
+
+impl CrankNicolson {
+    pub(crate) fn grid_greeks(&self, option: &BSOption, option_type: OptionType) -> Greeks {
+        Greeks::from_lattice(option, |option| self.solve(option, option_type, &[]))
+    }
+
+    /// Solve the PDE for the option, whose asset pays the `dividends`, as
+    /// pairs of ex-dividend dates in years from now and amounts.
+    fn solve(
+        &self,
+        option: &BSOption,
+        option_type: OptionType,
+        dividends: &[(f64, f64)],
+    ) -> LatticeGreeks {
+        let asset_price = option.shifted_asset_price();
+        let strike = option.shifted_strike();
+        let payoff = |price: f64| match option_type {
+            OptionType::Call => (price - strike).max(0.0),
+            OptionType::Put => (strike - price).max(0.0),
+        };
+
+        let lower_barrier = if self.lower_barrier > 0.0 {
+            self.lower_barrier + option.shift
+        } else {
+            0.0
+        };
+        let upper_barrier = self.upper_barrier + option.shift;
+        if asset_price <= lower_barrier || asset_price >= upper_barrier {
+            return LatticeGreeks {
+                value: 0.0,
+                delta: 0.0,
+                gamma: 0.0,
+                theta: 0.0,
+            };
+        }
+
+        let time = option.time_to_maturity;
+        if time <= 0.0 {
+            return LatticeGreeks::without_nodes(payoff(asset_price));
+        }
+
+        let rate = option.r_continuous();
+        let payout_rate = option.div_continuous();
+        let variance = option.volatility.powi(2);
+
+        // the grid's edges, on the barriers if they are within reach
+        let spread = GRID_WIDTH * option.volatility * time.sqrt();
+        let mut lowest = asset_price.ln().min(strike.ln()) - spread;
+        let mut highest = asset_price.ln().max(strike.ln()) + spread;
+        let knocks_out_below = lower_barrier.ln() > lowest;
+        let knocks_out_above = upper_barrier.ln() < highest;
+        if knocks_out_below {
+            lowest = lower_barrier.ln();
+        }
+        if knocks_out_above {
+            highest = upper_barrier.ln();
+        }
+
+        let price_steps = self.price_steps;
+        let dx = (highest - lowest) / price_steps as f64;
+        let prices: Vec<f64> = (0..=price_steps)
+            .map(|i| (lowest + i as f64 * dx).exp())
+            .collect();
+
+        // the values at the edges, with `tau` years to maturity
+        let edge_value = |price: f64, tau: f64, knocks_out: bool| {
+            if knocks_out {
+                return 0.0;
+            }
+
+            let forward_value = match option_type {
+                OptionType::Call => {
+                    price * (-payout_rate * tau).exp() - strike * (-rate * tau).exp()
+                }
+                OptionType::Put => {
+                    strike * (-rate * tau).exp() - price * (-payout_rate * tau).exp()
+                }
+            };
+            if self.american {
+                forward_value.max(payoff(price))
+            } else {
+                forward_value.max(0.0)
+            }
+        };
+        let exercise = |values: &mut [f64]| {
+            if self.american {
+                for (value, &price) in values.iter_mut().zip(&prices).skip(1).take(price_steps - 1)
+                {
+                    *value = value.max(payoff(price));
+                }
+            }
+        };
+
+        // the PDE's operator, for the value at a node and its neighbours
+        let diffusion = variance / (2.0 * dx.powi(2));
+        let drift = (rate - payout_rate - variance / 2.0) / (2.0 * dx);
+        let operator = (
+            diffusion - drift,
+            -2.0 * diffusion - rate,
+            diffusion + drift,
+        );
+
+        // the steps after which each dividend is paid, going back in time
+        let time_steps = self.time_steps;
+        let dt = time / time_steps as f64;
+        let dividend_steps: Vec<(usize, f64)> = dividends
+            .iter()
+            .map(|&(years, amount)| {
+                let step = (((time - years) / dt).round() as usize).min(time_steps);
+                (step, amount)
+            })
+            .collect();
+        let pay_dividends = |values: &mut Vec<f64>, step: usize| {
+            for &(_, amount) in dividend_steps.iter().filter(|&&(at, _)| at == step) {
+                // right before the ex-dividend date, the option is worth its
+                // value after the asset price drops
+                *values = prices
+                    .iter()
+                    .map(|&price| interpolate(&prices, values, price - amount))
+                    .collect();
+            }
+        };
+
+        let mut values: Vec<f64> = prices.iter().map(|&price| payoff(price)).collect();
+        if knocks_out_below {
+            values[0] = 0.0;
+        }
+        if knocks_out_above {
+            values[price_steps] = 0.0;
+        }
+        pay_dividends(&mut values, 0);
+
+        let mut previous = values.clone();
+        for step in 1..=time_steps {
+            previous.clone_from(&values);
+            let tau = step as f64 * dt;
+            let edges = (
+                edge_value(prices[0], tau, knocks_out_below),
+                edge_value(prices[price_steps], tau, knocks_out_above),
+            );
+
+            if step == 1 {
+                // Rannacher start: two implicit half steps
+                let half_edges = (
+                    edge_value(prices[0], tau / 2.0, knocks_out_below),
+                    edge_value(prices[price_steps], tau / 2.0, knocks_out_above),
+                );
+                values = time_step(&values, operator, 1.0, dt / 2.0, half_edges);
+                exercise(&mut values);
+                values = time_step(&values, operator, 1.0, dt / 2.0, edges);
+            } else {
+                values = time_step(&values, operator, 0.5, dt, edges);
+            }
+            exercise(&mut values);
+            pay_dividends(&mut values, step);
+        }
+
+        // quadratic interpolation around the asset price
+        let position = (asset_price.ln() - lowest) / dx;
+        let node = (position.round() as usize).max(1).min(price_steps - 1);
+        let offset = position - node as f64;
+        let at_asset_price = |values: &[f64]| {
+            let slope = (values[node + 1] - values[node - 1]) / 2.0;
+            let curvature = values[node + 1] - 2.0 * values[node] + values[node - 1];
+            (
+                values[node] + offset * slope + offset.powi(2) / 2.0 * curvature,
+                (slope + offset * curvature) / dx,
+                curvature / dx.powi(2),
+            )
+        };
+
+        let (value, value_dx, value_dxx) = at_asset_price(&values);
+        let (earlier_value, _, _) = at_asset_price(&previous);
+
+        LatticeGreeks {
+            value,
+            delta: value_dx / asset_price,
+            gamma: (value_dxx - value_dx) / asset_price.powi(2),
+            theta: (earlier_value - value) / dt,
+        }
+    }
+}
+
+/// Take a time step of `dt` years back from `values`, with the
+/// theta-scheme: `1` is fully implicit, and `0.5` is Crank-Nicolson. The
+/// `operator` holds the coefficients of the lower, middle and upper nodes,
+/// and `edges` the values at the grid's edges after the step.
+fn time_step(
+    values: &[f64],
+    operator: (f64, f64, f64),
+    theta: f64,
+    dt: f64,
+    edges: (f64, f64),
+) -> Vec<f64> {
+    let (lower, middle, upper) = operator;
+    let nodes = values.len();
+    let explicit = (1.0 - theta) * dt;
+
+    // the tridiagonal system of the interior nodes
+    let sub = -theta * dt * lower;
+    let diagonal = 1.0 - theta * dt * middle;
+    let sup = -theta * dt * upper;
+    let mut rhs: Vec<f64> = (1..nodes - 1)
+        .map(|i| {
+            values[i]
+                + explicit * (lower * values[i - 1] + middle * values[i] + upper * values[i + 1])
+        })
+        .collect();
+    let interior = rhs.len();
+    rhs[0] -= sub * edges.0;
+    rhs[interior - 1] -= sup * edges.1;
+
+    // Thomas algorithm
+    let mut factors = vec![0.0; interior];
+    factors[0] = sup / diagonal;
+    rhs[0] /= diagonal;
+    for i in 1..interior {
+        let pivot = diagonal - sub * factors[i - 1];
+        factors[i] = sup / pivot;
+        rhs[i] = (rhs[i] - sub * rhs[i - 1]) / pivot;
+    }
+    for i in (0..interior - 1).rev() {
+        rhs[i] -= factors[i] * rhs[i + 1];
+    }
+
+    let mut stepped = Vec::with_capacity(nodes);
+    stepped.push(edges.0);
+    stepped.extend(rhs);
+    stepped.push(edges.1);
+    stepped
+}
+
+/// Linearly interpolate `values` on the increasing `prices` at `price`,
+/// holding the edge values beyond the grid.
+fn interpolate(prices: &[f64], values: &[f64], price: f64) -> f64 {
+    let last = prices.len() - 1;
+    if price <= prices[0] {
+        return values[0];
+    }
+    if price >= prices[last] {
+        return values[last];
+    }
+
+    let above = prices.iter().position(|&node| node > price).unwrap_or(last);
+    let weight = (price - prices[above - 1]) / (prices[above] - prices[above - 1]);
+    values[above - 1] + weight * (values[above] - values[above - 1])
+}
+
+#[cfg(test)]
+mod pde_tests {
+    use crate::exotics::barrier::{BarrierKind, BarrierOption};
+    use crate::option::american::BinomialTree;
+    use crate::option::dividends::DividendOption;
+    use crate::option::numerical::{DifferenceScheme, NumericalConfig};
+    use crate::option::pde::CrankNicolson;
+    use crate::option::{BSOption, OptionType};
+
+    fn create_test_option() -> BSOption {
+        BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.02)
+    }
+
+    #[test]
+    fn values_european_options_like_black_scholes() {
+        let option = create_test_option();
+        let pde = CrankNicolson::new();
+
+        approx::assert_abs_diff_eq!(pde.call_value(&option), option.call_value(), epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(pde.put_value(&option), option.put_value(), epsilon = 1e-3);
+
+        let mut option = option;
+        option.set_numerical_config(
+            NumericalConfig::new()
+                .with_volatility_bump(0.001)
+                .with_difference_scheme(DifferenceScheme::Central),
+        );
+        let grid = pde.call_greeks(&option);
+        let analytic = option.greeks(OptionType::Call);
+        approx::assert_abs_diff_eq!(grid.delta(), analytic.delta(), epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(grid.gamma(), analytic.gamma(), epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(grid.theta(), analytic.theta(), epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(grid.vega(), analytic.vega(), epsilon = 1e-3);
+        approx::assert_abs_diff_eq!(grid.rho(), analytic.rho(), epsilon = 1e-3);
+    }
+
+    #[test]
+    fn values_american_options_like_trees() {
+        let mut option = create_test_option();
+        option.set_interest(0.08);
+        option.set_asset_price(95.0);
+
+        let pde = CrankNicolson::new().with_american(true);
+        let tree = BinomialTree::new().with_steps(2000).with_smoothing(true);
+        approx::assert_abs_diff_eq!(
+            pde.put_value(&option),
+            tree.put_value(&option),
+            epsilon = 5e-3
+        );
+        approx::assert_abs_diff_eq!(
+            pde.call_value(&option),
+            tree.call_value(&option),
+            epsilon = 5e-3
+        );
+    }
+
+    #[test]
+    fn knocks_out_at_barriers() {
+        let option = create_test_option();
+        let pde = CrankNicolson::new().with_time_steps(400);
+
+        let down_and_out = BarrierOption::new(&option, BarrierKind::DownAndOut, 90.0);
+        approx::assert_abs_diff_eq!(
+            pde.with_lower_barrier(90.0).call_value(&option),
+            down_and_out.call_value(),
+            epsilon = 5e-3
+        );
+        let up_and_out = BarrierOption::new(&option, BarrierKind::UpAndOut, 110.0);
+        approx::assert_abs_diff_eq!(
+            pde.with_upper_barrier(110.0).put_value(&option),
+            up_and_out.put_value(),
+            epsilon = 5e-3
+        );
+
+        // beyond the barrier, the option is already knocked out
+        approx::assert_abs_diff_eq!(pde.with_lower_barrier(101.0).put_value(&option), 0.0);
+    }
+
+    #[test]
+    fn drops_the_asset_price_on_ex_dividend_dates() {
+        let mut option = create_test_option();
+        option.set_payout_rate(0.0);
+        let dividend_option = DividendOption::new(&option, vec![1608422400], vec![2.0]);
+
+        // the whole asset price diffuses before the ex-dividend date, so the
+        // value lies between the escrowed dividend model's and that model's
+        // with the volatility scaled up to the whole asset price
+        let pde = CrankNicolson::new();
+        let mut scaled = option;
+        scaled.set_volatility(0.23 * 100.0 / (100.0 - dividend_option.dividends_present_value()));
+        let scaled_option = DividendOption::new(&scaled, vec![1608422400], vec![2.0]);
+        let call = pde.dividend_call_value(&dividend_option);
+        assert!(call > dividend_option.call_value());
+        assert!(call < scaled_option.call_value());
+        let american_put = pde.with_american(true).dividend_put_value(&dividend_option);
+        assert!(american_put > dividend_option.american_put_value());
+        assert!(american_put < scaled_option.american_put_value());
+        assert!(pde.dividend_put_value(&dividend_option) > pde.put_value(&option));
+    }
+}
@@ -0,0 +1,144 @@
+//! Greeks of option positions.
+
+use crate::option::greeks::Greeks;
+use wasm_bindgen::prelude::*;
+
+/// An enumeration of the sides of a position.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum PositionSide {
+    /// The option was bought.
+    Long,
+    /// The option was sold (written).
+    Short,
+}
+
+/// The value and greeks of a position in a call or put, signed by the
+/// position's side and scaled by its size.
+///
+/// Each figure is the single-option figure multiplied by
+/// `±quantity * multiplier`, with a negative sign for short positions.
+/// Figures of several positions can therefore be added up directly.
+///
+/// See `BSOption::position_greeks()`.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct PositionGreeks {
+    side: PositionSide,
+    quantity: f64,
+    multiplier: f64,
+    greeks: Greeks,
+}
+
+#[wasm_bindgen]
+impl PositionGreeks {
+    /// Get the position's side.
+    pub fn side(&self) -> PositionSide {
+        self.side
+    }
+
+    /// Get the number of contracts held.
+    pub fn quantity(&self) -> f64 {
+        self.quantity
+    }
+
+    /// Get the number of units of the asset per contract.
+    pub fn multiplier(&self) -> f64 {
+        self.multiplier
+    }
+
+    /// Get the position's value.
+    pub fn value(&self) -> f64 {
+        self.greeks.value()
+    }
+
+    /// Get the position's delta, in units of the asset.
+    pub fn delta(&self) -> f64 {
+        self.greeks.delta()
+    }
+
+    /// Get the position's gamma.
+    pub fn gamma(&self) -> f64 {
+        self.greeks.gamma()
+    }
+
+    /// Get the position's vega.
+    pub fn vega(&self) -> f64 {
+        self.greeks.vega()
+    }
+
+    /// Get the position's continuous-time theta, per day.
+    pub fn theta(&self) -> f64 {
+        self.greeks.theta()
+    }
+
+    /// Get the position's rho.
+    pub fn rho(&self) -> f64 {
+        self.greeks.rho()
+    }
+
+    /// Get the position's epsilon.
+    pub fn epsilon(&self) -> f64 {
+        self.greeks.epsilon()
+    }
+
+    /// Get the position's vanna.
+    pub fn vanna(&self) -> f64 {
+        self.greeks.vanna()
+    }
+
+    /// Get the position's volga.
+    pub fn volga(&self) -> f64 {
+        self.greeks.volga()
+    }
+
+    /// Get the position's veta.
+    pub fn veta(&self) -> f64 {
+        self.greeks.veta()
+    }
+}
+
+impl PositionGreeks {
+    pub(crate) fn new(
+        greeks: Greeks,
+        side: PositionSide,
+        quantity: f64,
+        multiplier: f64,
+    ) -> PositionGreeks {
+        let sign = match side {
+            PositionSide::Long => 1.0,
+            PositionSide::Short => -1.0,
+        };
+
+        PositionGreeks {
+            side,
+            quantity,
+            multiplier,
+            greeks: greeks.scaled(sign * quantity * multiplier),
+        }
+    }
+}
+
+#[cfg(test)]
+mod position_tests {
+    use crate::option::position::PositionSide;
+    use crate::option::{BSOption, OptionType};
+
+    #[test]
+    fn signs_and_scales_greeks() {
+        let option = BSOption::new(1606780800, 1610668800, 100.0, 105.0, 0.005, 0.23, 0.0);
+
+        let long = option.position_greeks(OptionType::Call, PositionSide::Long, 3.0, 100.0);
+        let short = option.position_greeks(OptionType::Call, PositionSide::Short, 3.0, 100.0);
+
+        approx::assert_abs_diff_eq!(long.value(), 300.0 * option.call_value(), epsilon = 1e-10);
+        approx::assert_abs_diff_eq!(long.delta(), 300.0 * option.call_delta(), epsilon = 1e-10);
+        approx::assert_abs_diff_eq!(short.gamma(), -300.0 * option.call_gamma(), epsilon = 1e-10);
+        approx::assert_abs_diff_eq!(
+            short.theta(),
+            -300.0 * option.call_theta_per_day(),
+            epsilon = 1e-10
+        );
+        approx::assert_abs_diff_eq!(long.vega() + short.vega(), 0.0, epsilon = f64::EPSILON);
+    }
+}
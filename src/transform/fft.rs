@@ -0,0 +1,76 @@
+//! A minimal radix-2 fast Fourier transform.
+
+use num_complex::Complex64;
+use std::f64::consts::PI;
+
+/// Computes the forward discrete Fourier transform of `buffer` in place,
+/// i.e. `X_u = sum_j x_j * e^(-2 pi i j u / N)`.
+///
+/// The buffer's length must be a power of two.
+pub fn fft(buffer: &mut [Complex64]) {
+    let n = buffer.len();
+    debug_assert!(n.is_power_of_two());
+
+    // reorder the input in bit-reversed index order
+    let mut j = 0;
+    for i in 1..n {
+        let mut bit = n >> 1;
+        while j & bit != 0 {
+            j ^= bit;
+            bit >>= 1;
+        }
+        j |= bit;
+
+        if i < j {
+            buffer.swap(i, j);
+        }
+    }
+
+    // combine transforms of increasing length
+    let mut len = 2;
+    while len <= n {
+        let angle = -2.0 * PI / len as f64;
+        let root = Complex64::new(angle.cos(), angle.sin());
+
+        for start in (0..n).step_by(len) {
+            let mut twiddle = Complex64::new(1.0, 0.0);
+            for k in 0..len / 2 {
+                let even = buffer[start + k];
+                let odd = buffer[start + k + len / 2] * twiddle;
+                buffer[start + k] = even + odd;
+                buffer[start + k + len / 2] = even - odd;
+                twiddle *= root;
+            }
+        }
+
+        len <<= 1;
+    }
+}
+
+#[cfg(test)]
+mod fft_tests {
+    use crate::transform::fft::fft;
+    use num_complex::Complex64;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn matches_naive_dft() {
+        let input: Vec<Complex64> = (0..16)
+            .map(|i| Complex64::new((i as f64).sin(), (i as f64 * 0.3).cos()))
+            .collect();
+
+        let mut output = input.clone();
+        fft(&mut output);
+
+        for (u, value) in output.iter().enumerate() {
+            let expected: Complex64 = input
+                .iter()
+                .enumerate()
+                .map(|(j, x)| x * Complex64::from_polar(1.0, -2.0 * PI * (j * u) as f64 / 16.0))
+                .sum();
+
+            approx::assert_abs_diff_eq!(value.re, expected.re, epsilon = 1e-12);
+            approx::assert_abs_diff_eq!(value.im, expected.im, epsilon = 1e-12);
+        }
+    }
+}
@@ -1,12 +1,15 @@
+use statrs::distribution::{Normal, Univariate};
+use statrs::function::erf::erf_inv;
+use wasm_bindgen::prelude::*;
 use web_sys::console;
 
-/// A macro to provide `println!(..)`-style syntax for `console.log` logging.
-#[allow(unused_macros)]
-#[macro_export]
-macro_rules! log {
-    ( $( $t:tt )* ) => {
-        web_sys::console::log_1(&format!( $( $t )* ).into());
-    }
+/// Enters a `tracing` span that lasts until the end of the enclosing scope.
+/// Expands to nothing unless the `tracing` feature is enabled.
+macro_rules! trace_scope {
+    ( $name:expr ) => {
+        #[cfg(feature = "tracing")]
+        let _span = tracing::trace_span!($name).entered();
+    };
 }
 
 #[allow(dead_code)]
@@ -21,6 +24,23 @@ pub fn set_panic_hook() {
     console_error_panic_hook::set_once();
 }
 
+/// The standard normal cumulative distribution function.
+#[wasm_bindgen]
+pub fn normal_cdf(x: f64) -> f64 {
+    Normal::new(0.0, 1.0).unwrap().cdf(x)
+}
+
+/// The inverse of the standard normal cumulative distribution function.
+pub fn normal_inverse_cdf(probability: f64) -> f64 {
+    std::f64::consts::SQRT_2 * erf_inv(2.0 * probability - 1.0)
+}
+
+/// The standard normal probability density function.
+#[wasm_bindgen]
+pub fn normal_pdf(x: f64) -> f64 {
+    (-x * x / 2.0).exp() / (2.0 * std::f64::consts::PI).sqrt()
+}
+
 pub struct Timer<'a> {
     name: &'a str,
 }
@@ -0,0 +1,215 @@
+//! Conversions between lognormal, shifted lognormal and normal volatilities.
+//!
+//! A volatility is converted by pricing an option under the source model and
+//! finding the volatility that reproduces that price under the target model.
+//! Prices are compared undiscounted, on a forward basis, so conversions do
+//! not depend on interest rates. The out-of-the-money side (call above the
+//! forward, put below it) is used, which keeps the inversion well
+//! conditioned.
+//!
+//! A plain lognormal (Black-Scholes) volatility is a shifted lognormal
+//! volatility with a shift of `0.0`.
+
+use crate::utils::{normal_cdf, normal_pdf};
+use wasm_bindgen::prelude::*;
+
+/// Convert a shifted lognormal volatility from one shift to another.
+///
+/// Returns `None` if the forward or strike is not positive once shifted, or
+/// if no volatility reproduces the price under the target shift.
+///
+/// # Arguments
+///
+/// - `forward`: the forward price of the underlying.
+///
+/// - `strike`: the option's strike price.
+///
+/// - `time_to_maturity`: the time to maturity as a fraction of 365 days.
+///
+/// - `volatility`: the shifted lognormal volatility under `from_shift`.
+///
+/// - `from_shift`, `to_shift`: the source and target shifts. Use `0.0` for a
+///      plain lognormal volatility.
+#[wasm_bindgen]
+pub fn convert_shifted_lognormal_vol(
+    forward: f64,
+    strike: f64,
+    time_to_maturity: f64,
+    volatility: f64,
+    from_shift: f64,
+    to_shift: f64,
+) -> Option<f64> {
+    let price = shifted_lognormal_price(forward, strike, time_to_maturity, volatility, from_shift)?;
+
+    invert(price, |vol| {
+        shifted_lognormal_price(forward, strike, time_to_maturity, vol, to_shift)
+    })
+}
+
+/// Convert a shifted lognormal volatility to a normal (Bachelier)
+/// volatility, quoted in the asset price's units per square-root year.
+///
+/// Returns `None` if the forward or strike is not positive once shifted.
+///
+/// See `convert_shifted_lognormal_vol()` for a description of the arguments.
+#[wasm_bindgen]
+pub fn shifted_lognormal_to_normal_vol(
+    forward: f64,
+    strike: f64,
+    time_to_maturity: f64,
+    volatility: f64,
+    shift: f64,
+) -> Option<f64> {
+    let price = shifted_lognormal_price(forward, strike, time_to_maturity, volatility, shift)?;
+
+    invert(price, |vol| {
+        Some(normal_price(forward, strike, time_to_maturity, vol))
+    })
+}
+
+/// Convert a normal (Bachelier) volatility to a shifted lognormal
+/// volatility.
+///
+/// Returns `None` if the forward or strike is not positive once shifted, or
+/// if the normal price is too high to be reproduced by a shifted lognormal
+/// model.
+///
+/// See `convert_shifted_lognormal_vol()` for a description of the arguments.
+#[wasm_bindgen]
+pub fn normal_to_shifted_lognormal_vol(
+    forward: f64,
+    strike: f64,
+    time_to_maturity: f64,
+    normal_volatility: f64,
+    shift: f64,
+) -> Option<f64> {
+    let price = normal_price(forward, strike, time_to_maturity, normal_volatility);
+
+    invert(price, |vol| {
+        shifted_lognormal_price(forward, strike, time_to_maturity, vol, shift)
+    })
+}
+
+/// Undiscounted out-of-the-money option price under a shifted lognormal
+/// (Black) model.
+fn shifted_lognormal_price(
+    forward: f64,
+    strike: f64,
+    time_to_maturity: f64,
+    volatility: f64,
+    shift: f64,
+) -> Option<f64> {
+    let forward = forward + shift;
+    let strike = strike + shift;
+    if forward <= 0.0 || strike <= 0.0 {
+        return None;
+    }
+
+    let std_dev = volatility * time_to_maturity.sqrt();
+    if std_dev <= 0.0 {
+        return Some(0.0);
+    }
+
+    let d1 = ((forward / strike).ln() + std_dev * std_dev / 2.0) / std_dev;
+    let d2 = d1 - std_dev;
+
+    if strike >= forward {
+        Some(forward * normal_cdf(d1) - strike * normal_cdf(d2))
+    } else {
+        Some(strike * normal_cdf(-d2) - forward * normal_cdf(-d1))
+    }
+}
+
+/// Undiscounted out-of-the-money option price under a normal (Bachelier)
+/// model.
+fn normal_price(forward: f64, strike: f64, time_to_maturity: f64, volatility: f64) -> f64 {
+    let std_dev = volatility * time_to_maturity.sqrt();
+    if std_dev <= 0.0 {
+        return 0.0;
+    }
+
+    // the out-of-the-money side has a negative moneyness for both calls and puts
+    let moneyness = -(forward - strike).abs();
+    let d = moneyness / std_dev;
+
+    moneyness * normal_cdf(d) + std_dev * normal_pdf(d)
+}
+
+/// Finds the volatility at which `price_at` reaches `target_price`, given
+/// that prices increase with volatility.
+fn invert<F: Fn(f64) -> Option<f64>>(target_price: f64, price_at: F) -> Option<f64> {
+    const MAX_BRACKET_STEPS: usize = 64;
+    const MAX_BISECTION_STEPS: usize = 200;
+
+    let mut low = 0.0;
+    let mut high = 1.0;
+    let mut steps = 0;
+    while price_at(high)? < target_price {
+        low = high;
+        high *= 2.0;
+        steps += 1;
+        if steps > MAX_BRACKET_STEPS {
+            return None;
+        }
+    }
+
+    for _ in 0..MAX_BISECTION_STEPS {
+        let mid = (low + high) / 2.0;
+        if mid <= low || mid >= high {
+            break;
+        }
+
+        if price_at(mid)? < target_price {
+            low = mid;
+        } else {
+            high = mid;
+        }
+    }
+
+    Some((low + high) / 2.0)
+}
+
+#[cfg(test)]
+mod conversion_tests {
+    use crate::volatility::conversion::{
+        convert_shifted_lognormal_vol, normal_to_shifted_lognormal_vol,
+        shifted_lognormal_to_normal_vol,
+    };
+
+    #[test]
+    fn converts_lognormal_to_normal_vol() {
+        // at the money, the normal vol is close to forward * lognormal vol
+        let normal_vol = shifted_lognormal_to_normal_vol(100.0, 100.0, 1.0, 0.2, 0.0).unwrap();
+        approx::assert_abs_diff_eq!(normal_vol, 20.0, epsilon = 0.05);
+
+        let vol = normal_to_shifted_lognormal_vol(100.0, 100.0, 1.0, normal_vol, 0.0).unwrap();
+        approx::assert_abs_diff_eq!(vol, 0.2, epsilon = 1e-10);
+    }
+
+    #[test]
+    fn round_trips_between_shifts() {
+        for &strike in &[0.5, 1.0, 2.0, 4.0] {
+            let shifted_vol =
+                convert_shifted_lognormal_vol(1.5, strike, 2.0, 0.4, 0.0, 3.0).unwrap();
+            assert!(shifted_vol < 0.4);
+
+            let vol =
+                convert_shifted_lognormal_vol(1.5, strike, 2.0, shifted_vol, 3.0, 0.0).unwrap();
+            approx::assert_abs_diff_eq!(vol, 0.4, epsilon = 1e-10);
+
+            let normal_vol =
+                shifted_lognormal_to_normal_vol(1.5, strike, 2.0, shifted_vol, 3.0).unwrap();
+            let vol = normal_to_shifted_lognormal_vol(1.5, strike, 2.0, normal_vol, 0.0).unwrap();
+            approx::assert_abs_diff_eq!(vol, 0.4, epsilon = 1e-10);
+        }
+    }
+
+    #[test]
+    fn rejects_negative_shifted_prices() {
+        assert_eq!(
+            shifted_lognormal_to_normal_vol(-0.1, 0.5, 1.0, 0.2, 0.0),
+            None
+        );
+        assert!(shifted_lognormal_to_normal_vol(-0.1, 0.5, 1.0, 0.2, 1.0).is_some());
+    }
+}
@@ -0,0 +1,327 @@
+//! GARCH(1,1) volatility fitting and forecasting.
+//!
+//! The variance of each period's return follows Bollerslev's (1986)
+//!
+//! ```text
+//! var(t) = omega + alpha * r(t - 1)^2 + beta * var(t - 1)
+//! ```
+//!
+//! so volatility clusters after large moves, and reverts to the long-run
+//! variance `omega / (1 - alpha - beta)`. Forecasts give a model-based
+//! `volatility` input for `BSOption`, matched to the option's maturity.
+//!
+//! Returns are assumed to have a zero mean, which is standard for daily
+//! returns. The parameters maximize the Gaussian likelihood, with the
+//! Nelder-Mead simplex method.
+
+use wasm_bindgen::prelude::*;
+
+/// The fewest returns a model can be fitted to.
+const MIN_RETURNS: usize = 10;
+const MAX_ITERATIONS: usize = 5000;
+/// The spread of the log-likelihoods over the simplex at which the fit
+/// stops.
+const LIKELIHOOD_TOLERANCE: f64 = 1e-10;
+
+/// A GARCH(1,1) model fitted to a return series.
+///
+/// ```rust
+/// use opcalc::volatility::garch::Garch;
+///
+/// let returns = [
+///     0.004, -0.012, 0.007, 0.021, -0.018, 0.003, -0.001, 0.009, -0.025, 0.016, 0.002, -0.006,
+/// ];
+/// let model = Garch::fit(&returns).unwrap();
+///
+/// // the annualized volatility over the next 30 trading days
+/// let volatility = model.forecast_volatility(30, 252.0);
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug)]
+pub struct Garch {
+    omega: f64,
+    alpha: f64,
+    beta: f64,
+    next_variance: f64,
+    log_likelihood: f64,
+}
+
+#[wasm_bindgen]
+impl Garch {
+    /// Fit a GARCH(1,1) model to `returns` by maximum likelihood.
+    ///
+    /// Returns `None` if there are fewer than 10 returns, if any is not
+    /// finite, or if they are all zero.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `returns`: the log returns of each period, ordered from oldest to
+    ///      newest.
+    pub fn fit(returns: &[f64]) -> Option<Garch> {
+        crate::utils::set_panic_hook();
+        trace_scope!("Garch::fit");
+
+        if returns.len() < MIN_RETURNS || returns.iter().any(|r| !r.is_finite()) {
+            return None;
+        }
+        let sample_variance = returns.iter().map(|r| r * r).sum::<f64>() / returns.len() as f64;
+        if sample_variance <= 0.0 {
+            return None;
+        }
+
+        // optimize ln(omega), which is many orders of magnitude below alpha
+        // and beta; start from typical daily values, with the sample
+        // variance as the long-run variance
+        let negative_log_likelihood = |point: &[f64; 3]| {
+            let [log_omega, alpha, beta] = *point;
+            if alpha < 0.0 || beta < 0.0 || alpha + beta >= 1.0 {
+                return f64::INFINITY;
+            }
+            -log_likelihood(returns, log_omega.exp(), alpha, beta, sample_variance).0
+        };
+        let (alpha, beta) = (0.1, 0.8);
+        let initial = [(sample_variance * (1.0 - alpha - beta)).ln(), alpha, beta];
+        let [log_omega, alpha, beta] =
+            nelder_mead(negative_log_likelihood, initial, [0.5, 0.05, 0.05]);
+
+        let omega = log_omega.exp();
+        let (log_likelihood, next_variance) =
+            log_likelihood(returns, omega, alpha, beta, sample_variance);
+
+        Some(Garch {
+            omega,
+            alpha,
+            beta,
+            next_variance,
+            log_likelihood,
+        })
+    }
+
+    /// Get the constant term of the variance, `omega`.
+    pub fn omega(&self) -> f64 {
+        self.omega
+    }
+
+    /// Get the reaction of the variance to the last squared return,
+    /// `alpha`.
+    pub fn alpha(&self) -> f64 {
+        self.alpha
+    }
+
+    /// Get the persistence of the last variance, `beta`.
+    pub fn beta(&self) -> f64 {
+        self.beta
+    }
+
+    /// Get the rate at which shocks to the variance persist each period,
+    /// `alpha + beta`. Values close to `1` revert slowly to the long-run
+    /// variance.
+    pub fn persistence(&self) -> f64 {
+        self.alpha + self.beta
+    }
+
+    /// Get the variance of one period the model reverts to,
+    /// `omega / (1 - alpha - beta)`.
+    pub fn long_run_variance(&self) -> f64 {
+        self.omega / (1.0 - self.persistence())
+    }
+
+    /// Get the maximized Gaussian log-likelihood of the returns, without the
+    /// constant term.
+    pub fn log_likelihood(&self) -> f64 {
+        self.log_likelihood
+    }
+
+    /// Get the forecast variance of the return `periods_ahead` periods after
+    /// the last one, `1` being the next period.
+    pub fn forecast_variance(&self, periods_ahead: usize) -> f64 {
+        let long_run_variance = self.long_run_variance();
+        let decay = self.persistence().powi(periods_ahead.max(1) as i32 - 1);
+
+        long_run_variance + decay * (self.next_variance - long_run_variance)
+    }
+
+    /// Get the forecast annualized volatility over the next `periods`, i.e.
+    /// the volatility to price an option maturing after `periods` with.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `periods`: the number of periods until the option's maturity, e.g.
+    ///      the number of trading days for daily returns.
+    /// - `annualization_factor`: the number of periods per year, e.g. `252`
+    ///      for daily returns on trading days.
+    pub fn forecast_volatility(&self, periods: usize, annualization_factor: f64) -> f64 {
+        let periods = periods.max(1);
+        let total_variance: f64 = (1..=periods).map(|h| self.forecast_variance(h)).sum();
+
+        (total_variance / periods as f64 * annualization_factor).sqrt()
+    }
+}
+
+/// The Gaussian log-likelihood of `returns`, without the constant term, and
+/// the variance of the period after the last one. The first variance is the
+/// `initial_variance`.
+fn log_likelihood(
+    returns: &[f64],
+    omega: f64,
+    alpha: f64,
+    beta: f64,
+    initial_variance: f64,
+) -> (f64, f64) {
+    returns
+        .iter()
+        .fold((0.0, initial_variance), |(log_likelihood, variance), r| {
+            (
+                log_likelihood - 0.5 * (variance.ln() + r * r / variance),
+                omega + alpha * r * r + beta * variance,
+            )
+        })
+}
+
+/// Minimize `f` with the Nelder-Mead simplex method, from a simplex around
+/// `initial` spanning `steps` along each coordinate.
+fn nelder_mead<F: Fn(&[f64; 3]) -> f64>(f: F, initial: [f64; 3], steps: [f64; 3]) -> [f64; 3] {
+    let mut simplex: Vec<([f64; 3], f64)> = (0..4)
+        .map(|i| {
+            let mut point = initial;
+            if i > 0 {
+                point[i - 1] += steps[i - 1];
+            }
+            (point, f(&point))
+        })
+        .collect();
+    let combine = |a: &[f64; 3], b: &[f64; 3], weight: f64| {
+        let mut point = *a;
+        for (x, y) in point.iter_mut().zip(b) {
+            *x += weight * (y - *x);
+        }
+        point
+    };
+
+    for _ in 0..MAX_ITERATIONS {
+        simplex.sort_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal));
+        let (best, worst) = (simplex[0].1, simplex[3].1);
+        if (worst - best).abs() < LIKELIHOOD_TOLERANCE {
+            break;
+        }
+
+        let mut centroid = [0.0; 3];
+        for (point, _) in &simplex[..3] {
+            for (c, x) in centroid.iter_mut().zip(point) {
+                *c += x / 3.0;
+            }
+        }
+
+        // reflect the worst point through the centroid of the others, then
+        // expand, contract or shrink depending on how good the reflection is
+        let worst_point = simplex[3].0;
+        let reflected = combine(&centroid, &worst_point, -1.0);
+        let reflected_value = f(&reflected);
+        if reflected_value < best {
+            let expanded = combine(&centroid, &worst_point, -2.0);
+            let expanded_value = f(&expanded);
+            simplex[3] = if expanded_value < reflected_value {
+                (expanded, expanded_value)
+            } else {
+                (reflected, reflected_value)
+            };
+        } else if reflected_value < simplex[2].1 {
+            simplex[3] = (reflected, reflected_value);
+        } else {
+            let contracted = combine(&centroid, &worst_point, 0.5);
+            let contracted_value = f(&contracted);
+            if contracted_value < worst {
+                simplex[3] = (contracted, contracted_value);
+            } else {
+                let best_point = simplex[0].0;
+                for vertex in simplex.iter_mut().skip(1) {
+                    let shrunk = combine(&best_point, &vertex.0, 0.5);
+                    *vertex = (shrunk, f(&shrunk));
+                }
+            }
+        }
+    }
+
+    simplex
+        .iter()
+        .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(std::cmp::Ordering::Equal))
+        .map(|(point, _)| *point)
+        .unwrap_or(initial)
+}
+
+#[cfg(test)]
+mod garch_tests {
+    use crate::montecarlo::random::Random;
+    use crate::volatility::garch::Garch;
+
+    /// Daily returns simulated from a GARCH(1,1) model.
+    fn simulate(omega: f64, alpha: f64, beta: f64, count: usize) -> Vec<f64> {
+        let mut random = Random::new(7);
+        let mut variance = omega / (1.0 - alpha - beta);
+
+        (0..count)
+            .map(|_| {
+                let r = variance.sqrt() * random.normal();
+                variance = omega + alpha * r * r + beta * variance;
+                r
+            })
+            .collect()
+    }
+
+    #[test]
+    fn recovers_simulated_parameters() {
+        let returns = simulate(4e-6, 0.08, 0.9, 5000);
+        let model = Garch::fit(&returns).unwrap();
+
+        approx::assert_abs_diff_eq!(model.alpha(), 0.08, epsilon = 0.03);
+        approx::assert_abs_diff_eq!(model.beta(), 0.9, epsilon = 0.04);
+        approx::assert_abs_diff_eq!(model.persistence(), 0.98, epsilon = 0.015);
+
+        // the fit beats the parameters the returns were simulated from
+        let simulated = super::log_likelihood(&returns, 4e-6, 0.08, 0.9, 2e-4).0;
+        assert!(model.log_likelihood() >= simulated - 1e-6);
+    }
+
+    #[test]
+    fn forecasts_revert_to_the_long_run_variance() {
+        let model = Garch {
+            omega: 4e-6,
+            alpha: 0.08,
+            beta: 0.9,
+            next_variance: 8e-4,
+            log_likelihood: 0.0,
+        };
+
+        approx::assert_abs_diff_eq!(model.long_run_variance(), 2e-4, epsilon = 1e-15);
+        approx::assert_abs_diff_eq!(model.forecast_variance(1), 8e-4, epsilon = 1e-15);
+        approx::assert_abs_diff_eq!(
+            model.forecast_variance(2),
+            2e-4 + 0.98 * 6e-4,
+            epsilon = 1e-15
+        );
+        approx::assert_abs_diff_eq!(model.forecast_variance(5000), 2e-4, epsilon = 1e-12);
+
+        // the term volatility falls from the current level towards the long run
+        let short = model.forecast_volatility(1, 252.0);
+        let long = model.forecast_volatility(250, 252.0);
+        approx::assert_abs_diff_eq!(short, (8e-4f64 * 252.0).sqrt(), epsilon = 1e-12);
+        assert!(long < short && long > (2e-4f64 * 252.0).sqrt());
+    }
+
+    #[test]
+    fn needs_enough_returns() {
+        assert!(Garch::fit(&[0.01, -0.02, 0.005]).is_none());
+        assert!(Garch::fit(&[0.0; 20]).is_none());
+    }
+
+    #[test]
+    fn rejects_non_finite_returns() {
+        let mut returns = simulate(4e-6, 0.08, 0.9, 100);
+        assert!(Garch::fit(&returns).is_some());
+
+        for &invalid in [f64::NAN, f64::INFINITY, f64::NEG_INFINITY].iter() {
+            returns[50] = invalid;
+            assert!(Garch::fit(&returns).is_none());
+        }
+    }
+}
@@ -0,0 +1,6 @@
+//! Volatility and variance analytics.
+
+pub mod conversion;
+pub mod garch;
+pub mod realized;
+pub mod variance_swap;
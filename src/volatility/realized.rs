@@ -0,0 +1,437 @@
+//! Historical (realized) volatility of a price series.
+//!
+//! Realized volatility is the usual starting point for the `volatility`
+//! input of `BSOption` when no option prices are available to imply it from.
+//! The classic estimate uses the log returns between consecutive closing
+//! prices, with the sample standard deviation. Range-based estimators also
+//! use the open, high and low prices of each period, which makes them more
+//! efficient, and lets Yang-Zhang account for overnight gaps. Estimates are
+//! annualized by the square root of the number of periods per year.
+
+use wasm_bindgen::prelude::*;
+
+/// An enumeration of the supported realized volatility estimators for
+/// open-high-low-close (OHLC) prices.
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub enum VolatilityEstimator {
+    /// The sample deviation of the log returns between closes. This is the
+    /// default.
+    CloseToClose,
+    /// Parkinson (1980), from the high-low range of each period. Assumes no
+    /// drift and no opening gaps, so it understates the volatility of gappy
+    /// assets.
+    Parkinson,
+    /// Garman and Klass (1980), from the high-low range and the open-to-close
+    /// return of each period. Assumes no drift and no opening gaps.
+    GarmanKlass,
+    /// Yang and Zhang (2000), combining the overnight returns, the
+    /// open-to-close returns and the Rogers-Satchell range estimator.
+    /// Handles both drift and opening gaps.
+    YangZhang,
+}
+
+/// A realized volatility estimator.
+///
+/// ```rust
+/// use opcalc::volatility::realized::RealizedVolatility;
+///
+/// let closes = [100.0, 101.2, 100.7, 102.3, 101.9, 103.0, 102.1];
+///
+/// // over the last 5 daily returns
+/// let estimator = RealizedVolatility::new().with_window(5);
+/// let volatility = estimator.close_to_close(&closes).unwrap();
+/// ```
+#[wasm_bindgen]
+#[derive(Copy, Clone, Debug, PartialEq)]
+pub struct RealizedVolatility {
+    window: usize,
+    annualization_factor: f64,
+    estimator: VolatilityEstimator,
+}
+
+impl Default for RealizedVolatility {
+    fn default() -> RealizedVolatility {
+        RealizedVolatility {
+            window: 0,
+            annualization_factor: 252.0,
+            estimator: VolatilityEstimator::CloseToClose,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl RealizedVolatility {
+    /// Create a close-to-close `RealizedVolatility` estimator over whole
+    /// series of daily prices, with 252 trading days per year.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> RealizedVolatility {
+        Default::default()
+    }
+
+    /// Set the number of returns each estimate uses, e.g. `21` for about a
+    /// month of daily prices. `0` uses the whole series.
+    pub fn with_window(self, window: usize) -> RealizedVolatility {
+        RealizedVolatility { window, ..self }
+    }
+
+    /// Set the number of periods per year between the prices, e.g. `252`
+    /// for daily prices on trading days, `365` for calendar days or `52` for
+    /// weekly prices.
+    pub fn with_annualization_factor(self, annualization_factor: f64) -> RealizedVolatility {
+        RealizedVolatility {
+            annualization_factor,
+            ..self
+        }
+    }
+
+    /// Set the estimator used by `ohlc()` and `rolling_ohlc()`.
+    pub fn with_estimator(self, estimator: VolatilityEstimator) -> RealizedVolatility {
+        RealizedVolatility { estimator, ..self }
+    }
+
+    /// Get the number of returns each estimate uses, `0` for the whole
+    /// series.
+    pub fn window(&self) -> usize {
+        self.window
+    }
+
+    /// Get the number of periods per year between the prices.
+    pub fn annualization_factor(&self) -> f64 {
+        self.annualization_factor
+    }
+
+    /// Get the estimator used by `ohlc()` and `rolling_ohlc()`.
+    pub fn estimator(&self) -> VolatilityEstimator {
+        self.estimator
+    }
+
+    /// Get the annualized close-to-close volatility over the last window of
+    /// `closes`, ordered from oldest to newest.
+    ///
+    /// Returns `None` if there are fewer than two returns to estimate from,
+    /// or fewer than the window.
+    pub fn close_to_close(&self, closes: &[f64]) -> Option<f64> {
+        crate::utils::set_panic_hook();
+        trace_scope!("RealizedVolatility::close_to_close");
+
+        let returns = log_returns(closes);
+        let window = self.window_length(returns.len())?;
+
+        Some(self.annualize(sample_variance(&returns[returns.len() - window..])))
+    }
+
+    /// Get the annualized close-to-close volatility over each window of
+    /// `closes`, ordered from oldest to newest. The estimates are ordered
+    /// like the windows' last prices.
+    ///
+    /// Returns an empty series if there are fewer than two returns to
+    /// estimate from, or fewer than the window.
+    pub fn rolling_close_to_close(&self, closes: &[f64]) -> Vec<f64> {
+        crate::utils::set_panic_hook();
+        trace_scope!("RealizedVolatility::rolling_close_to_close");
+
+        let returns = log_returns(closes);
+        match self.window_length(returns.len()) {
+            Some(window) => returns
+                .windows(window)
+                .map(|window| self.annualize(sample_variance(window)))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+
+    /// Get the annualized volatility over the last window of open-high-low-
+    /// close prices, ordered from oldest to newest, with the selected
+    /// estimator. The first period only provides the close the next one
+    /// opens from, so that all estimators use the same periods as
+    /// `close_to_close()`.
+    ///
+    /// Returns `None` if there are fewer than two periods after the first to
+    /// estimate from, or fewer than the window.
+    ///
+    /// **Arguments:**
+    ///
+    /// - `opens`, `highs`, `lows`, `closes`: the prices of each period, in
+    ///      the same order.
+    pub fn ohlc(&self, opens: &[f64], highs: &[f64], lows: &[f64], closes: &[f64]) -> Option<f64> {
+        crate::utils::set_panic_hook();
+        trace_scope!("RealizedVolatility::ohlc");
+
+        let periods = periods(opens, highs, lows, closes);
+        let window = self.window_length(periods.len())?;
+
+        Some(self.annualize(self.variance(&periods[periods.len() - window..])))
+    }
+
+    /// Get the annualized volatility over each window of open-high-low-close
+    /// prices with the selected estimator. See `ohlc()` and
+    /// `rolling_close_to_close()`.
+    pub fn rolling_ohlc(
+        &self,
+        opens: &[f64],
+        highs: &[f64],
+        lows: &[f64],
+        closes: &[f64],
+    ) -> Vec<f64> {
+        crate::utils::set_panic_hook();
+        trace_scope!("RealizedVolatility::rolling_ohlc");
+
+        let periods = periods(opens, highs, lows, closes);
+        match self.window_length(periods.len()) {
+            Some(window) => periods
+                .windows(window)
+                .map(|window| self.annualize(self.variance(window)))
+                .collect(),
+            None => Vec::new(),
+        }
+    }
+}
+
+impl RealizedVolatility {
+    /// The variance of one period estimated from `periods`.
+    fn variance(&self, periods: &[Period]) -> f64 {
+        let count = periods.len() as f64;
+        let mean = |f: fn(&Period) -> f64| periods.iter().map(f).sum::<f64>() / count;
+
+        match self.estimator {
+            VolatilityEstimator::CloseToClose => {
+                let returns: Vec<f64> = periods.iter().map(|p| p.close_to_close).collect();
+                sample_variance(&returns)
+            }
+            VolatilityEstimator::Parkinson => mean(|p| p.high_low.powi(2)) / (4.0 * 2f64.ln()),
+            VolatilityEstimator::GarmanKlass => mean(|p| {
+                0.5 * p.high_low.powi(2) - (2.0 * 2f64.ln() - 1.0) * p.open_to_close.powi(2)
+            }),
+            VolatilityEstimator::YangZhang => {
+                let overnight: Vec<f64> = periods.iter().map(|p| p.overnight).collect();
+                let open_to_close: Vec<f64> = periods.iter().map(|p| p.open_to_close).collect();
+                // the weight minimizing the estimator's variance
+                let k = 0.34 / (1.34 + (count + 1.0) / (count - 1.0));
+
+                sample_variance(&overnight)
+                    + k * sample_variance(&open_to_close)
+                    + (1.0 - k) * mean(|p| p.rogers_satchell)
+            }
+        }
+    }
+
+    /// The number of returns in each window, out of `returns`, if there are
+    /// enough of them.
+    fn window_length(&self, returns: usize) -> Option<usize> {
+        let window = if self.window == 0 {
+            returns
+        } else {
+            self.window
+        };
+
+        if window < 2 || window > returns {
+            None
+        } else {
+            Some(window)
+        }
+    }
+
+    /// The annualized volatility for the variance of one period.
+    fn annualize(&self, variance: f64) -> f64 {
+        (variance * self.annualization_factor).sqrt()
+    }
+}
+
+/// The log returns and ranges of one period of open-high-low-close prices.
+struct Period {
+    /// From the previous close to the open.
+    overnight: f64,
+    open_to_close: f64,
+    close_to_close: f64,
+    /// `ln(high / low)`.
+    high_low: f64,
+    /// `ln(high / close) * ln(high / open) + ln(low / close) * ln(low / open)`.
+    rogers_satchell: f64,
+}
+
+/// The periods after the first, which only provides a previous close.
+fn periods(opens: &[f64], highs: &[f64], lows: &[f64], closes: &[f64]) -> Vec<Period> {
+    let bars: Vec<(f64, f64, f64, f64)> = opens
+        .iter()
+        .zip(highs)
+        .zip(lows)
+        .zip(closes)
+        .map(|(((&open, &high), &low), &close)| (open, high, low, close))
+        .collect();
+
+    bars.windows(2)
+        .map(|pair| {
+            let (_, _, _, previous_close) = pair[0];
+            let (open, high, low, close) = pair[1];
+
+            Period {
+                overnight: (open / previous_close).ln(),
+                open_to_close: (close / open).ln(),
+                close_to_close: (close / previous_close).ln(),
+                high_low: (high / low).ln(),
+                rogers_satchell: (high / close).ln() * (high / open).ln()
+                    + (low / close).ln() * (low / open).ln(),
+            }
+        })
+        .collect()
+}
+
+/// The log returns between consecutive prices.
+fn log_returns(prices: &[f64]) -> Vec<f64> {
+    prices
+        .windows(2)
+        .map(|pair| (pair[1] / pair[0]).ln())
+        .collect()
+}
+
+/// The unbiased sample variance of at least two values.
+fn sample_variance(values: &[f64]) -> f64 {
+    let mean = values.iter().sum::<f64>() / values.len() as f64;
+
+    values.iter().map(|v| (v - mean).powi(2)).sum::<f64>() / (values.len() - 1) as f64
+}
+
+#[cfg(test)]
+mod realized_tests {
+    use crate::montecarlo::random::Random;
+    use crate::volatility::realized::{RealizedVolatility, VolatilityEstimator};
+
+    /// Daily bars of a random walk monitored 100 times a day, with opening
+    /// gaps. The gaps and the days have a deviation of 1% each, so the
+    /// annualized volatility is `sqrt(2 * 0.01^2 * 252)`, about 22.4%.
+    fn gappy_bars() -> (Vec<f64>, Vec<f64>, Vec<f64>, Vec<f64>) {
+        const STEPS_PER_DAY: usize = 100;
+
+        let mut random = Random::new(42);
+        let (mut opens, mut highs, mut lows, mut closes) = (vec![], vec![], vec![], vec![]);
+        let mut price: f64 = 100.0;
+        for _ in 0..500 {
+            price *= (0.01 * random.normal()).exp();
+            let open = price;
+            let (mut high, mut low) = (price, price);
+            for _ in 0..STEPS_PER_DAY {
+                price *= (0.01 / (STEPS_PER_DAY as f64).sqrt() * random.normal()).exp();
+                high = high.max(price);
+                low = low.min(price);
+            }
+
+            opens.push(open);
+            highs.push(high);
+            lows.push(low);
+            closes.push(price);
+        }
+
+        (opens, highs, lows, closes)
+    }
+
+    #[test]
+    fn annualizes_the_sample_deviation_of_log_returns() {
+        // log returns of alternating sign around a zero mean
+        let closes = [100.0, 101.0, 100.0, 101.0, 100.0];
+        let log_return = 1.01f64.ln();
+        let variance = 4.0 * log_return.powi(2) / 3.0;
+
+        approx::assert_abs_diff_eq!(
+            RealizedVolatility::new().close_to_close(&closes).unwrap(),
+            (variance * 252.0).sqrt(),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            RealizedVolatility::new()
+                .with_annualization_factor(365.0)
+                .close_to_close(&closes)
+                .unwrap(),
+            (variance * 365.0).sqrt(),
+            epsilon = 1e-12
+        );
+    }
+
+    #[test]
+    fn estimates_over_windows() {
+        // quiet, then volatile
+        let closes = [100.0, 100.1, 100.0, 100.1, 110.0, 100.0, 110.0];
+        let estimator = RealizedVolatility::new().with_window(3);
+
+        let rolling = estimator.rolling_close_to_close(&closes);
+        assert_eq!(rolling.len(), 4);
+        assert!(rolling[0] < rolling[3]);
+        approx::assert_abs_diff_eq!(
+            estimator.close_to_close(&closes).unwrap(),
+            rolling[3],
+            epsilon = 1e-12
+        );
+
+        assert!(estimator.close_to_close(&closes[..3]).is_none());
+        assert!(estimator.rolling_close_to_close(&closes[..3]).is_empty());
+    }
+
+    #[test]
+    fn defaults_to_close_to_close() {
+        let (opens, highs, lows, closes) = gappy_bars();
+        let estimator = RealizedVolatility::new().with_window(40);
+
+        approx::assert_abs_diff_eq!(
+            estimator.ohlc(&opens, &highs, &lows, &closes).unwrap(),
+            estimator.close_to_close(&closes).unwrap(),
+            epsilon = 1e-12
+        );
+        assert_eq!(
+            estimator.rolling_ohlc(&opens, &highs, &lows, &closes).len(),
+            estimator.rolling_close_to_close(&closes).len()
+        );
+    }
+
+    #[test]
+    fn accounts_for_opening_gaps() {
+        let (opens, highs, lows, closes) = gappy_bars();
+        let volatility = |estimator| {
+            RealizedVolatility::new()
+                .with_estimator(estimator)
+                .ohlc(&opens, &highs, &lows, &closes)
+                .unwrap()
+        };
+        let expected = (2.0 * 0.01f64.powi(2) * 252.0).sqrt();
+
+        approx::assert_abs_diff_eq!(
+            volatility(VolatilityEstimator::CloseToClose),
+            expected,
+            epsilon = 0.1 * expected
+        );
+        approx::assert_abs_diff_eq!(
+            volatility(VolatilityEstimator::YangZhang),
+            expected,
+            epsilon = 0.1 * expected
+        );
+        // the intraday ranges miss the gaps
+        assert!(volatility(VolatilityEstimator::Parkinson) < 0.8 * expected);
+        assert!(volatility(VolatilityEstimator::GarmanKlass) < 0.8 * expected);
+    }
+
+    #[test]
+    fn estimates_the_range_of_a_single_period() {
+        // no drift, no gaps, and the day's range equal to its move
+        let opens = [100.0, 100.0, 101.0];
+        let highs = [100.0, 101.0, 102.01];
+        let lows = [100.0, 100.0, 101.0];
+        let closes = [100.0, 101.0, 102.01];
+        let range = 1.01f64.ln();
+
+        approx::assert_abs_diff_eq!(
+            RealizedVolatility::new()
+                .with_estimator(VolatilityEstimator::Parkinson)
+                .ohlc(&opens, &highs, &lows, &closes)
+                .unwrap(),
+            (range.powi(2) / (4.0 * 2f64.ln()) * 252.0).sqrt(),
+            epsilon = 1e-12
+        );
+        approx::assert_abs_diff_eq!(
+            RealizedVolatility::new()
+                .with_estimator(VolatilityEstimator::GarmanKlass)
+                .ohlc(&opens, &highs, &lows, &closes)
+                .unwrap(),
+            ((0.5 - (2.0 * 2f64.ln() - 1.0)) * range.powi(2) * 252.0).sqrt(),
+            epsilon = 1e-12
+        );
+    }
+}
@@ -0,0 +1,142 @@
+//! Model-free variance swap pricing.
+//!
+//! The fair strike of a variance swap is replicated by a static portfolio of
+//! out-of-the-money options weighted by `1 / K^2` (the log contract), as
+//! described in Demeterfi, Derman, Kamal & Zou (1999), "More than you ever
+//! wanted to know about volatility swaps". The discretization follows the
+//! one used by the CBOE for the VIX.
+
+use wasm_bindgen::prelude::*;
+
+/// Get the fair (annualized) variance strike implied by an expiry's option
+/// strip.
+///
+/// Returns `None` if the strip is not usable: fewer than two strikes,
+/// mismatched array lengths, unsorted strikes, or a forward below the
+/// lowest strike.
+///
+/// # Arguments
+///
+/// - `strikes`: the strip's strikes, in ascending order.
+///
+/// - `call_values`, `put_values`: call and put prices for each strike. Only
+///      the out-of-the-money side is used; in-the-money prices may be `NaN`.
+///
+/// - `forward`: the forward price of the underlying for this expiry.
+///
+/// - `interest`: the prevailing interest rate, in decimal form (e.g. `0.006`
+///      for '0.6%'). Same convention as `BSOption`.
+///
+/// - `time_to_maturity`: the expiry's time to maturity as a fraction of 365
+///      days. See `BSOption::time_to_maturity()`.
+///
+/// # Examples
+///
+/// ```js
+/// const variance = opcalc.variance_swap_strike(strikes, calls, puts, 100.2, 0.005, 0.25);
+/// const vol = Math.sqrt(variance);
+/// ```
+#[wasm_bindgen]
+pub fn variance_swap_strike(
+    strikes: &[f64],
+    call_values: &[f64],
+    put_values: &[f64],
+    forward: f64,
+    interest: f64,
+    time_to_maturity: f64,
+) -> Option<f64> {
+    crate::utils::set_panic_hook();
+
+    let n = strikes.len();
+    if n < 2 || call_values.len() != n || put_values.len() != n {
+        return None;
+    }
+    if strikes.windows(2).any(|pair| pair[0] >= pair[1]) {
+        return None;
+    }
+
+    // k0 is the first strike at or below the forward
+    let k0_index = strikes.iter().rposition(|&strike| strike <= forward)?;
+    let k0 = strikes[k0_index];
+
+    let growth = (interest.ln_1p() * time_to_maturity).exp();
+
+    let contributions: f64 = (0..n)
+        .map(|i| {
+            let out_of_the_money_value = match i {
+                _ if i < k0_index => put_values[i],
+                _ if i > k0_index => call_values[i],
+                _ => (put_values[i] + call_values[i]) / 2.0,
+            };
+
+            let strike_interval = match i {
+                0 => strikes[1] - strikes[0],
+                _ if i == n - 1 => strikes[n - 1] - strikes[n - 2],
+                _ => (strikes[i + 1] - strikes[i - 1]) / 2.0,
+            };
+
+            strike_interval / strikes[i].powi(2) * growth * out_of_the_money_value
+        })
+        .sum();
+
+    let variance =
+        2.0 / time_to_maturity * contributions - (forward / k0 - 1.0).powi(2) / time_to_maturity;
+
+    Some(variance)
+}
+
+#[cfg(test)]
+mod variance_swap_tests {
+    use crate::option::BSOption;
+    use crate::volatility::variance_swap::variance_swap_strike;
+
+    #[test]
+    fn recovers_flat_volatility() {
+        let time_curr = 1606780800; // 2020/12/01 00:00:00
+        let time_maturity = 1622505600; // 2021/06/01 00:00:00
+        let volatility = 0.23;
+
+        let strikes: Vec<f64> = (20..=400).map(|strike| strike as f64).collect();
+        let options: Vec<BSOption> = strikes
+            .iter()
+            .map(|&strike| {
+                BSOption::new(
+                    time_curr,
+                    time_maturity,
+                    100.0,
+                    strike,
+                    0.005,
+                    volatility,
+                    0.0,
+                )
+            })
+            .collect();
+
+        let calls: Vec<f64> = options.iter().map(|option| option.call_value()).collect();
+        let puts: Vec<f64> = options.iter().map(|option| option.put_value()).collect();
+        let time_to_maturity = options[0].time_to_maturity();
+        let forward = 100.0 * (0.005_f64.ln_1p() * time_to_maturity).exp();
+
+        let variance =
+            variance_swap_strike(&strikes, &calls, &puts, forward, 0.005, time_to_maturity)
+                .unwrap();
+
+        approx::assert_abs_diff_eq!(variance, volatility.powi(2), epsilon = 1e-4);
+    }
+
+    #[test]
+    fn rejects_malformed_strips() {
+        assert_eq!(
+            variance_swap_strike(&[100.0], &[1.0], &[1.0], 100.0, 0.0, 1.0),
+            None
+        );
+        assert_eq!(
+            variance_swap_strike(&[110.0, 100.0], &[1.0, 2.0], &[2.0, 1.0], 105.0, 0.0, 1.0),
+            None
+        );
+        assert_eq!(
+            variance_swap_strike(&[110.0, 120.0], &[1.0, 2.0], &[2.0, 1.0], 105.0, 0.0, 1.0),
+            None
+        );
+    }
+}